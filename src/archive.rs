@@ -0,0 +1,192 @@
+// reqif-rs: Help library to write reqif files implemented in Rust.
+// Copyright (C) <2024>  INVAP S.E.
+//
+// This file is part of reqif-rs.
+//
+// reqif-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+use crate::req_if::ReqIf;
+use anyhow::{bail, Result};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufReader, Read, Seek, Write};
+use zip::write::FileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+/// A `.reqifz` bundle: one or more ReqIF documents zipped together with the
+/// binary files (images, OLE objects...) their XHTML content refers to.
+///
+/// Entries are keyed by their path inside the archive.
+#[derive(Debug, PartialEq, Default)]
+pub struct ReqIfArchive {
+    documents: BTreeMap<String, ReqIf>,
+    attachments: BTreeMap<String, Vec<u8>>,
+}
+
+fn is_reqif_entry(name: &str) -> bool {
+    name.to_ascii_lowercase().ends_with(".reqif")
+}
+
+impl ReqIfArchive {
+    pub fn new() -> Self {
+        ReqIfArchive {
+            documents: BTreeMap::new(),
+            attachments: BTreeMap::new(),
+        }
+    }
+
+    /// Adds `document` as the entry `name`, which must end with `.reqif`.
+    pub fn add_document(&mut self, name: String, document: ReqIf) -> Result<()> {
+        if !is_reqif_entry(&name) {
+            bail!("ReqIF entry name must end with .reqif: {}", name);
+        }
+        if self.documents.contains_key(&name) {
+            bail!("Duplicated archive entry: {}", name);
+        }
+        self.documents.insert(name, document);
+        Ok(())
+    }
+
+    /// Adds a binary attachment stored at `path` inside the archive.
+    pub fn add_attachment(&mut self, path: String, data: Vec<u8>) -> Result<()> {
+        if is_reqif_entry(&path) {
+            bail!("Attachment can not be a ReqIF entry: {}", path);
+        }
+        if self.attachments.contains_key(&path) {
+            bail!("Duplicated archive entry: {}", path);
+        }
+        self.attachments.insert(path, data);
+        Ok(())
+    }
+
+    pub fn get_documents(&self) -> &BTreeMap<String, ReqIf> {
+        &self.documents
+    }
+
+    pub fn get_attachments(&self) -> &BTreeMap<String, Vec<u8>> {
+        &self.attachments
+    }
+
+    /// Splits the archive into its documents and attachments.
+    pub fn into_parts(self) -> (BTreeMap<String, ReqIf>, BTreeMap<String, Vec<u8>>) {
+        (self.documents, self.attachments)
+    }
+
+    pub fn write<W: Write + Seek>(&self, writer: W) -> Result<()> {
+        let mut zip = ZipWriter::new(writer);
+        let options = FileOptions::default().compression_method(CompressionMethod::Deflated);
+
+        for (name, document) in &self.documents {
+            zip.start_file(name.as_str(), options)?;
+            document.write(&mut zip)?;
+        }
+        for (path, data) in &self.attachments {
+            zip.start_file(path.as_str(), options)?;
+            zip.write_all(data)?;
+        }
+        zip.finish()?;
+        Ok(())
+    }
+
+    pub fn write_to(&self, filename: &str) -> Result<()> {
+        let file = File::create(filename)?;
+        self.write(file)
+    }
+
+    /// Unpacks a `.reqifz` archive. Every `.reqif` entry is parsed into a
+    /// [`ReqIf`], any other file is kept as an attachment.
+    pub fn from_reader<R: Read + Seek>(reader: R) -> Result<Self> {
+        let mut zip = ZipArchive::new(reader)?;
+        let mut archive = ReqIfArchive::new();
+
+        for index in 0..zip.len() {
+            let mut entry = zip.by_index(index)?;
+            if entry.is_dir() {
+                continue;
+            }
+            let name = entry.name().to_string();
+            if is_reqif_entry(&name) {
+                let document = match ReqIf::from_reader(&mut entry) {
+                    Ok(document) => document,
+                    Err(e) => bail!("Unable to read {}: {}", name, e),
+                };
+                archive.add_document(name, document)?;
+            } else {
+                let mut data = Vec::new();
+                entry.read_to_end(&mut data)?;
+                archive.add_attachment(name, data)?;
+            }
+        }
+        Ok(archive)
+    }
+
+    pub fn read_from(filename: &str) -> Result<Self> {
+        let file = File::open(filename)?;
+        Self::from_reader(BufReader::new(file))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::ReqIfArchive;
+    use crate::fixture::document;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_archive_round_trip() {
+        let mut archive = ReqIfArchive::new();
+        archive
+            .add_document("system.reqif".to_string(), document("System"))
+            .expect("error");
+        archive
+            .add_document("software.reqif".to_string(), document("Software"))
+            .expect("error");
+        archive
+            .add_attachment(
+                "files/diagram.png".to_string(),
+                vec![0x89, 0x50, 0x4e, 0x47],
+            )
+            .expect("error");
+
+        let mut buffer = Cursor::new(Vec::new());
+        archive.write(&mut buffer).expect("error");
+        buffer.set_position(0);
+
+        let read = ReqIfArchive::from_reader(buffer).expect("error");
+        assert_eq!(read, archive);
+
+        let (documents, attachments) = read.into_parts();
+        assert_eq!(documents.len(), 2);
+        assert_eq!(
+            attachments["files/diagram.png"],
+            vec![0x89, 0x50, 0x4e, 0x47]
+        );
+    }
+
+    #[test]
+    fn test_archive_rejects_bad_entries() {
+        let mut archive = ReqIfArchive::new();
+        assert!(archive
+            .add_document("system.xml".to_string(), document("System"))
+            .is_err());
+        assert!(archive
+            .add_attachment("other.reqif".to_string(), vec![])
+            .is_err());
+        archive
+            .add_attachment("image.png".to_string(), vec![])
+            .expect("error");
+        assert!(archive
+            .add_attachment("image.png".to_string(), vec![])
+            .is_err());
+    }
+}
@@ -0,0 +1,220 @@
+// reqif-rs: Help library to write reqif files implemented in Rust.
+// Copyright (C) <2024>  INVAP S.E.
+//
+// This file is part of reqif-rs.
+//
+// reqif-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//! Timestamps of a ReqIF document and the clock used to generate them.
+use chrono::{
+    DateTime, FixedOffset, Local, NaiveDateTime, ParseError, SecondsFormat, SubsecRound, TimeZone,
+};
+use std::cell::RefCell;
+use std::fmt;
+use std::io::{Read, Write};
+use std::rc::Rc;
+use std::str::FromStr;
+use xml::reader::XmlEvent as ReaderEvent;
+use xml::writer::XmlEvent as WriterEvent;
+use yaserde::de::Deserializer;
+use yaserde::ser::Serializer;
+use yaserde::{YaDeserialize, YaSerialize};
+
+/// An xsd:dateTime value, such as LAST-CHANGE or CREATION-TIME.
+///
+/// Times given as [`DateTime`] are kept with millisecond precision, parsed
+/// values are kept as written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Timestamp(DateTime<FixedOffset>);
+
+impl Timestamp {
+    pub fn new(datetime: DateTime<FixedOffset>) -> Self {
+        Timestamp(datetime.trunc_subsecs(3))
+    }
+
+    pub fn datetime(&self) -> DateTime<FixedOffset> {
+        self.0
+    }
+}
+
+impl<Tz: TimeZone> From<DateTime<Tz>> for Timestamp {
+    fn from(datetime: DateTime<Tz>) -> Self {
+        Timestamp::new(datetime.fixed_offset())
+    }
+}
+
+impl From<Timestamp> for DateTime<FixedOffset> {
+    fn from(timestamp: Timestamp) -> Self {
+        timestamp.0
+    }
+}
+
+impl fmt::Display for Timestamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0.to_rfc3339_opts(SecondsFormat::AutoSi, false))
+    }
+}
+
+impl FromStr for Timestamp {
+    type Err = ParseError;
+
+    /// Parses an xsd:dateTime. A value without timezone is taken as UTC.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match DateTime::parse_from_rfc3339(s) {
+            Ok(datetime) => Ok(Timestamp(datetime)),
+            Err(e) => match NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S%.f") {
+                Ok(datetime) => Ok(Timestamp(datetime.and_utc().fixed_offset())),
+                Err(_) => Err(e),
+            },
+        }
+    }
+}
+
+impl YaSerialize for Timestamp {
+    fn serialize<W: Write>(&self, writer: &mut Serializer<W>) -> Result<(), String> {
+        let text = self.to_string();
+        // Attribute values are serialized without enclosing element.
+        if writer.skip_start_end() {
+            return writer
+                .write(WriterEvent::characters(&text))
+                .map_err(|e| e.to_string());
+        }
+        let name = writer
+            .get_start_event_name()
+            .unwrap_or_else(|| "Timestamp".to_string());
+        writer
+            .write(WriterEvent::start_element(name.as_str()))
+            .map_err(|e| e.to_string())?;
+        writer
+            .write(WriterEvent::characters(&text))
+            .map_err(|e| e.to_string())?;
+        writer
+            .write(WriterEvent::end_element())
+            .map_err(|e| e.to_string())
+    }
+
+    fn serialize_attributes(
+        &self,
+        attributes: Vec<xml::attribute::OwnedAttribute>,
+        namespace: xml::namespace::Namespace,
+    ) -> Result<
+        (
+            Vec<xml::attribute::OwnedAttribute>,
+            xml::namespace::Namespace,
+        ),
+        String,
+    > {
+        Ok((attributes, namespace))
+    }
+}
+
+impl YaDeserialize for Timestamp {
+    fn deserialize<R: Read>(reader: &mut Deserializer<R>) -> Result<Self, String> {
+        let name = match reader.next_event()? {
+            ReaderEvent::StartElement { name, .. } => name,
+            event => return Err(format!("Expected timestamp start, found {:?}", event)),
+        };
+        // The closing tag is left for the caller to consume.
+        let mut text = String::new();
+        while let ReaderEvent::Characters(_) = reader.peek()? {
+            if let ReaderEvent::Characters(characters) = reader.next_event()? {
+                text.push_str(&characters);
+            }
+        }
+        text.trim()
+            .parse()
+            .map_err(|e| format!("Invalid {} {:?}: {}", name.local_name, text, e))
+    }
+}
+
+/// Source of the current time for the timestamps the crate generates.
+pub trait Clock {
+    fn now(&self) -> DateTime<FixedOffset>;
+}
+
+/// The local time of the system, the default clock.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<FixedOffset> {
+        Local::now().fixed_offset()
+    }
+}
+
+/// A clock frozen at a given time.
+#[derive(Debug, Clone, Copy)]
+pub struct FixedClock(DateTime<FixedOffset>);
+
+impl FixedClock {
+    pub fn new<Tz: TimeZone>(time: DateTime<Tz>) -> Self {
+        FixedClock(time.fixed_offset())
+    }
+}
+
+impl Clock for FixedClock {
+    fn now(&self) -> DateTime<FixedOffset> {
+        self.0
+    }
+}
+
+thread_local! {
+    static CLOCK: RefCell<Rc<dyn Clock>> = RefCell::new(Rc::new(SystemClock));
+}
+
+/// Replaces the clock used by the current thread to generate timestamps.
+pub fn set_clock(clock: impl Clock + 'static) {
+    CLOCK.with(|c| *c.borrow_mut() = Rc::new(clock));
+}
+
+/// Restores the [`SystemClock`] for the current thread.
+pub fn reset_clock() {
+    set_clock(SystemClock);
+}
+
+/// The current time of the thread's clock.
+pub fn now() -> Timestamp {
+    let clock = CLOCK.with(|c| c.borrow().clone());
+    Timestamp::new(clock.now())
+}
+
+#[cfg(test)]
+mod test {
+    use super::{now, reset_clock, set_clock, FixedClock, Timestamp};
+    use chrono::{FixedOffset, TimeZone};
+
+    #[test]
+    fn test_parse_and_format() {
+        let timestamp: Timestamp = "2024-05-01T12:30:00.250+02:00".parse().expect("error");
+        assert_eq!(timestamp.to_string(), "2024-05-01T12:30:00.250+02:00");
+        let utc: Timestamp = "2024-05-01T10:30:00.25Z".parse().expect("error");
+        assert_eq!(utc, timestamp);
+        let naive: Timestamp = "2024-05-01T10:30:00.25".parse().expect("error");
+        assert_eq!(naive, timestamp);
+        assert!("2024-05-01".parse::<Timestamp>().is_err());
+        assert!("yesterday".parse::<Timestamp>().is_err());
+    }
+
+    #[test]
+    fn test_fixed_clock() {
+        let time = FixedOffset::east_opt(3600)
+            .unwrap()
+            .with_ymd_and_hms(2024, 5, 1, 12, 0, 0)
+            .unwrap();
+        set_clock(FixedClock::new(time));
+        assert_eq!(now(), Timestamp::from(time));
+        assert_eq!(now().to_string(), "2024-05-01T12:00:00+01:00");
+        reset_clock();
+        assert_ne!(now(), Timestamp::from(time));
+    }
+}
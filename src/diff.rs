@@ -0,0 +1,644 @@
+// reqif-rs: Help library to write reqif files implemented in Rust.
+// Copyright (C) <2024>  INVAP S.E.
+//
+// This file is part of reqif-rs.
+//
+// reqif-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//! Semantic comparison of two ReqIF documents.
+//!
+//! Elements are matched by IDENTIFIER, so the order in which they are
+//! written doesn't matter, except for the order of hierarchy nodes among
+//! their siblings. LAST-CHANGE is not compared.
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt;
+
+use crate::req_if::{AttributeValue, AttributeValues, Children, ReqIf, SpecAttributes};
+
+/// Kind of element a [`Change`] is about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ElementKind {
+    Datatype,
+    EnumValue,
+    SpecType,
+    AttributeDefinition,
+    SpecObject,
+    SpecRelation,
+    Specification,
+    SpecHierarchy,
+    RelationGroup,
+}
+
+impl fmt::Display for ElementKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ElementKind::Datatype => "DATATYPE",
+            ElementKind::EnumValue => "ENUM-VALUE",
+            ElementKind::SpecType => "SPEC-TYPE",
+            ElementKind::AttributeDefinition => "ATTRIBUTE-DEFINITION",
+            ElementKind::SpecObject => "SPEC-OBJECT",
+            ElementKind::SpecRelation => "SPEC-RELATION",
+            ElementKind::Specification => "SPECIFICATION",
+            ElementKind::SpecHierarchy => "SPEC-HIERARCHY",
+            ElementKind::RelationGroup => "RELATION-GROUP",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Place of a hierarchy node: its specification, its parent node (`None`
+/// at the top of the specification) and its index among its siblings.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HierarchyPosition {
+    pub specification: String,
+    pub parent: Option<String>,
+    pub index: usize,
+}
+
+impl fmt::Display for HierarchyPosition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.parent {
+            Some(parent) => write!(f, "{}/{}[{}]", self.specification, parent, self.index),
+            None => write!(f, "{}[{}]", self.specification, self.index),
+        }
+    }
+}
+
+/// A difference found by [`diff`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Change {
+    Added {
+        kind: ElementKind,
+        identifier: String,
+    },
+    Removed {
+        kind: ElementKind,
+        identifier: String,
+    },
+    /// `property` of the element, such as "LONG-NAME" or "TYPE", went from
+    /// `old` to `new`. Missing properties are empty.
+    Modified {
+        kind: ElementKind,
+        identifier: String,
+        property: &'static str,
+        old: String,
+        new: String,
+    },
+    /// The value of the attribute `definition` was added, removed or changed.
+    ValueChanged {
+        kind: ElementKind,
+        identifier: String,
+        definition: String,
+        old: Option<AttributeValue>,
+        new: Option<AttributeValue>,
+    },
+    /// A hierarchy node was moved to another parent, or reordered among its
+    /// siblings.
+    Moved {
+        identifier: String,
+        old: HierarchyPosition,
+        new: HierarchyPosition,
+    },
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Change::Added { kind, identifier } => write!(f, "Added {} {}", kind, identifier),
+            Change::Removed { kind, identifier } => write!(f, "Removed {} {}", kind, identifier),
+            Change::Modified {
+                kind,
+                identifier,
+                property,
+                old,
+                new,
+            } => write!(
+                f,
+                "Modified {} of {} {}: {:?} -> {:?}",
+                property, kind, identifier, old, new
+            ),
+            Change::ValueChanged {
+                kind,
+                identifier,
+                definition,
+                ..
+            } => write!(f, "Changed value {} of {} {}", definition, kind, identifier),
+            Change::Moved {
+                identifier,
+                old,
+                new,
+            } => write!(f, "Moved SPEC-HIERARCHY {}: {} -> {}", identifier, old, new),
+        }
+    }
+}
+
+type Properties = Vec<(&'static str, String)>;
+
+/// What is compared of an element.
+struct Element<'a> {
+    properties: Properties,
+    values: Option<&'a AttributeValues>,
+}
+
+fn element(properties: Properties) -> Element<'static> {
+    Element {
+        properties,
+        values: None,
+    }
+}
+
+type Elements<'a> = BTreeMap<(ElementKind, &'a str), Element<'a>>;
+
+fn add_attribute_definitions<'a>(
+    elements: &mut Elements<'a>,
+    spec_type: &str,
+    attributes: &'a SpecAttributes,
+) {
+    macro_rules! add {
+        ($definitions:expr, $kind:literal) => {
+            add!($definitions, $kind, _d => vec![])
+        };
+        ($definitions:expr, $kind:literal, $d:ident => $extra:expr) => {
+            for $d in &$definitions {
+                let mut properties: Properties = vec![
+                    ("KIND", $kind.to_string()),
+                    ("LONG-NAME", $d.long_name.clone()),
+                    ("TYPE", $d.type_ref.reference.clone()),
+                    ("SPEC-TYPE", spec_type.to_string()),
+                ];
+                properties.extend($extra);
+                elements.insert(
+                    (ElementKind::AttributeDefinition, $d.identifier.as_str()),
+                    element(properties),
+                );
+            }
+        };
+    }
+    add!(attributes.boolean_attributes, "BOOLEAN");
+    add!(attributes.date_attributes, "DATE");
+    add!(attributes.enumeration_attributes, "ENUMERATION", d => vec![
+        ("MULTI-VALUED", d.multi_valued.to_string())
+    ]);
+    add!(attributes.integer_attributes, "INTEGER");
+    add!(attributes.real_attributes, "REAL");
+    add!(attributes.string_attributes, "STRING");
+    add!(attributes.xhtml_attributes, "XHTML");
+}
+
+fn add_hierarchy<'a>(
+    elements: &mut Elements<'a>,
+    positions: &mut HashMap<&'a str, HierarchyPosition>,
+    specification: &str,
+    parent: Option<&str>,
+    children: &'a Children,
+) {
+    for (index, node) in children.get_spec_hierarchy().iter().enumerate() {
+        elements.insert(
+            (ElementKind::SpecHierarchy, node.identifier.as_str()),
+            element(vec![("OBJECT", node.object.object_ref.clone())]),
+        );
+        positions.insert(
+            &node.identifier,
+            HierarchyPosition {
+                specification: specification.to_string(),
+                parent: parent.map(str::to_string),
+                index,
+            },
+        );
+        if let Some(grand_children) = &node.children {
+            add_hierarchy(
+                elements,
+                positions,
+                specification,
+                Some(&node.identifier),
+                grand_children,
+            );
+        }
+    }
+}
+
+/// Every compared element of `req_if` and the positions of its hierarchy
+/// nodes.
+fn elements(req_if: &ReqIf) -> (Elements<'_>, HashMap<&str, HierarchyPosition>) {
+    let content = &req_if.core_content.req_if_content;
+    let mut elements = Elements::new();
+    let mut positions = HashMap::new();
+
+    let data_types = &content.data_types;
+    macro_rules! add_datatypes {
+        ($definitions:expr, $kind:literal) => {
+            add_datatypes!($definitions, $kind, _d => vec![])
+        };
+        ($definitions:expr, $kind:literal, $d:ident => $extra:expr) => {
+            for $d in &$definitions {
+                let mut properties: Properties =
+                    vec![("KIND", $kind.to_string()), ("LONG-NAME", $d.long_name.clone())];
+                properties.extend($extra);
+                elements.insert(
+                    (ElementKind::Datatype, $d.identifier.as_str()),
+                    element(properties),
+                );
+            }
+        };
+    }
+    add_datatypes!(data_types.boolean_definitions, "BOOLEAN");
+    add_datatypes!(data_types.date_definitions, "DATE");
+    add_datatypes!(data_types.enumeration_definitions, "ENUMERATION");
+    add_datatypes!(data_types.integer_definitions, "INTEGER", d => vec![
+        ("MAX", d.max.to_string()),
+        ("MIN", d.min.to_string()),
+    ]);
+    add_datatypes!(data_types.real_definitions, "REAL", d => vec![
+        ("ACCURACY", d.accuracy.to_string()),
+        ("MAX", d.max.to_string()),
+        ("MIN", d.min.to_string()),
+    ]);
+    add_datatypes!(data_types.string_definitions, "STRING", d => vec![
+        ("MAX-LENGTH", d.max_length.to_string())
+    ]);
+    add_datatypes!(data_types.xhtml_definitions, "XHTML");
+    for enumeration in &data_types.enumeration_definitions {
+        for value in &enumeration.specified_values.values {
+            let embedded = &value.properties.embedded_value;
+            elements.insert(
+                (ElementKind::EnumValue, value.identifier.as_str()),
+                element(vec![
+                    ("LONG-NAME", value.long_name.clone()),
+                    ("KEY", embedded.key.to_string()),
+                    ("OTHER-CONTENT", embedded.other_content.clone()),
+                    ("DATATYPE", enumeration.identifier.clone()),
+                ]),
+            );
+        }
+    }
+
+    let spec_types = &content.spec_types;
+    let mut types: Vec<(&str, &String, &String, &SpecAttributes)> = vec![];
+    types.extend(spec_types.get_spec_object_types().iter().map(|t| {
+        (
+            "SPEC-OBJECT-TYPE",
+            &t.identifier,
+            &t.long_name,
+            &t.attributes,
+        )
+    }));
+    types.extend(spec_types.get_specification_types().iter().map(|t| {
+        (
+            "SPECIFICATION-TYPE",
+            &t.identifier,
+            &t.long_name,
+            &t.attributes,
+        )
+    }));
+    types.extend(spec_types.get_spec_relation_types().iter().map(|t| {
+        (
+            "SPEC-RELATION-TYPE",
+            &t.identifier,
+            &t.long_name,
+            &t.attributes,
+        )
+    }));
+    types.extend(spec_types.get_relation_group_types().iter().map(|t| {
+        (
+            "RELATION-GROUP-TYPE",
+            &t.identifier,
+            &t.long_name,
+            &t.attributes,
+        )
+    }));
+    for (kind, identifier, long_name, attributes) in types {
+        elements.insert(
+            (ElementKind::SpecType, identifier.as_str()),
+            element(vec![
+                ("KIND", kind.to_string()),
+                ("LONG-NAME", long_name.clone()),
+            ]),
+        );
+        add_attribute_definitions(&mut elements, identifier, attributes);
+    }
+
+    for object in content.spec_object.get_spec_objects() {
+        elements.insert(
+            (ElementKind::SpecObject, object.identifier.as_str()),
+            Element {
+                properties: vec![
+                    ("LONG-NAME", object.long_name.clone()),
+                    ("TYPE", object.spec_object_type.reference.clone()),
+                ],
+                values: Some(&object.values),
+            },
+        );
+    }
+    for relation in content.spec_relations.get_spec_relations() {
+        elements.insert(
+            (ElementKind::SpecRelation, relation.identifier.as_str()),
+            Element {
+                properties: vec![
+                    ("LONG-NAME", relation.long_name.clone()),
+                    ("TYPE", relation.spec_relation_type.reference.clone()),
+                    ("SOURCE", relation.source.object_ref.clone()),
+                    ("TARGET", relation.target.object_ref.clone()),
+                ],
+                values: Some(&relation.values),
+            },
+        );
+    }
+    for specification in content.specifications.get_specifications() {
+        elements.insert(
+            (
+                ElementKind::Specification,
+                specification.identifier.as_str(),
+            ),
+            Element {
+                properties: vec![
+                    ("LONG-NAME", specification.long_name.clone()),
+                    ("TYPE", specification.type_ref.spec_ref.clone()),
+                ],
+                values: Some(&specification.values),
+            },
+        );
+        add_hierarchy(
+            &mut elements,
+            &mut positions,
+            &specification.identifier,
+            None,
+            &specification.children,
+        );
+    }
+    for group in content.spec_relation_groups.get_relation_groups() {
+        let mut relations = group.spec_relations.references.clone();
+        relations.sort();
+        elements.insert(
+            (ElementKind::RelationGroup, group.identifier.as_str()),
+            element(vec![
+                ("LONG-NAME", group.long_name.clone()),
+                ("TYPE", group.relation_group_type.reference.clone()),
+                (
+                    "SOURCE-SPECIFICATION",
+                    group.source_specification.specification_ref.clone(),
+                ),
+                (
+                    "TARGET-SPECIFICATION",
+                    group.target_specification.specification_ref.clone(),
+                ),
+                ("SPEC-RELATIONS", relations.join(" ")),
+            ]),
+        );
+    }
+    (elements, positions)
+}
+
+/// The value of `definition`, with the order of enumeration literals
+/// ignored.
+pub(crate) fn value(values: Option<&AttributeValues>, definition: &str) -> Option<AttributeValue> {
+    match values?.get_value(definition)? {
+        AttributeValue::Enumeration(mut value) => {
+            value.values.references.sort();
+            Some(value.into())
+        }
+        value => Some(value),
+    }
+}
+
+/// Indices of a longest increasing subsequence of `sequence`.
+fn longest_increasing(sequence: &[usize]) -> Vec<usize> {
+    // tails[k]: index of the smallest tail of the subsequences of length k + 1.
+    let mut tails: Vec<usize> = vec![];
+    let mut previous = vec![None; sequence.len()];
+    for (i, item) in sequence.iter().enumerate() {
+        let k = tails.partition_point(|&t| sequence[t] < *item);
+        if k > 0 {
+            previous[i] = Some(tails[k - 1]);
+        }
+        if k == tails.len() {
+            tails.push(i);
+        } else {
+            tails[k] = i;
+        }
+    }
+    let mut result = vec![];
+    let mut current = tails.last().copied();
+    while let Some(i) = current {
+        result.push(i);
+        current = previous[i];
+    }
+    result.reverse();
+    result
+}
+
+type Sibling<'a> = (usize, usize, &'a str);
+
+/// Hierarchy nodes present in both documents that changed parent, or whose
+/// order relative to the siblings they kept changed.
+fn moved_nodes<'a>(
+    old: &HashMap<&'a str, HierarchyPosition>,
+    new: &HashMap<&'a str, HierarchyPosition>,
+) -> BTreeSet<&'a str> {
+    let mut moved = BTreeSet::new();
+    // (new index, old index, identifier) of the nodes kept under each parent.
+    let mut groups: HashMap<(&str, Option<&str>), Vec<Sibling>> = HashMap::new();
+    for (identifier, new_position) in new {
+        let old_position = match old.get(identifier) {
+            Some(position) => position,
+            None => continue,
+        };
+        if old_position.specification != new_position.specification
+            || old_position.parent != new_position.parent
+        {
+            moved.insert(*identifier);
+        } else {
+            groups
+                .entry((
+                    new_position.specification.as_str(),
+                    new_position.parent.as_deref(),
+                ))
+                .or_default()
+                .push((new_position.index, old_position.index, identifier));
+        }
+    }
+    for siblings in groups.values_mut() {
+        siblings.sort();
+        let old_order: Vec<usize> = siblings.iter().map(|(_, old, _)| *old).collect();
+        let kept: BTreeSet<usize> = longest_increasing(&old_order).into_iter().collect();
+        for (i, (_, _, identifier)) in siblings.iter().enumerate() {
+            if !kept.contains(&i) {
+                moved.insert(*identifier);
+            }
+        }
+    }
+    moved
+}
+
+/// Compares `old` and `new`, returning the changes that turn `old` into
+/// `new` grouped by [`ElementKind`] and sorted by identifier.
+pub fn diff(old: &ReqIf, new: &ReqIf) -> Vec<Change> {
+    let (old_elements, old_positions) = elements(old);
+    let (new_elements, new_positions) = elements(new);
+    let moved = moved_nodes(&old_positions, &new_positions);
+
+    let keys: BTreeSet<&(ElementKind, &str)> =
+        old_elements.keys().chain(new_elements.keys()).collect();
+    let mut changes = vec![];
+    for key in keys {
+        let (kind, identifier) = *key;
+        let (old_element, new_element) = match (old_elements.get(key), new_elements.get(key)) {
+            (Some(old_element), Some(new_element)) => (old_element, new_element),
+            (Some(_), None) => {
+                changes.push(Change::Removed {
+                    kind,
+                    identifier: identifier.to_string(),
+                });
+                continue;
+            }
+            (None, _) => {
+                changes.push(Change::Added {
+                    kind,
+                    identifier: identifier.to_string(),
+                });
+                continue;
+            }
+        };
+
+        let property = |element: &Element, name: &str| {
+            element
+                .properties
+                .iter()
+                .find(|(n, _)| *n == name)
+                .map(|(_, value)| value.clone())
+                .unwrap_or_default()
+        };
+        let mut names: Vec<&'static str> = vec![];
+        for (name, _) in old_element.properties.iter().chain(&new_element.properties) {
+            if !names.contains(name) {
+                names.push(name);
+            }
+        }
+        for name in names {
+            let (old_value, new_value) = (property(old_element, name), property(new_element, name));
+            if old_value != new_value {
+                changes.push(Change::Modified {
+                    kind,
+                    identifier: identifier.to_string(),
+                    property: name,
+                    old: old_value,
+                    new: new_value,
+                });
+            }
+        }
+
+        let definitions: BTreeSet<&String> = old_element
+            .values
+            .iter()
+            .chain(new_element.values.iter())
+            .flat_map(|values| values.definitions())
+            .collect();
+        for definition in definitions {
+            let old_value = value(old_element.values, definition);
+            let new_value = value(new_element.values, definition);
+            if old_value != new_value {
+                changes.push(Change::ValueChanged {
+                    kind,
+                    identifier: identifier.to_string(),
+                    definition: definition.clone(),
+                    old: old_value,
+                    new: new_value,
+                });
+            }
+        }
+
+        if kind == ElementKind::SpecHierarchy && moved.contains(identifier) {
+            changes.push(Change::Moved {
+                identifier: identifier.to_string(),
+                old: old_positions[identifier].clone(),
+                new: new_positions[identifier].clone(),
+            });
+        }
+    }
+    changes
+}
+
+#[cfg(test)]
+mod test {
+    use super::{diff, Change, ElementKind, HierarchyPosition};
+    use crate::fixture::module;
+    use crate::req_if::AttributeValueXHtml;
+
+    #[test]
+    fn test_order_is_ignored() {
+        let old = module("Milestone", &["REQ-2", "REQ-1"]);
+        let mut new = old.clone();
+        new.sort();
+        new.core_content.req_if_content.spec_object = module("Milestone", &["REQ-1", "REQ-2"])
+            .core_content
+            .req_if_content
+            .spec_object;
+        assert_eq!(diff(&old, &new), vec![]);
+    }
+
+    #[test]
+    fn test_changes() {
+        let old = module("Milestone", &["REQ-1", "REQ-2", "REQ-3", "REQ-4"]);
+        let mut new = module("Milestone", &["REQ-2", "REQ-1", "REQ-3", "REQ-5"]);
+        let text = AttributeValueXHtml::new(
+            "ATTRIBUTE-DEFINITION-XHTML-REQIF.Text-ID".to_string(),
+            "New text.".to_string(),
+        );
+        new.update_spec_object("REQ-1", |o| {
+            o.long_name = "First".to_string();
+            o.set_value(text.clone());
+        })
+        .expect("error");
+
+        let changes = diff(&old, &new);
+        let summary: Vec<String> = changes.iter().map(|c| c.to_string()).collect();
+        assert_eq!(
+            summary,
+            vec![
+                "Modified LONG-NAME of SPEC-OBJECT REQ-1: \"REQ-1\" -> \"First\"",
+                "Changed value ATTRIBUTE-DEFINITION-XHTML-REQIF.Text-ID of SPEC-OBJECT REQ-1",
+                "Removed SPEC-OBJECT REQ-4",
+                "Added SPEC-OBJECT REQ-5",
+                "Moved SPEC-HIERARCHY H-REQ-2: SPEC-1[1] -> SPEC-1[0]",
+                "Removed SPEC-HIERARCHY H-REQ-4",
+                "Added SPEC-HIERARCHY H-REQ-5",
+            ]
+        );
+        assert_eq!(
+            changes[1],
+            Change::ValueChanged {
+                kind: ElementKind::SpecObject,
+                identifier: "REQ-1".to_string(),
+                definition: "ATTRIBUTE-DEFINITION-XHTML-REQIF.Text-ID".to_string(),
+                old: Some(
+                    AttributeValueXHtml::new(
+                        "ATTRIBUTE-DEFINITION-XHTML-REQIF.Text-ID".to_string(),
+                        "Text.".to_string(),
+                    )
+                    .into()
+                ),
+                new: Some(text.into()),
+            }
+        );
+        match &changes[4] {
+            Change::Moved { new, .. } => assert_eq!(
+                new,
+                &HierarchyPosition {
+                    specification: "SPEC-1".to_string(),
+                    parent: None,
+                    index: 0,
+                }
+            ),
+            change => panic!("unexpected change {:?}", change),
+        }
+    }
+}
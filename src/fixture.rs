@@ -0,0 +1,79 @@
+// reqif-rs: Help library to write reqif files implemented in Rust.
+// Copyright (C) <2024>  INVAP S.E.
+//
+// This file is part of reqif-rs.
+//
+// reqif-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//! Documents shared by the tests.
+use chrono::{DateTime, Local, TimeZone};
+
+use crate::clock::{self, FixedClock};
+use crate::req_if::{Object, ReqIf, SpecHierarchy, SpecObject};
+
+/// The time the clock is frozen at by [`document`].
+pub fn created() -> DateTime<Local> {
+    Local.with_ymd_and_hms(2024, 5, 1, 12, 0, 0).unwrap()
+}
+
+/// A new document titled `title`. The clock of the test thread stays frozen
+/// at [`created`] afterwards.
+pub fn document(title: &str) -> ReqIf {
+    clock::set_clock(FixedClock::new(created()));
+    ReqIf::new(
+        "ID-1".to_string(),
+        created(),
+        "repository".to_string(),
+        "reqif-rs".to_string(),
+        "Doorstop".to_string(),
+        title.to_string(),
+    )
+}
+
+/// Adds a requirement named `identifier` with `text`.
+pub fn add_requirement(reqif: &mut ReqIf, identifier: &str, text: &str) {
+    let requirement = SpecObject::requirement(
+        identifier.to_string(),
+        clock::now(),
+        identifier.to_string(),
+        text.to_string(),
+        &reqif.core_content.req_if_content.spec_types,
+    )
+    .expect("error");
+    reqif.add_requirement(requirement).expect("error");
+}
+
+/// A document holding a requirement for each of `identifiers` and the module
+/// specification "SPEC-1" with a node "H-<identifier>" pointing at each one.
+pub fn module(title: &str, identifiers: &[&str]) -> ReqIf {
+    let mut reqif = document(title);
+    let mut specification = reqif
+        .build_module_specification("SPEC-1".to_string(), clock::now(), "Module".to_string())
+        .expect("error");
+    for identifier in identifiers {
+        add_requirement(&mut reqif, identifier, "Text.");
+        specification
+            .children
+            .add_spec_hierarchy(
+                SpecHierarchy::new(
+                    format!("H-{}", identifier),
+                    clock::now(),
+                    Object::new(identifier.to_string()),
+                ),
+                0,
+            )
+            .expect("error");
+    }
+    reqif.add_specification(specification).expect("error");
+    reqif
+}
@@ -0,0 +1,195 @@
+// reqif-rs: Help library to write reqif files implemented in Rust.
+// Copyright (C) <2024>  INVAP S.E.
+//
+// This file is part of reqif-rs.
+//
+// reqif-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//! Generation and checking of element IDENTIFIERs, which must be xsd:ID
+//! values, that is XML names without colon (NCName).
+use std::cell::RefCell;
+use uuid::Uuid;
+
+fn is_name_start_char(c: char) -> bool {
+    matches!(c,
+        'A'..='Z' | '_' | 'a'..='z'
+        | '\u{C0}'..='\u{D6}' | '\u{D8}'..='\u{F6}' | '\u{F8}'..='\u{2FF}'
+        | '\u{370}'..='\u{37D}' | '\u{37F}'..='\u{1FFF}' | '\u{200C}'..='\u{200D}'
+        | '\u{2070}'..='\u{218F}' | '\u{2C00}'..='\u{2FEF}' | '\u{3001}'..='\u{D7FF}'
+        | '\u{F900}'..='\u{FDCF}' | '\u{FDF0}'..='\u{FFFD}' | '\u{10000}'..='\u{EFFFF}')
+}
+
+fn is_name_char(c: char) -> bool {
+    is_name_start_char(c)
+        || matches!(c,
+            '-' | '.' | '0'..='9' | '\u{B7}' | '\u{300}'..='\u{36F}' | '\u{203F}'..='\u{2040}')
+}
+
+/// Returns `true` if `identifier` is a valid NCName, e.g. "REQ-1.2" but not
+/// "1.2" or "REQ:1".
+pub fn is_valid_ncname(identifier: &str) -> bool {
+    let mut chars = identifier.chars();
+    match chars.next() {
+        Some(c) if is_name_start_char(c) => chars.all(is_name_char),
+        _ => false,
+    }
+}
+
+/// Turns `identifier` into a valid NCName: invalid characters are replaced
+/// by '_' and a '_' is prepended if it doesn't start with a letter.
+pub fn sanitize_ncname(identifier: &str) -> String {
+    let mut sanitized: String = identifier
+        .chars()
+        .map(|c| if is_name_char(c) { c } else { '_' })
+        .collect();
+    if !sanitized.starts_with(is_name_start_char) {
+        sanitized.insert(0, '_');
+    }
+    sanitized
+}
+
+/// Strategy to create the IDENTIFIER of elements added without one.
+pub trait IdGenerator {
+    /// Returns an identifier for a new element named `kind`, such as
+    /// "SPEC-OBJECT", whose content is summarized by `content`.
+    fn generate(&mut self, kind: &str, content: &str) -> String;
+}
+
+/// Random identifiers such as "_1b4e28ba-2fa1-41d2-883f-0016d3cca427".
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UuidV4Generator;
+
+impl IdGenerator for UuidV4Generator {
+    fn generate(&mut self, _kind: &str, _content: &str) -> String {
+        format!("_{}", Uuid::new_v4())
+    }
+}
+
+/// Identifiers derived from a hash of the element content, so the same
+/// element always gets the same identifier.
+#[derive(Debug, Clone, Copy)]
+pub struct UuidV5Generator {
+    namespace: Uuid,
+}
+
+impl UuidV5Generator {
+    pub fn new(namespace: Uuid) -> Self {
+        UuidV5Generator { namespace }
+    }
+}
+
+impl Default for UuidV5Generator {
+    fn default() -> Self {
+        Self::new(Uuid::new_v5(
+            &Uuid::NAMESPACE_URL,
+            b"http://www.omg.org/spec/ReqIF/20110401/reqif.xsd",
+        ))
+    }
+}
+
+impl IdGenerator for UuidV5Generator {
+    fn generate(&mut self, kind: &str, content: &str) -> String {
+        let name = format!("{}\n{}", kind, content);
+        format!("_{}", Uuid::new_v5(&self.namespace, name.as_bytes()))
+    }
+}
+
+/// Sequential identifiers such as "REQ-1", "REQ-2"...
+#[derive(Debug, Clone)]
+pub struct CounterGenerator {
+    prefix: String,
+    next: u64,
+}
+
+impl CounterGenerator {
+    pub fn new(prefix: String) -> Self {
+        CounterGenerator { prefix, next: 1 }
+    }
+}
+
+impl IdGenerator for CounterGenerator {
+    fn generate(&mut self, _kind: &str, _content: &str) -> String {
+        let identifier = format!("{}{}", self.prefix, self.next);
+        self.next += 1;
+        identifier
+    }
+}
+
+thread_local! {
+    static GENERATOR: RefCell<Box<dyn IdGenerator>> = RefCell::new(Box::new(UuidV4Generator));
+}
+
+/// Replaces the generator used by the current thread.
+pub fn set_id_generator(generator: impl IdGenerator + 'static) {
+    GENERATOR.with(|g| *g.borrow_mut() = Box::new(generator));
+}
+
+/// Restores the [`UuidV4Generator`] for the current thread.
+pub fn reset_id_generator() {
+    set_id_generator(UuidV4Generator);
+}
+
+/// Generates an identifier with the thread's generator, sanitized if the
+/// generator doesn't return a valid NCName.
+pub fn generate(kind: &str, content: &str) -> String {
+    let identifier = GENERATOR.with(|g| g.borrow_mut().generate(kind, content));
+    if is_valid_ncname(&identifier) {
+        identifier
+    } else {
+        sanitize_ncname(&identifier)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        generate, is_valid_ncname, reset_id_generator, sanitize_ncname, set_id_generator,
+        CounterGenerator, IdGenerator, UuidV4Generator, UuidV5Generator,
+    };
+
+    #[test]
+    fn test_ncname() {
+        for valid in ["REQ-1.2", "_1", "Anforderung_ä", "h1"] {
+            assert!(is_valid_ncname(valid), "{}", valid);
+        }
+        for invalid in ["", "1.2", "-REQ", "REQ:1", "REQ 1", "REQ/1"] {
+            assert!(!is_valid_ncname(invalid), "{}", invalid);
+        }
+        assert_eq!(sanitize_ncname("1.2"), "_1.2");
+        assert_eq!(sanitize_ncname("REQ:1 a"), "REQ_1_a");
+        assert_eq!(sanitize_ncname(""), "_");
+        assert!(is_valid_ncname(&sanitize_ncname("-9 x")));
+    }
+
+    #[test]
+    fn test_generators() {
+        let mut v4 = UuidV4Generator;
+        let first = v4.generate("SPEC-OBJECT", "");
+        assert!(is_valid_ncname(&first));
+        assert_ne!(first, v4.generate("SPEC-OBJECT", ""));
+
+        let mut v5 = UuidV5Generator::default();
+        let hashed = v5.generate("SPEC-OBJECT", "The system shall...");
+        assert!(is_valid_ncname(&hashed));
+        assert_eq!(hashed, v5.generate("SPEC-OBJECT", "The system shall..."));
+        assert_ne!(hashed, v5.generate("SPEC-OBJECT", "The software shall..."));
+
+        set_id_generator(CounterGenerator::new("REQ-".to_string()));
+        assert_eq!(generate("SPEC-OBJECT", ""), "REQ-1");
+        assert_eq!(generate("SPEC-OBJECT", ""), "REQ-2");
+        set_id_generator(CounterGenerator::new(String::new()));
+        assert_eq!(generate("SPEC-OBJECT", ""), "_1");
+        reset_id_generator();
+        assert!(generate("SPEC-OBJECT", "").starts_with('_'));
+    }
+}
@@ -15,13 +15,32 @@
 //
 // You should have received a copy of the GNU Affero General Public License
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
+// yaserde_derive 0.10 expands its impls inside anonymous constants.
+#![allow(non_local_definitions)]
+
+pub mod archive;
+pub mod clock;
+pub mod diff;
+#[cfg(test)]
+mod fixture;
+pub mod identifier;
+pub mod merge;
+pub mod reader;
 pub mod req_if;
+pub mod schema;
+pub mod validation;
+pub mod writer;
+pub mod xhtml;
+
+pub use diff::diff;
+pub use merge::merge;
 
 #[cfg(test)]
 mod tests {
 
-    use crate::req_if::{Object, ReqIf, SpecHierarchy, SpecObjectRequirement};
-    use chrono::{DateTime, Local, SecondsFormat};
+    use crate::clock::Timestamp;
+    use crate::req_if::{Object, ReqIf, SpecHierarchy, SpecObject};
+    use chrono::{DateTime, Local};
 
     #[test]
     fn test_serialize() {
@@ -44,36 +63,42 @@ mod tests {
 
         let local: DateTime<Local> = Local::now();
 
-        let now = local.to_rfc3339_opts(SecondsFormat::Millis, false);
+        let now = Timestamp::from(local);
 
-        reqif.add_requirement(SpecObjectRequirement::new(
+        let requirement = SpecObject::requirement(
             "REQS-1".to_string(),
-            now.clone(),
+            now,
             "Titulo del requerimiento 1".to_string(),
             "Texto del requerimiento 1.".to_string(),
             &reqif.core_content.req_if_content.spec_types,
-        ));
+        )
+        .expect("error");
+        reqif.add_requirement(requirement).expect("error");
 
-        reqif.add_requirement(SpecObjectRequirement::new(
+        let requirement = SpecObject::requirement(
             "REQS-2".to_string(),
-            now.clone(),
+            now,
             "Titulo del requerimiento 2".to_string(),
             "Texto del requerimiento 2.".to_string(),
             &reqif.core_content.req_if_content.spec_types,
-        ));
+        )
+        .expect("error");
+        reqif.add_requirement(requirement).expect("error");
 
-        let mut specification = reqif.build_module_specification(
-            "REQS".to_string(),
-            now.to_string(),
-            "Project User Requirements".to_string(),
-        );
+        let mut specification = reqif
+            .build_module_specification(
+                "REQS".to_string(),
+                now,
+                "Project User Requirements".to_string(),
+            )
+            .expect("error");
 
         specification
             .children
             .add_spec_hierarchy(
                 SpecHierarchy {
                     identifier: "h1".to_string(),
-                    last_change: now.clone(),
+                    last_change: now,
                     object: Object {
                         object_ref: "REQS-1".to_string(),
                     },
@@ -88,7 +113,7 @@ mod tests {
             .add_spec_hierarchy(
                 SpecHierarchy {
                     identifier: "h2".to_string(),
-                    last_change: now.clone(),
+                    last_change: now,
                     object: Object {
                         object_ref: "REQS-2".to_string(),
                     },
@@ -98,7 +123,9 @@ mod tests {
             )
             .expect("Unexpected error adding children");
 
-        reqif.add_specification(specification);
+        reqif
+            .add_specification(specification)
+            .expect("Unexpected error adding the specification");
         reqif.write_to("libtest.reqif").unwrap();
     }
 }
@@ -0,0 +1,947 @@
+// reqif-rs: Help library to write reqif files implemented in Rust.
+// Copyright (C) <2024>  INVAP S.E.
+//
+// This file is part of reqif-rs.
+//
+// reqif-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//! Three-way merge of ReqIF documents.
+//!
+//! Both edited documents are compared with their common base, elements
+//! being matched by IDENTIFIER: what only one side changed is taken, and a
+//! [`Conflict`] is reported where both sides changed the same thing
+//! differently. Spec objects, relations and specifications are merged
+//! property by property and attribute value by attribute value, hierarchy
+//! nodes one by one. Datatypes, spec types and relation groups are merged
+//! as whole elements.
+//!
+//! A conflict is resolved in favour of the element with the newer
+//! LAST-CHANGE, ours on a tie. An element changed on one side and removed
+//! on the other is kept.
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use crate::clock::Timestamp;
+use crate::diff::{value, ElementKind};
+use crate::req_if::{
+    AttributeValues, Children, DataTypeDefinitionBoolean, DataTypeDefinitionDate,
+    DataTypeDefinitionEnumeration, DataTypeDefinitionInteger, DataTypeDefinitionReal,
+    DataTypeDefinitionString, DataTypeDefinitionXHtml, RelationGroup, RelationGroupType, ReqIf,
+    SpecAttributes, SpecHierarchy, SpecObject, SpecObjectType, SpecRelation, SpecRelationType,
+    Specification, SpecificationType,
+};
+
+/// One of the two edited documents.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    Ours,
+    Theirs,
+}
+
+/// Something both sides changed differently.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Conflict {
+    pub kind: ElementKind,
+    pub identifier: String,
+    /// What both sides changed: a property such as "LONG-NAME" or "PARENT",
+    /// the identifier of an attribute definition, "CONTENT" for elements
+    /// merged as a whole, or "REMOVED" when one side removed the element.
+    pub item: String,
+    /// The side whose version is in the merged document.
+    pub resolution: Side,
+}
+
+impl fmt::Display for Conflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let side = match self.resolution {
+            Side::Ours => "ours",
+            Side::Theirs => "theirs",
+        };
+        write!(
+            f,
+            "Conflict on {} of {} {}, kept {}",
+            self.item, self.kind, self.identifier, side
+        )
+    }
+}
+
+/// Result of [`merge`].
+#[derive(Debug)]
+pub struct Merge {
+    pub document: ReqIf,
+    pub conflicts: Vec<Conflict>,
+}
+
+/// The element being merged and the conflicts found so far.
+struct Context<'a> {
+    kind: ElementKind,
+    identifier: &'a str,
+    prefer: Side,
+    conflicts: &'a mut Vec<Conflict>,
+}
+
+impl Context<'_> {
+    /// The side whose version of `item` to keep. `base` is `None` when the
+    /// element was added on both sides.
+    fn side<V: PartialEq>(&mut self, item: &str, base: Option<&V>, ours: &V, theirs: &V) -> Side {
+        if ours == theirs || base == Some(theirs) {
+            Side::Ours
+        } else if base == Some(ours) {
+            Side::Theirs
+        } else {
+            self.conflicts.push(Conflict {
+                kind: self.kind,
+                identifier: self.identifier.to_string(),
+                item: item.to_string(),
+                resolution: self.prefer,
+            });
+            self.prefer
+        }
+    }
+
+    fn choose<V: PartialEq + Clone>(
+        &mut self,
+        item: &str,
+        base: Option<&V>,
+        ours: &V,
+        theirs: &V,
+    ) -> V {
+        match self.side(item, base, ours, theirs) {
+            Side::Ours => ours.clone(),
+            Side::Theirs => theirs.clone(),
+        }
+    }
+}
+
+trait Mergeable: Clone + PartialEq {
+    const KIND: ElementKind;
+
+    fn identifier(&self) -> &str;
+
+    fn last_change(&self) -> Timestamp;
+
+    fn set_last_change(&mut self, last_change: Timestamp);
+
+    /// Sets the LAST-CHANGE of the element and everything it contains.
+    fn stamp(&mut self, last_change: Timestamp);
+
+    fn merge(base: Option<&Self>, ours: &Self, theirs: &Self, context: &mut Context) -> Self;
+}
+
+macro_rules! mergeable {
+    ($($type:ty: $kind:ident, $merge:ident, |$item:pat_param, $last_change:pat_param| $stamp:expr;)+) => {$(
+        impl Mergeable for $type {
+            const KIND: ElementKind = ElementKind::$kind;
+
+            fn identifier(&self) -> &str {
+                &self.identifier
+            }
+
+            fn last_change(&self) -> Timestamp {
+                self.last_change
+            }
+
+            fn set_last_change(&mut self, last_change: Timestamp) {
+                self.last_change = last_change;
+            }
+
+            fn stamp(&mut self, last_change: Timestamp) {
+                self.last_change = last_change;
+                let ($item, $last_change) = (self, last_change);
+                $stamp
+            }
+
+            fn merge(base: Option<&Self>, ours: &Self, theirs: &Self, context: &mut Context) -> Self {
+                $merge(base, ours, theirs, context)
+            }
+        }
+    )+};
+}
+
+mergeable! {
+    DataTypeDefinitionBoolean: Datatype, merge_whole, |_, _| ();
+    DataTypeDefinitionDate: Datatype, merge_whole, |_, _| ();
+    DataTypeDefinitionEnumeration: Datatype, merge_whole, |datatype, last_change| {
+        for value in &mut datatype.specified_values.values {
+            value.last_change = last_change;
+        }
+    };
+    DataTypeDefinitionInteger: Datatype, merge_whole, |_, _| ();
+    DataTypeDefinitionReal: Datatype, merge_whole, |_, _| ();
+    DataTypeDefinitionString: Datatype, merge_whole, |_, _| ();
+    DataTypeDefinitionXHtml: Datatype, merge_whole, |_, _| ();
+    SpecObjectType: SpecType, merge_whole, |t, last_change| stamp_attributes(&mut t.attributes, last_change);
+    SpecificationType: SpecType, merge_whole, |t, last_change| stamp_attributes(&mut t.attributes, last_change);
+    SpecRelationType: SpecType, merge_whole, |t, last_change| stamp_attributes(&mut t.attributes, last_change);
+    RelationGroupType: SpecType, merge_whole, |t, last_change| stamp_attributes(&mut t.attributes, last_change);
+    SpecObject: SpecObject, merge_spec_object, |_, _| ();
+    SpecRelation: SpecRelation, merge_spec_relation, |_, _| ();
+    Specification: Specification, merge_specification, |specification, last_change| {
+        stamp_children(&mut specification.children, last_change)
+    };
+    RelationGroup: RelationGroup, merge_whole, |_, _| ();
+}
+
+fn stamp_attributes(attributes: &mut SpecAttributes, last_change: Timestamp) {
+    macro_rules! stamp {
+        ($($field:ident),+) => {$(
+            for definition in &mut attributes.$field {
+                definition.last_change = last_change;
+            }
+        )+};
+    }
+    stamp!(
+        boolean_attributes,
+        date_attributes,
+        enumeration_attributes,
+        integer_attributes,
+        real_attributes,
+        string_attributes,
+        xhtml_attributes
+    );
+}
+
+fn stamp_children(children: &mut Children, last_change: Timestamp) {
+    for node in children.get_spec_hierarchy_mut() {
+        node.last_change = last_change;
+        if let Some(children) = &mut node.children {
+            stamp_children(children, last_change);
+        }
+    }
+}
+
+/// A copy of `item` with every LAST-CHANGE set to `last_change`, so that
+/// versions can be compared by content.
+fn stamped<T: Mergeable>(item: &T, last_change: Timestamp) -> T {
+    let mut item = item.clone();
+    item.stamp(last_change);
+    item
+}
+
+fn merge_whole<T: Mergeable>(base: Option<&T>, ours: &T, theirs: &T, context: &mut Context) -> T {
+    let last_change = ours.last_change();
+    let base = base.map(|base| stamped(base, last_change));
+    let (stamped_ours, stamped_theirs) = (stamped(ours, last_change), stamped(theirs, last_change));
+    match context.side("CONTENT", base.as_ref(), &stamped_ours, &stamped_theirs) {
+        Side::Ours => ours.clone(),
+        Side::Theirs => theirs.clone(),
+    }
+}
+
+fn merge_values(
+    base: Option<&AttributeValues>,
+    ours: &AttributeValues,
+    theirs: &AttributeValues,
+    context: &mut Context,
+) -> AttributeValues {
+    let mut definitions: Vec<String> = vec![];
+    for definition in ours.definitions().into_iter().chain(theirs.definitions()) {
+        if !definitions.contains(definition) {
+            definitions.push(definition.clone());
+        }
+    }
+
+    let mut merged = ours.clone();
+    for definition in &definitions {
+        let base_value = base.map(|base| value(Some(base), definition));
+        let ours_value = value(Some(ours), definition);
+        let theirs_value = value(Some(theirs), definition);
+        if context.side(definition, base_value.as_ref(), &ours_value, &theirs_value) == Side::Theirs
+        {
+            match theirs.get_value(definition) {
+                Some(value) => merged.set_value(value),
+                None => {
+                    merged.remove_value(definition);
+                }
+            }
+        }
+    }
+    merged
+}
+
+fn merge_spec_object(
+    base: Option<&SpecObject>,
+    ours: &SpecObject,
+    theirs: &SpecObject,
+    context: &mut Context,
+) -> SpecObject {
+    let mut merged = ours.clone();
+    merged.long_name = context.choose(
+        "LONG-NAME",
+        base.map(|b| &b.long_name),
+        &ours.long_name,
+        &theirs.long_name,
+    );
+    merged.spec_object_type = context.choose(
+        "TYPE",
+        base.map(|b| &b.spec_object_type),
+        &ours.spec_object_type,
+        &theirs.spec_object_type,
+    );
+    merged.values = merge_values(
+        base.map(|b| &b.values),
+        &ours.values,
+        &theirs.values,
+        context,
+    );
+    merged
+}
+
+fn merge_spec_relation(
+    base: Option<&SpecRelation>,
+    ours: &SpecRelation,
+    theirs: &SpecRelation,
+    context: &mut Context,
+) -> SpecRelation {
+    let mut merged = ours.clone();
+    merged.long_name = context.choose(
+        "LONG-NAME",
+        base.map(|b| &b.long_name),
+        &ours.long_name,
+        &theirs.long_name,
+    );
+    merged.spec_relation_type = context.choose(
+        "TYPE",
+        base.map(|b| &b.spec_relation_type),
+        &ours.spec_relation_type,
+        &theirs.spec_relation_type,
+    );
+    merged.source = context.choose(
+        "SOURCE",
+        base.map(|b| &b.source),
+        &ours.source,
+        &theirs.source,
+    );
+    merged.target = context.choose(
+        "TARGET",
+        base.map(|b| &b.target),
+        &ours.target,
+        &theirs.target,
+    );
+    merged.values = merge_values(
+        base.map(|b| &b.values),
+        &ours.values,
+        &theirs.values,
+        context,
+    );
+    merged
+}
+
+fn merge_specification(
+    base: Option<&Specification>,
+    ours: &Specification,
+    theirs: &Specification,
+    context: &mut Context,
+) -> Specification {
+    let mut merged = ours.clone();
+    merged.long_name = context.choose(
+        "LONG-NAME",
+        base.map(|b| &b.long_name),
+        &ours.long_name,
+        &theirs.long_name,
+    );
+    merged.type_ref = context.choose(
+        "TYPE",
+        base.map(|b| &b.type_ref),
+        &ours.type_ref,
+        &theirs.type_ref,
+    );
+    merged.values = merge_values(
+        base.map(|b| &b.values),
+        &ours.values,
+        &theirs.values,
+        context,
+    );
+    merged.children = merge_hierarchy(
+        base.map(|b| &b.children),
+        &ours.children,
+        &theirs.children,
+        context.conflicts,
+    );
+    merged
+}
+
+/// Hierarchy nodes by identifier with their parent, and the children of
+/// each node (`None` being the top of the specification).
+#[derive(Default)]
+struct Tree<'a> {
+    nodes: HashMap<&'a str, (&'a SpecHierarchy, Option<&'a str>)>,
+    children: HashMap<Option<&'a str>, Vec<&'a str>>,
+    order: Vec<&'a str>,
+}
+
+impl<'a> Tree<'a> {
+    fn new(children: Option<&'a Children>) -> Self {
+        let mut tree = Tree::default();
+        if let Some(children) = children {
+            tree.add(None, children);
+        }
+        tree
+    }
+
+    fn add(&mut self, parent: Option<&'a str>, children: &'a Children) {
+        for node in children.get_spec_hierarchy() {
+            let identifier = node.identifier.as_str();
+            self.nodes.insert(identifier, (node, parent));
+            self.children.entry(parent).or_default().push(identifier);
+            self.order.push(identifier);
+            if let Some(children) = &node.children {
+                self.add(Some(identifier), children);
+            }
+        }
+    }
+
+    fn parent(&self, identifier: &str) -> Option<Option<&'a str>> {
+        self.nodes.get(identifier).map(|(_, parent)| *parent)
+    }
+
+    /// Children of `parent` that are placed under it in `placed`.
+    fn children_in(&self, parent: Option<&str>, placed: &Placed<'a>) -> Vec<&'a str> {
+        self.children
+            .get(&parent)
+            .into_iter()
+            .flatten()
+            .filter(|id| placed.get(*id).is_some_and(|(_, p)| *p == parent))
+            .copied()
+            .collect()
+    }
+}
+
+/// Merged hierarchy nodes, without their children, and their parent.
+type Placed<'a> = HashMap<&'a str, (SpecHierarchy, Option<&'a str>)>;
+
+fn merge_hierarchy(
+    base: Option<&Children>,
+    ours: &Children,
+    theirs: &Children,
+    conflicts: &mut Vec<Conflict>,
+) -> Children {
+    let (base, ours, theirs) = (
+        Tree::new(base),
+        Tree::new(Some(ours)),
+        Tree::new(Some(theirs)),
+    );
+    let leaf = |node: &SpecHierarchy| {
+        SpecHierarchy::new(
+            node.identifier.clone(),
+            node.last_change,
+            node.object.clone(),
+        )
+    };
+
+    let mut order: Vec<&str> = vec![];
+    let mut seen = HashSet::new();
+    let mut placed: Placed = HashMap::new();
+    for &identifier in ours.order.iter().chain(&theirs.order) {
+        if !seen.insert(identifier) {
+            continue;
+        }
+        order.push(identifier);
+        let base_node = base.nodes.get(identifier);
+        match (ours.nodes.get(identifier), theirs.nodes.get(identifier)) {
+            (Some((ours_node, ours_parent)), Some((theirs_node, theirs_parent))) => {
+                let mut context = Context {
+                    kind: ElementKind::SpecHierarchy,
+                    identifier,
+                    prefer: if theirs_node.last_change > ours_node.last_change {
+                        Side::Theirs
+                    } else {
+                        Side::Ours
+                    },
+                    conflicts: &mut *conflicts,
+                };
+                let mut node = leaf(ours_node);
+                node.last_change = ours_node.last_change.max(theirs_node.last_change);
+                node.object = context.choose(
+                    "OBJECT",
+                    base_node.map(|(n, _)| &n.object),
+                    &ours_node.object,
+                    &theirs_node.object,
+                );
+                let parent = context.choose(
+                    "PARENT",
+                    base_node.map(|(_, p)| p),
+                    ours_parent,
+                    theirs_parent,
+                );
+                placed.insert(identifier, (node, parent));
+            }
+            (Some((node, parent)), None) | (None, Some((node, parent))) => {
+                let side = if ours.nodes.contains_key(identifier) {
+                    Side::Ours
+                } else {
+                    Side::Theirs
+                };
+                match base_node {
+                    // Removed on the other side.
+                    Some((base_node, base_parent))
+                        if base_node.object == node.object && base_parent == parent => {}
+                    Some(_) => {
+                        conflicts.push(Conflict {
+                            kind: ElementKind::SpecHierarchy,
+                            identifier: identifier.to_string(),
+                            item: "REMOVED".to_string(),
+                            resolution: side,
+                        });
+                        placed.insert(identifier, (leaf(node), *parent));
+                    }
+                    None => {
+                        placed.insert(identifier, (leaf(node), *parent));
+                    }
+                }
+            }
+            (None, None) => {}
+        }
+    }
+
+    loop {
+        // Nodes whose parent was removed go up to its closest remaining
+        // ancestor.
+        for identifier in &order {
+            let Some(mut parent) = placed.get(identifier).map(|(_, parent)| *parent) else {
+                continue;
+            };
+            while let Some(missing) = parent.filter(|p| !placed.contains_key(p)) {
+                parent = ours
+                    .parent(missing)
+                    .or_else(|| theirs.parent(missing))
+                    .or_else(|| base.parent(missing))
+                    .flatten();
+            }
+            placed.get_mut(identifier).unwrap().1 = parent;
+        }
+
+        // Moves made on each side can make a node its own ancestor. Such
+        // a node goes back to our parent, or to the top.
+        let Some(cyclic) = order.iter().find_map(|identifier| {
+            let mut current = *identifier;
+            for _ in 0..=placed.len() {
+                current = placed.get(current)?.1?;
+            }
+            Some(current)
+        }) else {
+            break;
+        };
+        let ours_parent = ours.parent(cyclic).flatten();
+        let (_, parent) = placed.get_mut(cyclic).unwrap();
+        if *parent != ours_parent && ours_parent.is_some() {
+            *parent = ours_parent;
+            conflicts.push(Conflict {
+                kind: ElementKind::SpecHierarchy,
+                identifier: cyclic.to_string(),
+                item: "PARENT".to_string(),
+                resolution: Side::Ours,
+            });
+        } else {
+            *parent = None;
+        }
+    }
+
+    let mut children = Children::new();
+    *children.get_spec_hierarchy_mut() =
+        build_children(None, &order, &placed, &base, &ours, &theirs);
+    children
+}
+
+/// The merged children of `parent`. Siblings keep our order unless only
+/// theirs was reordered; nodes placed by one side only are put after the
+/// sibling they follow on that side.
+fn build_children(
+    parent: Option<&str>,
+    order: &[&str],
+    placed: &Placed,
+    base: &Tree,
+    ours: &Tree,
+    theirs: &Tree,
+) -> Vec<SpecHierarchy> {
+    let (base_list, ours_list, theirs_list) = (
+        base.children_in(parent, placed),
+        ours.children_in(parent, placed),
+        theirs.children_in(parent, placed),
+    );
+    let sets: Vec<HashSet<&str>> = [&base_list, &ours_list, &theirs_list]
+        .into_iter()
+        .map(|list| list.iter().copied().collect())
+        .collect();
+    let in_all = |id: &&&str| sets.iter().all(|set| set.contains(**id));
+    let base_common: Vec<_> = base_list.iter().filter(in_all).collect();
+    let ours_common: Vec<_> = ours_list.iter().filter(in_all).collect();
+    let theirs_common: Vec<_> = theirs_list.iter().filter(in_all).collect();
+    let (kept, other) = if ours_common == base_common && theirs_common != base_common {
+        (&theirs_list, &ours_list)
+    } else {
+        (&ours_list, &theirs_list)
+    };
+
+    // Nodes missing from the kept side follow the node before them on the
+    // other side, which is in the merged siblings once it is handled.
+    let mut present: HashSet<&str> = kept.iter().copied().collect();
+    let mut following: HashMap<Option<&str>, Vec<&str>> = HashMap::new();
+    let mut previous = None;
+    for &identifier in other {
+        if present.insert(identifier) {
+            following.entry(previous).or_default().push(identifier);
+        }
+        previous = Some(identifier);
+    }
+    let mut siblings = vec![];
+    for anchor in std::iter::once(None).chain(kept.iter().map(|id| Some(*id))) {
+        siblings.extend(anchor);
+        let mut pending: Vec<&str> = following
+            .get(&anchor)
+            .into_iter()
+            .flatten()
+            .rev()
+            .copied()
+            .collect();
+        while let Some(identifier) = pending.pop() {
+            siblings.push(identifier);
+            pending.extend(following.get(&Some(identifier)).into_iter().flatten().rev());
+        }
+    }
+    for identifier in order {
+        let placed_here = placed.get(identifier).is_some_and(|(_, p)| *p == parent);
+        if placed_here && present.insert(identifier) {
+            siblings.push(identifier);
+        }
+    }
+
+    siblings
+        .into_iter()
+        .map(|identifier| {
+            let mut node = placed[identifier].0.clone();
+            let children = build_children(Some(identifier), order, placed, base, ours, theirs);
+            if !children.is_empty() {
+                let mut node_children = Children::new();
+                *node_children.get_spec_hierarchy_mut() = children;
+                node.children = Some(node_children);
+            }
+            node
+        })
+        .collect()
+}
+
+fn merge_element<T: Mergeable>(
+    base: Option<&T>,
+    ours: Option<&T>,
+    theirs: Option<&T>,
+    conflicts: &mut Vec<Conflict>,
+) -> Option<T> {
+    match (ours, theirs) {
+        (Some(ours), Some(theirs)) => {
+            let mut context = Context {
+                kind: T::KIND,
+                identifier: ours.identifier(),
+                prefer: if theirs.last_change() > ours.last_change() {
+                    Side::Theirs
+                } else {
+                    Side::Ours
+                },
+                conflicts,
+            };
+            let mut merged = T::merge(base, ours, theirs, &mut context);
+            merged.set_last_change(ours.last_change().max(theirs.last_change()));
+            Some(merged)
+        }
+        (Some(kept), None) | (None, Some(kept)) => match base {
+            None => Some(kept.clone()),
+            // Removed on the other side.
+            Some(base)
+                if stamped(base, kept.last_change()) == stamped(kept, kept.last_change()) =>
+            {
+                None
+            }
+            Some(_) => {
+                conflicts.push(Conflict {
+                    kind: T::KIND,
+                    identifier: kept.identifier().to_string(),
+                    item: "REMOVED".to_string(),
+                    resolution: if ours.is_some() {
+                        Side::Ours
+                    } else {
+                        Side::Theirs
+                    },
+                });
+                Some(kept.clone())
+            }
+        },
+        (None, None) => None,
+    }
+}
+
+/// Merges lists of elements matched by IDENTIFIER. Our elements keep their
+/// order, the ones only theirs has are appended.
+fn merge_elements<T: Mergeable>(
+    base: &[T],
+    ours: &[T],
+    theirs: &[T],
+    conflicts: &mut Vec<Conflict>,
+) -> Vec<T> {
+    fn index<T: Mergeable>(items: &[T]) -> HashMap<&str, &T> {
+        items.iter().map(|item| (item.identifier(), item)).collect()
+    }
+    let (base_items, ours_items, theirs_items) = (index(base), index(ours), index(theirs));
+    let mut merged = vec![];
+    let theirs_only = theirs
+        .iter()
+        .filter(|item| !ours_items.contains_key(item.identifier()));
+    for item in ours.iter().chain(theirs_only) {
+        let identifier = item.identifier();
+        if let Some(element) = merge_element(
+            base_items.get(identifier).copied(),
+            ours_items.get(identifier).copied(),
+            theirs_items.get(identifier).copied(),
+            conflicts,
+        ) {
+            merged.push(element);
+        }
+    }
+    merged
+}
+
+/// Merges the changes made in `ours` and `theirs` since `base`. The merged
+/// document keeps our header and options.
+pub fn merge(base: &ReqIf, ours: &ReqIf, theirs: &ReqIf) -> Merge {
+    let mut conflicts = vec![];
+    let mut document = ours.clone();
+    let content = &mut document.core_content.req_if_content;
+    let (b, o, t) = (
+        &base.core_content.req_if_content,
+        &ours.core_content.req_if_content,
+        &theirs.core_content.req_if_content,
+    );
+    macro_rules! merge {
+        ($($field:ident),+) => {$(
+            content.data_types.$field = merge_elements(
+                &b.data_types.$field,
+                &o.data_types.$field,
+                &t.data_types.$field,
+                &mut conflicts,
+            );
+        )+};
+    }
+    macro_rules! merge_with {
+        ($($section:ident.$get:ident / $get_mut:ident),+) => {$(
+            *content.$section.$get_mut() = merge_elements(
+                b.$section.$get(),
+                o.$section.$get(),
+                t.$section.$get(),
+                &mut conflicts,
+            );
+        )+};
+    }
+    merge!(
+        boolean_definitions,
+        date_definitions,
+        enumeration_definitions,
+        integer_definitions,
+        real_definitions,
+        string_definitions,
+        xhtml_definitions
+    );
+    merge_with!(
+        spec_types.get_spec_object_types / get_spec_object_types_mut,
+        spec_types.get_specification_types / get_specification_types_mut,
+        spec_types.get_spec_relation_types / get_spec_relation_types_mut,
+        spec_types.get_relation_group_types / get_relation_group_types_mut,
+        spec_object.get_spec_objects / get_spec_objects_mut,
+        spec_relations.get_spec_relations / get_spec_relations_mut,
+        specifications.get_specifications / get_specifications_mut,
+        spec_relation_groups.get_relation_groups / get_relation_groups_mut
+    );
+    document.forget_identifiers();
+    Merge {
+        document,
+        conflicts,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{merge, Conflict, Side};
+    use crate::clock::{self, Timestamp};
+    use crate::diff::ElementKind;
+    use crate::fixture::{self, module};
+    use crate::req_if::{AttributeValue, AttributeValueXHtml, Object, ReqIf, SpecHierarchy};
+    use chrono::Duration;
+
+    const TEXT: &str = "ATTRIBUTE-DEFINITION-XHTML-REQIF.Text-ID";
+
+    fn text(text: &str) -> AttributeValueXHtml {
+        AttributeValueXHtml::new(TEXT.to_string(), text.to_string())
+    }
+
+    fn hierarchy(reqif: &ReqIf) -> Vec<(usize, String)> {
+        let specification = reqif.get_specification("SPEC-1").expect("error");
+        specification
+            .children
+            .iter_depth_first()
+            .map(|(depth, _, node)| (depth, node.identifier.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn test_merge_independent_changes() {
+        let base = module("Round trip", &["REQ-1", "REQ-2", "REQ-3"]);
+
+        let mut ours = base.clone();
+        ours.update_spec_object("REQ-1", |o| o.set_value(text("Ours.")))
+            .expect("error");
+        ours.update_specification("SPEC-1", |s| {
+            s.children.move_to("H-REQ-3", Some("H-REQ-1"), 0)
+        })
+        .expect("error")
+        .expect("error");
+
+        let mut theirs = base.clone();
+        theirs
+            .update_spec_object("REQ-2", |o| o.long_name = "Second".to_string())
+            .expect("error");
+        fixture::add_requirement(&mut theirs, "REQ-4", "Text.");
+        theirs
+            .update_specification("SPEC-1", |s| {
+                s.children.insert_child(
+                    None,
+                    3,
+                    SpecHierarchy::new(
+                        "H-REQ-4".to_string(),
+                        clock::now(),
+                        Object::new("REQ-4".to_string()),
+                    ),
+                )
+            })
+            .expect("error")
+            .expect("error");
+
+        let merged = merge(&base, &ours, &theirs);
+        assert_eq!(merged.conflicts, vec![]);
+        let document = merged.document;
+        assert_eq!(
+            document
+                .get_spec_object("REQ-1")
+                .unwrap()
+                .values
+                .get_value(TEXT),
+            Some(AttributeValue::from(text("Ours.")))
+        );
+        assert_eq!(
+            document.get_spec_object("REQ-2").unwrap().long_name,
+            "Second"
+        );
+        assert!(document.get_spec_object("REQ-4").is_some());
+        assert_eq!(
+            hierarchy(&document),
+            vec![
+                (0, "H-REQ-1".to_string()),
+                (1, "H-REQ-3".to_string()),
+                (0, "H-REQ-2".to_string()),
+                (0, "H-REQ-4".to_string()),
+            ]
+        );
+        assert!(document.validate().is_empty());
+    }
+
+    #[test]
+    fn test_merge_sibling_order() {
+        let base = module("Round trip", &["REQ-1", "REQ-2", "REQ-3"]);
+        let node = |reqif: &mut ReqIf, identifier: &str, index| {
+            fixture::add_requirement(reqif, identifier, "Text.");
+            let spec_hierarchy = SpecHierarchy::new(
+                format!("H-{}", identifier),
+                clock::now(),
+                Object::new(identifier.to_string()),
+            );
+            reqif
+                .insert_spec_hierarchy("SPEC-1", None, index, spec_hierarchy)
+                .expect("error");
+        };
+
+        let mut ours = base.clone();
+        node(&mut ours, "REQ-4", 0);
+
+        let mut theirs = base.clone();
+        theirs
+            .update_specification("SPEC-1", |s| s.children.move_to("H-REQ-3", None, 0))
+            .expect("error")
+            .expect("error");
+        node(&mut theirs, "REQ-5", 3);
+        node(&mut theirs, "REQ-6", 4);
+
+        let merged = merge(&base, &ours, &theirs);
+        assert_eq!(merged.conflicts, vec![]);
+        assert_eq!(
+            hierarchy(&merged.document),
+            ["H-REQ-4", "H-REQ-3", "H-REQ-1", "H-REQ-2", "H-REQ-5", "H-REQ-6"]
+                .iter()
+                .map(|identifier| (0, identifier.to_string()))
+                .collect::<Vec<_>>()
+        );
+        assert!(merged.document.validate().is_empty());
+    }
+
+    #[test]
+    fn test_merge_conflicts() {
+        let base = module("Round trip", &["REQ-1", "REQ-2", "REQ-3"]);
+        let later = Timestamp::from(fixture::created() + Duration::days(1));
+
+        let mut ours = base.clone();
+        ours.update_spec_object("REQ-1", |o| o.set_value(text("Ours.")))
+            .expect("error");
+        ours.update_spec_object("REQ-3", |o| o.long_name = "Ours".to_string())
+            .expect("error");
+        ours.remove_spec_object("REQ-2", true).expect("error");
+
+        let mut theirs = base.clone();
+        theirs
+            .update_spec_object("REQ-1", |o| {
+                o.set_value(text("Theirs."));
+                o.last_change = later;
+            })
+            .expect("error");
+        theirs
+            .update_spec_object("REQ-3", |o| o.long_name = "Theirs".to_string())
+            .expect("error");
+        theirs
+            .update_spec_object("REQ-2", |o| o.long_name = "Second".to_string())
+            .expect("error");
+
+        let merged = merge(&base, &ours, &theirs);
+        let conflict = |identifier: &str, item: &str, resolution| Conflict {
+            kind: ElementKind::SpecObject,
+            identifier: identifier.to_string(),
+            item: item.to_string(),
+            resolution,
+        };
+        assert_eq!(
+            merged.conflicts,
+            vec![
+                conflict("REQ-1", TEXT, Side::Theirs),
+                conflict("REQ-3", "LONG-NAME", Side::Ours),
+                conflict("REQ-2", "REMOVED", Side::Theirs),
+            ]
+        );
+        let document = merged.document;
+        let object = document.get_spec_object("REQ-1").unwrap();
+        assert_eq!(object.values.get_value(TEXT), Some(text("Theirs.").into()));
+        assert_eq!(object.last_change, later);
+        assert_eq!(document.get_spec_object("REQ-3").unwrap().long_name, "Ours");
+        assert_eq!(
+            document.get_spec_object("REQ-2").unwrap().long_name,
+            "Second"
+        );
+        assert_eq!(
+            hierarchy(&document),
+            vec![(0, "H-REQ-1".to_string()), (0, "H-REQ-3".to_string())]
+        );
+    }
+}
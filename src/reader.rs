@@ -0,0 +1,316 @@
+// reqif-rs: Help library to write reqif files implemented in Rust.
+// Copyright (C) <2024>  INVAP S.E.
+//
+// This file is part of reqif-rs.
+//
+// reqif-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use anyhow::{anyhow, bail, Result};
+use std::fs::File;
+use std::io::{BufReader, Read};
+use xml::reader::XmlEvent as ReaderEvent;
+use yaserde::de::Deserializer;
+use yaserde::YaDeserialize;
+
+use crate::req_if::{
+    deserializer, DataTypeDefinition, DataTypeDefinitionBoolean, DataTypeDefinitionDate,
+    DataTypeDefinitionEnumeration, DataTypeDefinitionInteger, DataTypeDefinitionReal,
+    DataTypeDefinitionString, DataTypeDefinitionXHtml, RelationGroup, RelationGroupType,
+    ReqIfHeader, SpecObject, SpecObjectType, SpecRelation, SpecRelationType, SpecType,
+    Specification, SpecificationType,
+};
+
+/// An element of a ReqIF document, as produced by [`ReqIfReader`].
+#[derive(Debug, PartialEq)]
+pub enum ReqIfEvent {
+    Header(ReqIfHeader),
+    Datatype(DataTypeDefinition),
+    SpecType(SpecType),
+    SpecObject(SpecObject),
+    SpecRelation(SpecRelation),
+    Specification(Specification),
+    RelationGroup(RelationGroup),
+}
+
+/// Parent of each element the reader descends into.
+const CONTAINERS: [(&str, Option<&str>); 10] = [
+    ("REQ-IF", None),
+    ("THE-HEADER", Some("REQ-IF")),
+    ("CORE-CONTENT", Some("REQ-IF")),
+    ("REQ-IF-CONTENT", Some("CORE-CONTENT")),
+    ("DATATYPES", Some("REQ-IF-CONTENT")),
+    ("SPEC-TYPES", Some("REQ-IF-CONTENT")),
+    ("SPEC-OBJECTS", Some("REQ-IF-CONTENT")),
+    ("SPEC-RELATIONS", Some("REQ-IF-CONTENT")),
+    ("SPECIFICATIONS", Some("REQ-IF-CONTENT")),
+    ("SPEC-RELATION-GROUPS", Some("REQ-IF-CONTENT")),
+];
+
+fn read<T: YaDeserialize, R: Read>(reader: &mut Deserializer<R>) -> Result<T> {
+    let element = T::deserialize(reader).map_err(|e| anyhow!(e))?;
+    // The derived deserializers leave their closing tag to the caller.
+    reader.next_event().map_err(|e| anyhow!(e))?;
+    Ok(element)
+}
+
+/// Reads a ReqIF document one element at a time.
+///
+/// The header, each datatype, spec type, spec object, relation,
+/// specification and relation group are yielded as soon as they are parsed,
+/// so only one of them is held in memory at once. Elements outside the model,
+/// such as TOOL-EXTENSIONS, are skipped.
+pub struct ReqIfReader<R: Read> {
+    reader: Deserializer<R>,
+    /// Names of the containers the reader is in.
+    path: Vec<String>,
+    done: bool,
+}
+
+impl<R: Read> ReqIfReader<R> {
+    pub fn new(reader: R) -> Self {
+        ReqIfReader {
+            reader: deserializer(reader),
+            path: vec![],
+            done: false,
+        }
+    }
+
+    /// Reads the element starting at the next event, if it is one of the model.
+    fn read_element(&mut self, name: &str) -> Result<Option<ReqIfEvent>> {
+        let reader = &mut self.reader;
+        let parent = self.path.last().map(String::as_str).unwrap_or_default();
+        let event = match (parent, name) {
+            ("THE-HEADER", "REQ-IF-HEADER") => ReqIfEvent::Header(read(reader)?),
+            ("DATATYPES", "DATATYPE-DEFINITION-BOOLEAN") => {
+                ReqIfEvent::Datatype(read::<DataTypeDefinitionBoolean, R>(reader)?.into())
+            }
+            ("DATATYPES", "DATATYPE-DEFINITION-DATE") => {
+                ReqIfEvent::Datatype(read::<DataTypeDefinitionDate, R>(reader)?.into())
+            }
+            ("DATATYPES", "DATATYPE-DEFINITION-ENUMERATION") => {
+                ReqIfEvent::Datatype(read::<DataTypeDefinitionEnumeration, R>(reader)?.into())
+            }
+            ("DATATYPES", "DATATYPE-DEFINITION-INTEGER") => {
+                ReqIfEvent::Datatype(read::<DataTypeDefinitionInteger, R>(reader)?.into())
+            }
+            ("DATATYPES", "DATATYPE-DEFINITION-REAL") => {
+                ReqIfEvent::Datatype(read::<DataTypeDefinitionReal, R>(reader)?.into())
+            }
+            ("DATATYPES", "DATATYPE-DEFINITION-STRING") => {
+                ReqIfEvent::Datatype(read::<DataTypeDefinitionString, R>(reader)?.into())
+            }
+            ("DATATYPES", "DATATYPE-DEFINITION-XHTML") => {
+                ReqIfEvent::Datatype(read::<DataTypeDefinitionXHtml, R>(reader)?.into())
+            }
+            ("SPEC-TYPES", "SPEC-OBJECT-TYPE") => {
+                ReqIfEvent::SpecType(read::<SpecObjectType, R>(reader)?.into())
+            }
+            ("SPEC-TYPES", "SPECIFICATION-TYPE") => {
+                ReqIfEvent::SpecType(read::<SpecificationType, R>(reader)?.into())
+            }
+            ("SPEC-TYPES", "SPEC-RELATION-TYPE") => {
+                ReqIfEvent::SpecType(read::<SpecRelationType, R>(reader)?.into())
+            }
+            ("SPEC-TYPES", "RELATION-GROUP-TYPE") => {
+                ReqIfEvent::SpecType(read::<RelationGroupType, R>(reader)?.into())
+            }
+            ("SPEC-OBJECTS", "SPEC-OBJECT") => ReqIfEvent::SpecObject(read(reader)?),
+            ("SPEC-RELATIONS", "SPEC-RELATION") => ReqIfEvent::SpecRelation(read(reader)?),
+            ("SPECIFICATIONS", "SPECIFICATION") => ReqIfEvent::Specification(read(reader)?),
+            ("SPEC-RELATION-GROUPS", "RELATION-GROUP") => ReqIfEvent::RelationGroup(read(reader)?),
+            _ => return Ok(None),
+        };
+        Ok(Some(event))
+    }
+
+    fn next_event(&mut self) -> Result<Option<ReqIfEvent>> {
+        loop {
+            let name = match self.reader.peek().map_err(|e| anyhow!(e))? {
+                ReaderEvent::StartElement { name, .. } => name.local_name.clone(),
+                ReaderEvent::EndElement { .. } => {
+                    self.reader.next_event().map_err(|e| anyhow!(e))?;
+                    self.path.pop();
+                    continue;
+                }
+                ReaderEvent::EndDocument => return Ok(None),
+                _ => {
+                    self.reader.next_event().map_err(|e| anyhow!(e))?;
+                    continue;
+                }
+            };
+
+            if let Some(event) = self.read_element(&name)? {
+                return Ok(Some(event));
+            }
+            let parent = self.path.last().map(String::as_str);
+            let is_container = CONTAINERS.contains(&(name.as_str(), parent));
+            if self.path.is_empty() && !is_container {
+                bail!("Expected a REQ-IF document, found {}", name);
+            }
+            self.reader.next_event().map_err(|e| anyhow!(e))?;
+            if is_container {
+                self.path.push(name);
+            } else {
+                self.reader.skip_element(|_| {}).map_err(|e| anyhow!(e))?;
+            }
+        }
+    }
+}
+
+impl ReqIfReader<BufReader<File>> {
+    pub fn open(filename: &str) -> Result<Self> {
+        Ok(Self::new(BufReader::new(File::open(filename)?)))
+    }
+}
+
+impl<R: Read> Iterator for ReqIfReader<R> {
+    type Item = Result<ReqIfEvent>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let event = self.next_event();
+        if !matches!(event, Ok(Some(_))) {
+            self.done = true;
+        }
+        event.transpose()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ReqIfEvent, ReqIfReader};
+    use crate::clock;
+    use crate::fixture::{self, document};
+    use crate::req_if::{
+        DataTypeDefinitionBoolean, Object, SpecHierarchy, SpecObject, SpecRelation,
+        SpecRelationType,
+    };
+
+    #[test]
+    fn test_read_events() {
+        let mut reqif = document("Events");
+        let now = clock::now();
+        reqif
+            .add_datatype(DataTypeDefinitionBoolean::new(
+                "DT-BOOLEAN".to_string(),
+                now,
+                "Boolean".to_string(),
+            ))
+            .expect("error");
+        reqif
+            .add_spec_relation_type(SpecRelationType::new(
+                "SRT-REFINES".to_string(),
+                now,
+                "refines".to_string(),
+            ))
+            .expect("error");
+        let mut specification = reqif
+            .build_module_specification("SPEC-1".to_string(), now, "Module".to_string())
+            .expect("error");
+        for i in 0..2 {
+            let id = format!("REQ-{}", i);
+            fixture::add_requirement(&mut reqif, &id, &format!("The <b>system</b> {}.", i));
+            specification
+                .children
+                .add_spec_hierarchy(
+                    SpecHierarchy::new(format!("H-{}", i), now, Object::new(id)),
+                    0,
+                )
+                .expect("error");
+        }
+        reqif.add_specification(specification).expect("error");
+        reqif
+            .add_spec_relation(SpecRelation::new(
+                "REL-1".to_string(),
+                now,
+                String::new(),
+                "SRT-REFINES".to_string(),
+                "REQ-1".to_string(),
+                "REQ-0".to_string(),
+            ))
+            .expect("error");
+
+        let xml = reqif.to_xml_string().expect("error");
+        let events: Vec<ReqIfEvent> = ReqIfReader::new(xml.as_bytes())
+            .collect::<anyhow::Result<_>>()
+            .expect("error");
+
+        let content = &reqif.core_content.req_if_content;
+        assert_eq!(events.len(), 1 + 2 + 3 + 2 + 1 + 1);
+        assert_eq!(
+            events[0],
+            ReqIfEvent::Header(reqif.the_header.req_if_header)
+        );
+        assert!(matches!(&events[1], ReqIfEvent::Datatype(d) if d.identifier() == "DT-BOOLEAN"));
+        assert!(matches!(&events[5], ReqIfEvent::SpecType(t) if t.identifier() == "SRT-REFINES"));
+        assert_eq!(
+            events[6],
+            ReqIfEvent::SpecObject(
+                SpecObject::requirement(
+                    "REQ-0".to_string(),
+                    content.spec_object.get_spec_objects()[0].last_change,
+                    "REQ-0".to_string(),
+                    "The <b>system</b> 0.".to_string(),
+                    &content.spec_types,
+                )
+                .expect("error")
+            )
+        );
+        assert!(matches!(&events[8], ReqIfEvent::SpecRelation(r) if r.identifier == "REL-1"));
+        assert!(
+            matches!(&events[9], ReqIfEvent::Specification(s) if s.children.get_spec_hierarchy().len() == 2)
+        );
+    }
+
+    #[test]
+    fn test_skip_unknown_elements() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<REQ-IF xmlns="http://www.omg.org/spec/ReqIF/20110401/reqif.xsd">
+  <THE-HEADER>
+    <REQ-IF-HEADER IDENTIFIER="header">
+      <CREATION-TIME>2024-04-01T10:00:00.000+02:00</CREATION-TIME>
+      <REQ-IF-TOOL-ID>tool</REQ-IF-TOOL-ID>
+      <REQ-IF-VERSION>1.0</REQ-IF-VERSION>
+      <SOURCE-TOOL-ID>tool</SOURCE-TOOL-ID>
+      <TITLE>Unknown elements</TITLE>
+    </REQ-IF-HEADER>
+  </THE-HEADER>
+  <CORE-CONTENT>
+    <REQ-IF-CONTENT>
+      <SPEC-OBJECTS>
+        <SPEC-OBJECT IDENTIFIER="obj-1" LAST-CHANGE="2024-04-01T10:00:00.000+02:00">
+          <TYPE><SPEC-OBJECT-TYPE-REF>type</SPEC-OBJECT-TYPE-REF></TYPE>
+        </SPEC-OBJECT>
+      </SPEC-OBJECTS>
+    </REQ-IF-CONTENT>
+  </CORE-CONTENT>
+  <TOOL-EXTENSIONS>
+    <REQ-IF-TOOL-EXTENSION>
+      <SPEC-OBJECT IDENTIFIER="not-an-object"/>
+    </REQ-IF-TOOL-EXTENSION>
+  </TOOL-EXTENSIONS>
+</REQ-IF>"#;
+        let events: Vec<ReqIfEvent> = ReqIfReader::new(xml.as_bytes())
+            .collect::<anyhow::Result<_>>()
+            .expect("error");
+        assert_eq!(events.len(), 2);
+        assert!(matches!(&events[1], ReqIfEvent::SpecObject(o) if o.identifier == "obj-1"));
+
+        let mut reader = ReqIfReader::new("<OTHER/>".as_bytes());
+        assert!(reader.next().expect("error").is_err());
+        assert!(reader.next().is_none());
+    }
+}
@@ -16,22 +16,64 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 use anyhow::{bail, Result};
-use chrono::{DateTime, Local, SecondsFormat};
+use std::borrow::Cow;
+use std::collections::{HashSet, VecDeque};
 use std::fs::File;
-use std::io::Write;
-use yaserde_derive::YaSerialize;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::ops::{Deref, DerefMut};
+use xml::reader::{EventReader, ParserConfig};
+use yaserde::de::Deserializer;
+use yaserde::ser::Serializer;
+use yaserde_derive::{YaDeserialize, YaSerialize};
 
-fn get_default_last_change_date() -> String {
-    Local::now().to_rfc3339_opts(SecondsFormat::Millis, false)
+use crate::clock::{self, Timestamp};
+use crate::identifier::{self, is_valid_ncname, sanitize_ncname};
+use crate::validation::{self, Diagnostic, ValidationError};
+use crate::writer::{take_error, IoErrorKeeper, ReqIfWriter};
+use crate::xhtml::XHtmlValue;
+
+/// Unlike yaserde's default, keeps the spaces around inline XHTML markup
+/// such as `The <b>system</b>`.
+pub(crate) fn deserializer<R: Read>(reader: R) -> Deserializer<R> {
+    let config = ParserConfig::new()
+        .trim_whitespace(false)
+        .whitespace_to_characters(true)
+        .cdata_to_characters(true)
+        .ignore_comments(true)
+        .coalesce_characters(true);
+    Deserializer::new(EventReader::new_with_config(reader, config))
+}
+
+/// Default for optional attributes and elements that may be missing on import.
+fn empty_string() -> String {
+    String::new()
+}
+
+/// Sorts `items` by the identifier returned by `key`, keeping the relative
+/// order of items with the same identifier.
+fn sort_by_identifier<T>(items: &mut [T], key: impl Fn(&T) -> &str) {
+    items.sort_by(|a, b| key(a).cmp(key(b)));
+}
+
+fn is_sorted_by_identifier<T>(items: &[T], key: impl Fn(&T) -> &str) -> bool {
+    items.windows(2).all(|w| key(&w[0]) <= key(&w[1]))
+}
+
+/// References to `items` in the order of [`sort_by_identifier`].
+fn sorted_by_identifier<T>(items: &[T], key: impl Fn(&T) -> &str) -> Vec<&T> {
+    let mut sorted: Vec<&T> = items.iter().collect();
+    sorted.sort_by(|a, b| key(a).cmp(key(b)));
+    sorted
 }
 
-#[derive(Debug, PartialEq, YaSerialize)]
+#[derive(Debug, Clone, PartialEq, YaSerialize, YaDeserialize)]
+#[yaserde(namespace = "http://www.omg.org/spec/ReqIF/20110401/reqif.xsd")]
 pub struct ReqIfHeader {
     #[yaserde(rename = "IDENTIFIER", attribute)]
     pub identifier: String,
     #[yaserde(rename = "CREATION-TIME")]
-    pub creation_time: String,
-    #[yaserde(rename = "REPOSITORY-ID")]
+    pub creation_time: Timestamp,
+    #[yaserde(rename = "REPOSITORY-ID", default = "empty_string")]
     pub repository_id: String,
     #[yaserde(rename = "REQ-IF-TOOL-ID")]
     pub req_if_tool_id: String,
@@ -43,585 +85,5847 @@ pub struct ReqIfHeader {
     pub title: String,
 }
 
-#[derive(Debug, PartialEq, YaSerialize)]
+#[derive(Debug, Clone, PartialEq, YaSerialize, YaDeserialize)]
+#[yaserde(namespace = "http://www.omg.org/spec/ReqIF/20110401/reqif.xsd")]
 pub struct TheHeader {
     #[yaserde(rename = "REQ-IF-HEADER")]
     pub req_if_header: ReqIfHeader,
 }
 
-#[derive(Debug, PartialEq, YaSerialize)]
-pub struct DataType {
+#[derive(Debug, Clone, PartialEq, YaSerialize, YaDeserialize)]
+#[yaserde(namespace = "http://www.omg.org/spec/ReqIF/20110401/reqif.xsd")]
+pub struct DataTypeDefinitionBoolean {
     #[yaserde(attribute, rename = "IDENTIFIER")]
-    identifier: String,
+    pub identifier: String,
     #[yaserde(attribute, rename = "LAST-CHANGE")]
-    last_change: String,
-    #[yaserde(attribute, rename = "LONG-NAME")]
-    long_name: String,
-}
-
-#[derive(Debug, PartialEq, YaSerialize)]
-pub struct DataTypes {
-    #[yaserde(rename = "DATATYPE-DEFINITION-STRING")]
-    xhtml_definition: DataType,
+    pub last_change: Timestamp,
+    #[yaserde(attribute, rename = "LONG-NAME", default = "empty_string")]
+    pub long_name: String,
 }
 
-impl DataTypes {
-    pub fn new() -> Self {
-        DataTypes {
-            xhtml_definition: DataType {
-                identifier: "DATATYPE-DEFINITION-XHTML-IDENTIFIER".to_string(),
-                last_change: get_default_last_change_date(),
-                long_name: "XHTMLString".to_string(),
-            },
+impl DataTypeDefinitionBoolean {
+    pub fn new(identifier: String, last_change: Timestamp, long_name: String) -> Self {
+        DataTypeDefinitionBoolean {
+            identifier,
+            last_change,
+            long_name,
         }
     }
 }
 
-#[derive(Debug, PartialEq, YaSerialize)]
-pub struct SpecificationTypeModuleAttributes {
-    #[yaserde(rename = "ATTRIBUTE-DEFINITION-XHTML")]
-    reqif_name_attribute: AttributeDefinitionXHtml,
-}
-
-#[derive(Debug, PartialEq, YaSerialize)]
-pub struct SpecificationTypeModule {
+#[derive(Debug, Clone, PartialEq, YaSerialize, YaDeserialize)]
+#[yaserde(namespace = "http://www.omg.org/spec/ReqIF/20110401/reqif.xsd")]
+pub struct DataTypeDefinitionDate {
     #[yaserde(attribute, rename = "IDENTIFIER")]
-    identifier: String,
+    pub identifier: String,
     #[yaserde(attribute, rename = "LAST-CHANGE")]
-    last_change: String,
-    #[yaserde(attribute, rename = "LONG-NAME")]
-    long_name: String,
-    #[yaserde(rename = "SPEC-ATTRIBUTES")]
-    attributes: SpecificationTypeModuleAttributes,
-}
-
-#[derive(Debug, PartialEq, YaSerialize)]
-pub struct SpecTypes {
-    #[yaserde(rename = "SPEC-OBJECT-TYPE")]
-    spec_object_type_requirement: SpecObjectTypeRequirement,
-    #[yaserde(rename = "SPECIFICATION-TYPE")]
-    specification_type_module: SpecificationTypeModule,
+    pub last_change: Timestamp,
+    #[yaserde(attribute, rename = "LONG-NAME", default = "empty_string")]
+    pub long_name: String,
 }
 
-impl SpecTypes {
-    fn new(data_types: &DataTypes) -> Self {
-        SpecTypes {
-            specification_type_module: SpecificationTypeModule {
-                identifier: "MODULE-SPECIFICATION-TYPE-ID".to_string(),
-                last_change: get_default_last_change_date(),
-                long_name: "Module Type".to_string(),
-                attributes: SpecificationTypeModuleAttributes {
-                    reqif_name_attribute: AttributeDefinitionXHtml {
-                        identifier: "ATTRIBUTE-DEFINITION-XHTML-REQIF.NAME-ID".to_string(),
-                        last_change: get_default_last_change_date(),
-                        long_name: "ReqIF.Name".to_string(),
-                        type_ref: TypeDefinitionXHtmlRef {
-                            reference: data_types.xhtml_definition.identifier.clone(),
-                        },
-                    },
-                },
-            },
-            spec_object_type_requirement: SpecObjectTypeRequirement::new(data_types),
+impl DataTypeDefinitionDate {
+    pub fn new(identifier: String, last_change: Timestamp, long_name: String) -> Self {
+        DataTypeDefinitionDate {
+            identifier,
+            last_change,
+            long_name,
         }
     }
 }
 
-#[derive(Debug, PartialEq, YaSerialize)]
-pub struct TypeDefinitionXHtmlRef {
-    #[yaserde(rename = "DATATYPE-DEFINITION-XHTML-REF")]
-    reference: String,
-}
-
-#[derive(Debug, PartialEq, YaSerialize)]
-pub struct AttributeDefinitionXHtml {
-    #[yaserde(attribute, rename = "IDENTIFIER")]
-    identifier: String,
-    #[yaserde(attribute, rename = "LAST-CHANGE")]
-    last_change: String,
-    #[yaserde(attribute, rename = "LONG-NAME")]
-    long_name: String,
-    #[yaserde(rename = "TYPE")]
-    type_ref: TypeDefinitionXHtmlRef,
+#[derive(Debug, Clone, PartialEq, YaSerialize, YaDeserialize)]
+#[yaserde(namespace = "http://www.omg.org/spec/ReqIF/20110401/reqif.xsd")]
+pub struct EmbeddedValue {
+    /// Numeric key of the enumeration literal.
+    #[yaserde(attribute, rename = "KEY")]
+    pub key: i64,
+    /// Tool specific content, usually a color or a textual key.
+    #[yaserde(attribute, rename = "OTHER-CONTENT")]
+    pub other_content: String,
 }
 
-#[derive(Debug, PartialEq, YaSerialize)]
-pub struct RequirementSpecObjectTypeAttributes {
-    #[yaserde(rename = "ATTRIBUTE-DEFINITION-XHTML")]
-    text_attribute: AttributeDefinitionXHtml,
-    #[yaserde(rename = "ATTRIBUTE-DEFINITION-XHTML")]
-    id_attribute: AttributeDefinitionXHtml,
+#[derive(Debug, Clone, PartialEq, YaSerialize, YaDeserialize)]
+#[yaserde(namespace = "http://www.omg.org/spec/ReqIF/20110401/reqif.xsd")]
+pub struct EnumValueProperties {
+    #[yaserde(rename = "EMBEDDED-VALUE")]
+    pub embedded_value: EmbeddedValue,
 }
 
-#[derive(Debug, PartialEq, YaSerialize)]
-pub struct SpecObjectTypeRequirement {
+#[derive(Debug, Clone, PartialEq, YaSerialize, YaDeserialize)]
+#[yaserde(namespace = "http://www.omg.org/spec/ReqIF/20110401/reqif.xsd")]
+pub struct EnumValue {
     #[yaserde(attribute, rename = "IDENTIFIER")]
-    identifier: String,
+    pub identifier: String,
     #[yaserde(attribute, rename = "LAST-CHANGE")]
-    last_change: String,
-    #[yaserde(attribute, rename = "LONG-NAME")]
-    long_name: String,
-    #[yaserde(rename = "SPEC-ATTRIBUTES")]
-    attributes: RequirementSpecObjectTypeAttributes,
+    pub last_change: Timestamp,
+    #[yaserde(attribute, rename = "LONG-NAME", default = "empty_string")]
+    pub long_name: String,
+    #[yaserde(rename = "PROPERTIES")]
+    pub properties: EnumValueProperties,
 }
 
-impl SpecObjectTypeRequirement {
-    fn new(data_types: &DataTypes) -> Self {
-        SpecObjectTypeRequirement {
-            identifier: "SPEC-OBJEC-TYPE-REQ-TYPE-IDENTIFIER".to_string(),
-            long_name: "Requirement Type".to_string(),
-            last_change: get_default_last_change_date(),
-            attributes: RequirementSpecObjectTypeAttributes {
-                text_attribute: AttributeDefinitionXHtml {
-                    identifier: "ATTRIBUTE-DEFINITION-XHTML-REQIF.Text-ID".to_string(),
-                    last_change: get_default_last_change_date(),
-                    long_name: "ReqIF.Text".to_string(),
-                    type_ref: TypeDefinitionXHtmlRef {
-                        reference: data_types.xhtml_definition.identifier.clone(),
-                    },
-                },
-                id_attribute: AttributeDefinitionXHtml {
-                    identifier: "ATTRIBUTE-DEFINITION-XHTML-PUID-ID".to_string(),
-                    last_change: get_default_last_change_date(),
-                    long_name: "IE PUID".to_string(),
-                    type_ref: TypeDefinitionXHtmlRef {
-                        reference: data_types.xhtml_definition.identifier.clone(),
-                    },
-                },
+impl EnumValue {
+    pub fn new(
+        identifier: String,
+        last_change: Timestamp,
+        long_name: String,
+        key: i64,
+        other_content: String,
+    ) -> Self {
+        EnumValue {
+            identifier,
+            last_change,
+            long_name,
+            properties: EnumValueProperties {
+                embedded_value: EmbeddedValue { key, other_content },
             },
         }
     }
 }
 
-#[derive(Debug, PartialEq, YaSerialize)]
-pub struct SpecObjectType {
-    #[yaserde(rename = "SPEC-OBJECT-TYPE-REF")]
-    reference: String,
+#[derive(Debug, Clone, PartialEq, YaSerialize, YaDeserialize)]
+#[yaserde(namespace = "http://www.omg.org/spec/ReqIF/20110401/reqif.xsd")]
+pub struct SpecifiedValues {
+    #[yaserde(rename = "ENUM-VALUE")]
+    pub values: Vec<EnumValue>,
 }
 
-#[derive(Debug, PartialEq, YaSerialize)]
-pub struct AttributeValueXHtmlDefinition {
-    #[yaserde(rename = "ATTRIBUTE-DEFINITION-XHTML-REF")]
-    reference: String,
+#[derive(Debug, Clone, PartialEq, YaSerialize, YaDeserialize)]
+#[yaserde(namespace = "http://www.omg.org/spec/ReqIF/20110401/reqif.xsd")]
+pub struct DataTypeDefinitionEnumeration {
+    #[yaserde(attribute, rename = "IDENTIFIER")]
+    pub identifier: String,
+    #[yaserde(attribute, rename = "LAST-CHANGE")]
+    pub last_change: Timestamp,
+    #[yaserde(attribute, rename = "LONG-NAME", default = "empty_string")]
+    pub long_name: String,
+    #[yaserde(rename = "SPECIFIED-VALUES")]
+    pub specified_values: SpecifiedValues,
 }
 
-#[derive(Debug, PartialEq, YaSerialize)]
-pub struct XHtmlValue {
-    #[yaserde(rename = "xhtml:div")]
-    value: String,
-}
-#[derive(Debug, PartialEq, YaSerialize)]
-pub struct AttributeValueXHtml {
-    #[yaserde(rename = "THE-VALUE")]
-    the_value: XHtmlValue,
-    #[yaserde(rename = "DEFINITION")]
-    definition: AttributeValueXHtmlDefinition,
-}
+impl DataTypeDefinitionEnumeration {
+    pub fn new(
+        identifier: String,
+        last_change: Timestamp,
+        long_name: String,
+        values: Vec<EnumValue>,
+    ) -> Self {
+        DataTypeDefinitionEnumeration {
+            identifier,
+            last_change,
+            long_name,
+            specified_values: SpecifiedValues { values },
+        }
+    }
 
-#[derive(Debug, PartialEq, YaSerialize)]
-pub struct SpecObjectRequirementValues {
-    #[yaserde(rename = "ATTRIBUTE-VALUE-XHTML")]
-    req_id: AttributeValueXHtml,
-    #[yaserde(rename = "ATTRIBUTE-VALUE-XHTML")]
-    req_text: AttributeValueXHtml,
+    /// Looks up an enumeration literal by its LONG-NAME.
+    pub fn get_value_by_name(&self, long_name: &str) -> Option<&EnumValue> {
+        self.specified_values
+            .values
+            .iter()
+            .find(|value| value.long_name == long_name)
+    }
 }
 
-#[derive(Debug, PartialEq, YaSerialize)]
-pub struct SpecObjectRequirement {
+#[derive(Debug, Clone, PartialEq, YaSerialize, YaDeserialize)]
+#[yaserde(namespace = "http://www.omg.org/spec/ReqIF/20110401/reqif.xsd")]
+pub struct DataTypeDefinitionInteger {
     #[yaserde(attribute, rename = "IDENTIFIER")]
-    identifier: String,
+    pub identifier: String,
     #[yaserde(attribute, rename = "LAST-CHANGE")]
-    last_change: String,
-    #[yaserde(attribute, rename = "LONG-NAME")]
-    long_name: String,
-    #[yaserde(rename = "TYPE")]
-    spec_object_type: SpecObjectType,
-    #[yaserde(rename = "VALUES")]
-    values: SpecObjectRequirementValues,
+    pub last_change: Timestamp,
+    #[yaserde(attribute, rename = "LONG-NAME", default = "empty_string")]
+    pub long_name: String,
+    #[yaserde(attribute, rename = "MAX")]
+    pub max: i64,
+    #[yaserde(attribute, rename = "MIN")]
+    pub min: i64,
 }
 
-impl SpecObjectRequirement {
+impl DataTypeDefinitionInteger {
     pub fn new(
         identifier: String,
-        last_change: String,
+        last_change: Timestamp,
         long_name: String,
-        text: String,
-        spec_types: &SpecTypes,
+        min: i64,
+        max: i64,
     ) -> Self {
-        SpecObjectRequirement {
-            identifier: identifier.clone(),
+        DataTypeDefinitionInteger {
+            identifier,
             last_change,
             long_name,
-            spec_object_type: SpecObjectType {
-                reference: spec_types.spec_object_type_requirement.identifier.clone(),
-            },
-            values: SpecObjectRequirementValues {
-                req_id: AttributeValueXHtml {
-                    definition: AttributeValueXHtmlDefinition {
-                        reference: spec_types
-                            .spec_object_type_requirement
-                            .attributes
-                            .id_attribute
-                            .identifier
-                            .clone(),
-                    },
-                    the_value: XHtmlValue { value: identifier },
-                },
-                req_text: AttributeValueXHtml {
-                    definition: AttributeValueXHtmlDefinition {
-                        reference: spec_types
-                            .spec_object_type_requirement
-                            .attributes
-                            .text_attribute
-                            .identifier
-                            .clone(),
-                    },
-                    the_value: XHtmlValue { value: text },
-                },
-            },
+            max,
+            min,
         }
     }
 }
 
-#[derive(Debug, PartialEq, YaSerialize)]
-pub struct SpecObjects {
-    #[yaserde(rename = "SPEC-OBJECT")]
-    requirements: Vec<SpecObjectRequirement>,
+#[derive(Debug, Clone, PartialEq, YaSerialize, YaDeserialize)]
+#[yaserde(namespace = "http://www.omg.org/spec/ReqIF/20110401/reqif.xsd")]
+pub struct DataTypeDefinitionReal {
+    #[yaserde(attribute, rename = "IDENTIFIER")]
+    pub identifier: String,
+    #[yaserde(attribute, rename = "LAST-CHANGE")]
+    pub last_change: Timestamp,
+    #[yaserde(attribute, rename = "LONG-NAME", default = "empty_string")]
+    pub long_name: String,
+    /// Number of digits after the decimal point.
+    #[yaserde(attribute, rename = "ACCURACY")]
+    pub accuracy: i64,
+    #[yaserde(attribute, rename = "MAX")]
+    pub max: f64,
+    #[yaserde(attribute, rename = "MIN")]
+    pub min: f64,
 }
 
-#[derive(Debug, PartialEq, YaSerialize)]
-pub struct SpecificationRef {
-    #[yaserde(rename = "SPECIFICATION-TYPE-REF")]
-    pub spec_ref: String,
+impl DataTypeDefinitionReal {
+    pub fn new(
+        identifier: String,
+        last_change: Timestamp,
+        long_name: String,
+        min: f64,
+        max: f64,
+        accuracy: i64,
+    ) -> Self {
+        DataTypeDefinitionReal {
+            identifier,
+            last_change,
+            long_name,
+            accuracy,
+            max,
+            min,
+        }
+    }
 }
 
-#[derive(Debug, PartialEq, YaSerialize)]
-pub struct Object {
-    #[yaserde(rename = "SPEC-OBJECT-REF")]
-    pub object_ref: String,
+#[derive(Debug, Clone, PartialEq, YaSerialize, YaDeserialize)]
+#[yaserde(namespace = "http://www.omg.org/spec/ReqIF/20110401/reqif.xsd")]
+pub struct DataTypeDefinitionString {
+    #[yaserde(attribute, rename = "IDENTIFIER")]
+    pub identifier: String,
+    #[yaserde(attribute, rename = "LAST-CHANGE")]
+    pub last_change: Timestamp,
+    #[yaserde(attribute, rename = "LONG-NAME", default = "empty_string")]
+    pub long_name: String,
+    #[yaserde(attribute, rename = "MAX-LENGTH")]
+    pub max_length: i64,
 }
-impl Object {
-    pub fn new(object_ref: String) -> Object {
-        Object { object_ref }
+
+impl DataTypeDefinitionString {
+    pub fn new(
+        identifier: String,
+        last_change: Timestamp,
+        long_name: String,
+        max_length: i64,
+    ) -> Self {
+        DataTypeDefinitionString {
+            identifier,
+            last_change,
+            long_name,
+            max_length,
+        }
     }
 }
 
-#[derive(Debug, PartialEq, YaSerialize)]
-pub struct SpecHierarchy {
+#[derive(Debug, Clone, PartialEq, YaSerialize, YaDeserialize)]
+#[yaserde(namespace = "http://www.omg.org/spec/ReqIF/20110401/reqif.xsd")]
+pub struct DataTypeDefinitionXHtml {
     #[yaserde(attribute, rename = "IDENTIFIER")]
     pub identifier: String,
     #[yaserde(attribute, rename = "LAST-CHANGE")]
-    pub last_change: String,
-    #[yaserde(rename = "OBJECT")]
-    pub object: Object,
-    #[yaserde(rename = "CHILDREN")]
-    pub children: Option<Children>,
+    pub last_change: Timestamp,
+    #[yaserde(attribute, rename = "LONG-NAME", default = "empty_string")]
+    pub long_name: String,
 }
 
-impl SpecHierarchy {
-    pub fn new(identifier: String, last_change: String, object: Object) -> Self {
-        SpecHierarchy {
+impl DataTypeDefinitionXHtml {
+    pub fn new(identifier: String, last_change: Timestamp, long_name: String) -> Self {
+        DataTypeDefinitionXHtml {
             identifier,
             last_change,
-            object,
-            children: None,
+            long_name,
         }
     }
 }
 
-#[derive(Debug, PartialEq, YaSerialize)]
-pub struct Children {
-    #[yaserde(rename = "SPEC-HIERARCHY")]
-    spec_hierarchy: Vec<SpecHierarchy>,
+/// Any of the datatype definitions a [`DataTypes`] section can hold.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DataTypeDefinition {
+    Boolean(DataTypeDefinitionBoolean),
+    Date(DataTypeDefinitionDate),
+    Enumeration(DataTypeDefinitionEnumeration),
+    Integer(DataTypeDefinitionInteger),
+    Real(DataTypeDefinitionReal),
+    String(DataTypeDefinitionString),
+    XHtml(DataTypeDefinitionXHtml),
 }
 
-impl Children {
-    /// Adds the `spec_hierarchy` as the last children in the given `depth`.
-    /// a `depth` of 0 means add the spec as direct children.
-    /// # Panics:
-    /// Panic will occur in case that any intermediate level is missing.
-    pub fn add_spec_hierarchy(
-        &mut self,
-        spec_hierarchy: SpecHierarchy,
-        mut depth: i32,
-    ) -> Result<()> {
-        if depth == 0 {
-            self.spec_hierarchy.push(spec_hierarchy);
-        } else {
-            let spec = match self.spec_hierarchy.last_mut() {
-                Some(s) => s,
-                None => bail!("Missing spech hierarchy at level: {}", depth),
-            };
-
-            depth -= 1;
-            if spec.children.is_none() {
-                spec.children = Some(Children::new());
-            }
+impl DataTypeDefinition {
+    pub fn identifier(&self) -> &String {
+        match self {
+            DataTypeDefinition::Boolean(d) => &d.identifier,
+            DataTypeDefinition::Date(d) => &d.identifier,
+            DataTypeDefinition::Enumeration(d) => &d.identifier,
+            DataTypeDefinition::Integer(d) => &d.identifier,
+            DataTypeDefinition::Real(d) => &d.identifier,
+            DataTypeDefinition::String(d) => &d.identifier,
+            DataTypeDefinition::XHtml(d) => &d.identifier,
+        }
+    }
 
-            spec.children
-                .as_mut()
-                .unwrap()
-                .add_spec_hierarchy(spec_hierarchy, depth)?;
+    fn identifier_mut(&mut self) -> &mut String {
+        match self {
+            DataTypeDefinition::Boolean(d) => &mut d.identifier,
+            DataTypeDefinition::Date(d) => &mut d.identifier,
+            DataTypeDefinition::Enumeration(d) => &mut d.identifier,
+            DataTypeDefinition::Integer(d) => &mut d.identifier,
+            DataTypeDefinition::Real(d) => &mut d.identifier,
+            DataTypeDefinition::String(d) => &mut d.identifier,
+            DataTypeDefinition::XHtml(d) => &mut d.identifier,
         }
-        Ok(())
     }
 
-    pub fn new() -> Self {
-        Children {
-            spec_hierarchy: Vec::new(),
+    pub fn long_name(&self) -> &String {
+        match self {
+            DataTypeDefinition::Boolean(d) => &d.long_name,
+            DataTypeDefinition::Date(d) => &d.long_name,
+            DataTypeDefinition::Enumeration(d) => &d.long_name,
+            DataTypeDefinition::Integer(d) => &d.long_name,
+            DataTypeDefinition::Real(d) => &d.long_name,
+            DataTypeDefinition::String(d) => &d.long_name,
+            DataTypeDefinition::XHtml(d) => &d.long_name,
         }
     }
+}
 
-    pub fn get_spec_hierarchy(&self) -> &Vec<SpecHierarchy> {
-        &self.spec_hierarchy
+impl From<DataTypeDefinitionBoolean> for DataTypeDefinition {
+    fn from(definition: DataTypeDefinitionBoolean) -> Self {
+        DataTypeDefinition::Boolean(definition)
     }
 }
 
-#[derive(Debug, PartialEq, YaSerialize)]
-pub struct Specification {
-    #[yaserde(attribute, rename = "IDENTIFIER")]
-    pub identifier: String,
-    #[yaserde(attribute, rename = "LAST-CHANGE")]
-    pub last_change: String,
-    #[yaserde(attribute, rename = "LONG-NAME")]
-    pub long_name: String,
-    #[yaserde(rename = "TYPE")]
-    pub type_ref: SpecificationRef,
-    #[yaserde(rename = "CHILDREN")]
-    pub children: Children,
+impl From<DataTypeDefinitionDate> for DataTypeDefinition {
+    fn from(definition: DataTypeDefinitionDate) -> Self {
+        DataTypeDefinition::Date(definition)
+    }
 }
 
-#[derive(Debug, PartialEq, YaSerialize)]
-pub struct Specifications {
-    #[yaserde(rename = "SPECIFICATION")]
-    specifications: Vec<Specification>,
+impl From<DataTypeDefinitionEnumeration> for DataTypeDefinition {
+    fn from(definition: DataTypeDefinitionEnumeration) -> Self {
+        DataTypeDefinition::Enumeration(definition)
+    }
 }
 
-#[derive(Debug, PartialEq, YaSerialize)]
-pub struct ReqIfContent {
-    #[yaserde(rename = "DATATYPES")]
-    pub data_types: DataTypes,
-    #[yaserde(rename = "SPEC-TYPES")]
-    pub spec_types: SpecTypes,
-    #[yaserde(rename = "SPEC-OBJECTS")]
-    pub spec_object: SpecObjects,
-    #[yaserde(rename = "SPECIFICATIONS")]
-    pub specifications: Specifications,
+impl From<DataTypeDefinitionInteger> for DataTypeDefinition {
+    fn from(definition: DataTypeDefinitionInteger) -> Self {
+        DataTypeDefinition::Integer(definition)
+    }
 }
 
-#[derive(Debug, PartialEq, YaSerialize)]
-pub struct CoreContent {
-    #[yaserde(rename = "REQ-IF-CONTENT")]
-    pub req_if_content: ReqIfContent,
+impl From<DataTypeDefinitionReal> for DataTypeDefinition {
+    fn from(definition: DataTypeDefinitionReal) -> Self {
+        DataTypeDefinition::Real(definition)
+    }
 }
 
-impl CoreContent {
-    pub fn new() -> Self {
-        let data_types = DataTypes::new();
-        let spec_types = SpecTypes::new(&data_types);
-        CoreContent {
-            req_if_content: ReqIfContent {
-                spec_object: SpecObjects {
-                    requirements: vec![],
-                },
-                specifications: Specifications {
-                    specifications: vec![],
-                },
-                spec_types,
-                data_types,
-            },
-        }
+impl From<DataTypeDefinitionString> for DataTypeDefinition {
+    fn from(definition: DataTypeDefinitionString) -> Self {
+        DataTypeDefinition::String(definition)
     }
 }
 
-#[derive(Debug, PartialEq, YaSerialize)]
-#[yaserde(rename = "REQ-IF")]
-pub struct ReqIf {
-    #[yaserde(attribute)]
-    pub xmlns: String,
-    #[yaserde(rename = "xmlns:xhtml", attribute)]
-    pub xmlns_xhtml: String,
-    #[yaserde(rename = "THE-HEADER")]
-    pub the_header: TheHeader,
-    #[yaserde(rename = "CORE-CONTENT")]
-    pub core_content: CoreContent,
+impl From<DataTypeDefinitionXHtml> for DataTypeDefinition {
+    fn from(definition: DataTypeDefinitionXHtml) -> Self {
+        DataTypeDefinition::XHtml(definition)
+    }
 }
 
-impl ReqIf {
-    pub fn new(
-        identifier: String,
-        creation_time: DateTime<Local>,
-        repository_id: String,
-        req_if_tool_id: String,
-        source_tool_id: String,
-        title: String,
-    ) -> Self {
-        let req_if_header = ReqIfHeader {
-            identifier,
-            creation_time: creation_time.to_rfc3339_opts(SecondsFormat::Millis, false),
-            repository_id,
-            req_if_tool_id,
-            req_if_version: "1.0".to_string(),
-            source_tool_id,
-            title,
-        };
+const DEFAULT_XHTML_DATATYPE_IDENTIFIER: &str = "DATATYPE-DEFINITION-XHTML-IDENTIFIER";
 
-        let the_header = TheHeader { req_if_header };
-        let xmlns = "http://www.omg.org/spec/ReqIF/20110401/reqif.xsd".to_string();
-        let xmlns_xhtml = "http://www.w3.org/1999/xhtml".to_string();
+#[derive(Debug, Clone, PartialEq, YaSerialize, YaDeserialize)]
+#[yaserde(namespace = "http://www.omg.org/spec/ReqIF/20110401/reqif.xsd")]
+pub struct DataTypes {
+    #[yaserde(rename = "DATATYPE-DEFINITION-BOOLEAN")]
+    pub boolean_definitions: Vec<DataTypeDefinitionBoolean>,
+    #[yaserde(rename = "DATATYPE-DEFINITION-DATE")]
+    pub date_definitions: Vec<DataTypeDefinitionDate>,
+    #[yaserde(rename = "DATATYPE-DEFINITION-ENUMERATION")]
+    pub enumeration_definitions: Vec<DataTypeDefinitionEnumeration>,
+    #[yaserde(rename = "DATATYPE-DEFINITION-INTEGER")]
+    pub integer_definitions: Vec<DataTypeDefinitionInteger>,
+    #[yaserde(rename = "DATATYPE-DEFINITION-REAL")]
+    pub real_definitions: Vec<DataTypeDefinitionReal>,
+    #[yaserde(rename = "DATATYPE-DEFINITION-STRING")]
+    pub string_definitions: Vec<DataTypeDefinitionString>,
+    #[yaserde(rename = "DATATYPE-DEFINITION-XHTML")]
+    pub xhtml_definitions: Vec<DataTypeDefinitionXHtml>,
+}
 
-        ReqIf {
-            the_header,
-            xmlns,
-            xmlns_xhtml,
-            core_content: CoreContent::new(),
+impl DataTypes {
+    /// Creates the section with the XHTML datatype used by the default spec types.
+    pub fn new() -> Self {
+        Self::with_last_change(clock::now())
+    }
+
+    fn with_last_change(last_change: Timestamp) -> Self {
+        DataTypes {
+            boolean_definitions: vec![],
+            date_definitions: vec![],
+            enumeration_definitions: vec![],
+            integer_definitions: vec![],
+            real_definitions: vec![],
+            string_definitions: vec![],
+            xhtml_definitions: vec![DataTypeDefinitionXHtml::new(
+                DEFAULT_XHTML_DATATYPE_IDENTIFIER.to_string(),
+                last_change,
+                "XHTMLString".to_string(),
+            )],
         }
     }
 
-    pub fn add_requirement(&mut self, requirement: SpecObjectRequirement) {
-        self.core_content
-            .req_if_content
-            .spec_object
-            .requirements
-            .push(requirement);
+    /// Returns `true` if any datatype definition uses `identifier`.
+    pub fn contains(&self, identifier: &str) -> bool {
+        self.boolean_definitions
+            .iter()
+            .any(|d| d.identifier == identifier)
+            || self
+                .date_definitions
+                .iter()
+                .any(|d| d.identifier == identifier)
+            || self
+                .enumeration_definitions
+                .iter()
+                .any(|d| d.identifier == identifier)
+            || self
+                .integer_definitions
+                .iter()
+                .any(|d| d.identifier == identifier)
+            || self
+                .real_definitions
+                .iter()
+                .any(|d| d.identifier == identifier)
+            || self
+                .string_definitions
+                .iter()
+                .any(|d| d.identifier == identifier)
+            || self
+                .xhtml_definitions
+                .iter()
+                .any(|d| d.identifier == identifier)
     }
 
-    pub fn build_module_specification(
-        &mut self,
-        identifier: String,
-        last_change: String,
-        long_name: String,
-    ) -> Specification {
-        Specification {
-            identifier,
-            last_change,
-            long_name,
-            type_ref: SpecificationRef {
-                spec_ref: self.get_module_specification_type().clone(),
-            },
-            children: Children {
-                spec_hierarchy: vec![],
+    /// Adds a datatype definition of any kind.
+    /// Fails if its identifier is already used by another datatype.
+    pub fn add_definition(&mut self, definition: impl Into<DataTypeDefinition>) -> Result<()> {
+        let definition = definition.into();
+        if self.contains(definition.identifier()) {
+            bail!(
+                "Duplicated datatype identifier: {}",
+                definition.identifier()
+            );
+        }
+        match definition {
+            DataTypeDefinition::Boolean(d) => self.boolean_definitions.push(d),
+            DataTypeDefinition::Date(d) => self.date_definitions.push(d),
+            DataTypeDefinition::Enumeration(d) => self.enumeration_definitions.push(d),
+            DataTypeDefinition::Integer(d) => self.integer_definitions.push(d),
+            DataTypeDefinition::Real(d) => self.real_definitions.push(d),
+            DataTypeDefinition::String(d) => self.string_definitions.push(d),
+            DataTypeDefinition::XHtml(d) => self.xhtml_definitions.push(d),
+        }
+        Ok(())
+    }
+
+    pub fn get_enumeration_definition(
+        &self,
+        identifier: &str,
+    ) -> Option<&DataTypeDefinitionEnumeration> {
+        self.enumeration_definitions
+            .iter()
+            .find(|d| d.identifier == identifier)
+    }
+
+    /// Sorts the definitions of each kind by identifier. The order of the
+    /// values of an enumeration is meaningful and is kept.
+    fn sort(&mut self) {
+        sort_by_identifier(&mut self.boolean_definitions, |d| &d.identifier);
+        sort_by_identifier(&mut self.date_definitions, |d| &d.identifier);
+        sort_by_identifier(&mut self.enumeration_definitions, |d| &d.identifier);
+        sort_by_identifier(&mut self.integer_definitions, |d| &d.identifier);
+        sort_by_identifier(&mut self.real_definitions, |d| &d.identifier);
+        sort_by_identifier(&mut self.string_definitions, |d| &d.identifier);
+        sort_by_identifier(&mut self.xhtml_definitions, |d| &d.identifier);
+    }
+}
+
+impl Default for DataTypes {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn no_data_types() -> DataTypes {
+    DataTypes {
+        boolean_definitions: vec![],
+        date_definitions: vec![],
+        enumeration_definitions: vec![],
+        integer_definitions: vec![],
+        real_definitions: vec![],
+        string_definitions: vec![],
+        xhtml_definitions: vec![],
+    }
+}
+
+const DEFAULT_MODULE_TYPE_IDENTIFIER: &str = "MODULE-SPECIFICATION-TYPE-ID";
+
+/// A SPECIFICATION-TYPE: the set of attributes specifications of this type
+/// can hold.
+#[derive(Debug, Clone, PartialEq, YaSerialize, YaDeserialize)]
+#[yaserde(namespace = "http://www.omg.org/spec/ReqIF/20110401/reqif.xsd")]
+pub struct SpecificationType {
+    #[yaserde(attribute, rename = "IDENTIFIER")]
+    pub identifier: String,
+    #[yaserde(attribute, rename = "LAST-CHANGE")]
+    pub last_change: Timestamp,
+    #[yaserde(attribute, rename = "LONG-NAME", default = "empty_string")]
+    pub long_name: String,
+    #[yaserde(rename = "SPEC-ATTRIBUTES", default = "no_attributes")]
+    pub attributes: SpecAttributes,
+}
+
+impl SpecificationType {
+    pub fn new(identifier: String, last_change: Timestamp, long_name: String) -> Self {
+        SpecificationType {
+            identifier,
+            last_change,
+            long_name,
+            attributes: SpecAttributes::new(),
+        }
+    }
+
+    /// The "Module Type" with the "ReqIF.Name" attribute.
+    fn module(last_change: Timestamp) -> Self {
+        SpecificationType {
+            identifier: DEFAULT_MODULE_TYPE_IDENTIFIER.to_string(),
+            last_change,
+            long_name: "Module Type".to_string(),
+            attributes: SpecAttributes {
+                xhtml_attributes: vec![AttributeDefinitionXHtml::new(
+                    "ATTRIBUTE-DEFINITION-XHTML-REQIF.NAME-ID".to_string(),
+                    last_change,
+                    "ReqIF.Name".to_string(),
+                    DEFAULT_XHTML_DATATYPE_IDENTIFIER.to_string(),
+                )],
+                ..SpecAttributes::new()
+            },
+        }
+    }
+
+    /// Declares a new attribute for specifications of this type.
+    pub fn add_attribute(&mut self, definition: impl Into<AttributeDefinition>) -> Result<()> {
+        self.attributes.add_definition(definition)
+    }
+
+    /// Returns the "ReqIF.Name" attribute definition, if the type declares it.
+    pub fn name_attribute(&self) -> Option<&AttributeDefinitionXHtml> {
+        self.attributes
+            .xhtml_attributes
+            .iter()
+            .find(|attribute| attribute.long_name == "ReqIF.Name")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, YaSerialize, YaDeserialize)]
+#[yaserde(namespace = "http://www.omg.org/spec/ReqIF/20110401/reqif.xsd")]
+pub struct SpecTypes {
+    #[yaserde(rename = "SPEC-OBJECT-TYPE")]
+    spec_object_types: Vec<SpecObjectType>,
+    #[yaserde(rename = "SPECIFICATION-TYPE")]
+    specification_types: Vec<SpecificationType>,
+    #[yaserde(rename = "SPEC-RELATION-TYPE")]
+    spec_relation_types: Vec<SpecRelationType>,
+    #[yaserde(rename = "RELATION-GROUP-TYPE")]
+    relation_group_types: Vec<RelationGroupType>,
+}
+
+fn no_spec_types() -> SpecTypes {
+    SpecTypes {
+        spec_object_types: vec![],
+        specification_types: vec![],
+        spec_relation_types: vec![],
+        relation_group_types: vec![],
+    }
+}
+
+impl SpecTypes {
+    fn new(last_change: Timestamp) -> Self {
+        SpecTypes {
+            specification_types: vec![SpecificationType::module(last_change)],
+            spec_object_types: vec![SpecObjectType::requirement(last_change)],
+            spec_relation_types: vec![],
+            relation_group_types: vec![],
+        }
+    }
+
+    /// Returns `true` if any spec type uses `identifier`.
+    pub fn contains(&self, identifier: &str) -> bool {
+        self.specification_types
+            .iter()
+            .any(|t| t.identifier == identifier)
+            || self
+                .spec_object_types
+                .iter()
+                .any(|t| t.identifier == identifier)
+            || self
+                .spec_relation_types
+                .iter()
+                .any(|t| t.identifier == identifier)
+            || self
+                .relation_group_types
+                .iter()
+                .any(|t| t.identifier == identifier)
+    }
+
+    /// Registers a new spec object type.
+    /// Fails if its identifier is already used by another spec type.
+    pub fn add_spec_object_type(&mut self, spec_object_type: SpecObjectType) -> Result<()> {
+        if self.contains(&spec_object_type.identifier) {
+            bail!(
+                "Duplicated spec type identifier: {}",
+                spec_object_type.identifier
+            );
+        }
+        self.spec_object_types.push(spec_object_type);
+        Ok(())
+    }
+
+    /// Registers a new specification type.
+    /// Fails if its identifier is already used by another spec type.
+    pub fn add_specification_type(&mut self, specification_type: SpecificationType) -> Result<()> {
+        if self.contains(&specification_type.identifier) {
+            bail!(
+                "Duplicated spec type identifier: {}",
+                specification_type.identifier
+            );
+        }
+        self.specification_types.push(specification_type);
+        Ok(())
+    }
+
+    pub fn get_specification_types(&self) -> &Vec<SpecificationType> {
+        &self.specification_types
+    }
+
+    pub(crate) fn get_specification_types_mut(&mut self) -> &mut Vec<SpecificationType> {
+        &mut self.specification_types
+    }
+
+    pub fn get_specification_type(&self, identifier: &str) -> Option<&SpecificationType> {
+        self.specification_types
+            .iter()
+            .find(|t| t.identifier == identifier)
+    }
+
+    pub fn get_specification_type_mut(
+        &mut self,
+        identifier: &str,
+    ) -> Option<&mut SpecificationType> {
+        self.specification_types
+            .iter_mut()
+            .find(|t| t.identifier == identifier)
+    }
+
+    /// Returns the "Module Type" created along with a new document, or the
+    /// first specification type of a document that was read.
+    pub fn get_module_type(&self) -> Option<&SpecificationType> {
+        self.get_specification_type(DEFAULT_MODULE_TYPE_IDENTIFIER)
+            .or_else(|| self.specification_types.first())
+    }
+
+    pub fn get_spec_object_types(&self) -> &Vec<SpecObjectType> {
+        &self.spec_object_types
+    }
+
+    pub(crate) fn get_spec_object_types_mut(&mut self) -> &mut Vec<SpecObjectType> {
+        &mut self.spec_object_types
+    }
+
+    pub fn get_spec_object_type(&self, identifier: &str) -> Option<&SpecObjectType> {
+        self.spec_object_types
+            .iter()
+            .find(|t| t.identifier == identifier)
+    }
+
+    pub fn get_spec_object_type_mut(&mut self, identifier: &str) -> Option<&mut SpecObjectType> {
+        self.spec_object_types
+            .iter_mut()
+            .find(|t| t.identifier == identifier)
+    }
+
+    /// Registers a new spec relation type.
+    /// Fails if its identifier is already used by another spec type.
+    pub fn add_spec_relation_type(&mut self, spec_relation_type: SpecRelationType) -> Result<()> {
+        if self.contains(&spec_relation_type.identifier) {
+            bail!(
+                "Duplicated spec type identifier: {}",
+                spec_relation_type.identifier
+            );
+        }
+        self.spec_relation_types.push(spec_relation_type);
+        Ok(())
+    }
+
+    pub fn get_spec_relation_types(&self) -> &Vec<SpecRelationType> {
+        &self.spec_relation_types
+    }
+
+    pub(crate) fn get_spec_relation_types_mut(&mut self) -> &mut Vec<SpecRelationType> {
+        &mut self.spec_relation_types
+    }
+
+    pub fn get_spec_relation_type(&self, identifier: &str) -> Option<&SpecRelationType> {
+        self.spec_relation_types
+            .iter()
+            .find(|t| t.identifier == identifier)
+    }
+
+    pub fn get_spec_relation_type_mut(
+        &mut self,
+        identifier: &str,
+    ) -> Option<&mut SpecRelationType> {
+        self.spec_relation_types
+            .iter_mut()
+            .find(|t| t.identifier == identifier)
+    }
+
+    /// Registers a new relation group type.
+    /// Fails if its identifier is already used by another spec type.
+    pub fn add_relation_group_type(
+        &mut self,
+        relation_group_type: RelationGroupType,
+    ) -> Result<()> {
+        if self.contains(&relation_group_type.identifier) {
+            bail!(
+                "Duplicated spec type identifier: {}",
+                relation_group_type.identifier
+            );
+        }
+        self.relation_group_types.push(relation_group_type);
+        Ok(())
+    }
+
+    pub fn get_relation_group_types(&self) -> &Vec<RelationGroupType> {
+        &self.relation_group_types
+    }
+
+    pub(crate) fn get_relation_group_types_mut(&mut self) -> &mut Vec<RelationGroupType> {
+        &mut self.relation_group_types
+    }
+
+    pub fn get_relation_group_type(&self, identifier: &str) -> Option<&RelationGroupType> {
+        self.relation_group_types
+            .iter()
+            .find(|t| t.identifier == identifier)
+    }
+
+    pub fn get_relation_group_type_mut(
+        &mut self,
+        identifier: &str,
+    ) -> Option<&mut RelationGroupType> {
+        self.relation_group_types
+            .iter_mut()
+            .find(|t| t.identifier == identifier)
+    }
+
+    /// Returns the "Requirement Type" created along with a new document.
+    pub fn get_requirement_type(&self) -> Option<&SpecObjectType> {
+        self.get_spec_object_type(DEFAULT_REQUIREMENT_TYPE_IDENTIFIER)
+    }
+
+    /// Sorts the spec types, and the attribute definitions of each one, by
+    /// identifier.
+    fn sort(&mut self) {
+        sort_by_identifier(&mut self.spec_object_types, |t| &t.identifier);
+        sort_by_identifier(&mut self.spec_relation_types, |t| &t.identifier);
+        sort_by_identifier(&mut self.relation_group_types, |t| &t.identifier);
+        sort_by_identifier(&mut self.specification_types, |t| &t.identifier);
+        for specification_type in &mut self.specification_types {
+            specification_type.attributes.sort();
+        }
+        for spec_object_type in &mut self.spec_object_types {
+            spec_object_type.attributes.sort();
+        }
+        for spec_relation_type in &mut self.spec_relation_types {
+            spec_relation_type.attributes.sort();
+        }
+        for relation_group_type in &mut self.relation_group_types {
+            relation_group_type.attributes.sort();
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, YaSerialize, YaDeserialize)]
+#[yaserde(namespace = "http://www.omg.org/spec/ReqIF/20110401/reqif.xsd")]
+pub struct TypeDefinitionBooleanRef {
+    #[yaserde(rename = "DATATYPE-DEFINITION-BOOLEAN-REF")]
+    pub reference: String,
+}
+
+#[derive(Debug, Clone, PartialEq, YaSerialize, YaDeserialize)]
+#[yaserde(namespace = "http://www.omg.org/spec/ReqIF/20110401/reqif.xsd")]
+pub struct TypeDefinitionDateRef {
+    #[yaserde(rename = "DATATYPE-DEFINITION-DATE-REF")]
+    pub reference: String,
+}
+
+#[derive(Debug, Clone, PartialEq, YaSerialize, YaDeserialize)]
+#[yaserde(namespace = "http://www.omg.org/spec/ReqIF/20110401/reqif.xsd")]
+pub struct TypeDefinitionEnumerationRef {
+    #[yaserde(rename = "DATATYPE-DEFINITION-ENUMERATION-REF")]
+    pub reference: String,
+}
+
+#[derive(Debug, Clone, PartialEq, YaSerialize, YaDeserialize)]
+#[yaserde(namespace = "http://www.omg.org/spec/ReqIF/20110401/reqif.xsd")]
+pub struct TypeDefinitionIntegerRef {
+    #[yaserde(rename = "DATATYPE-DEFINITION-INTEGER-REF")]
+    pub reference: String,
+}
+
+#[derive(Debug, Clone, PartialEq, YaSerialize, YaDeserialize)]
+#[yaserde(namespace = "http://www.omg.org/spec/ReqIF/20110401/reqif.xsd")]
+pub struct TypeDefinitionRealRef {
+    #[yaserde(rename = "DATATYPE-DEFINITION-REAL-REF")]
+    pub reference: String,
+}
+
+#[derive(Debug, Clone, PartialEq, YaSerialize, YaDeserialize)]
+#[yaserde(namespace = "http://www.omg.org/spec/ReqIF/20110401/reqif.xsd")]
+pub struct TypeDefinitionStringRef {
+    #[yaserde(rename = "DATATYPE-DEFINITION-STRING-REF")]
+    pub reference: String,
+}
+
+#[derive(Debug, Clone, PartialEq, YaSerialize, YaDeserialize)]
+#[yaserde(namespace = "http://www.omg.org/spec/ReqIF/20110401/reqif.xsd")]
+pub struct TypeDefinitionXHtmlRef {
+    #[yaserde(rename = "DATATYPE-DEFINITION-XHTML-REF")]
+    pub reference: String,
+}
+
+#[derive(Debug, Clone, PartialEq, YaSerialize, YaDeserialize)]
+#[yaserde(namespace = "http://www.omg.org/spec/ReqIF/20110401/reqif.xsd")]
+pub struct AttributeDefinitionBoolean {
+    #[yaserde(attribute, rename = "IDENTIFIER")]
+    pub identifier: String,
+    #[yaserde(attribute, rename = "LAST-CHANGE")]
+    pub last_change: Timestamp,
+    #[yaserde(attribute, rename = "LONG-NAME", default = "empty_string")]
+    pub long_name: String,
+    #[yaserde(rename = "TYPE")]
+    pub type_ref: TypeDefinitionBooleanRef,
+}
+
+impl AttributeDefinitionBoolean {
+    /// Creates an attribute definition typed by the boolean datatype
+    /// identified by `data_type`.
+    pub fn new(
+        identifier: String,
+        last_change: Timestamp,
+        long_name: String,
+        data_type: String,
+    ) -> Self {
+        AttributeDefinitionBoolean {
+            identifier,
+            last_change,
+            long_name,
+            type_ref: TypeDefinitionBooleanRef {
+                reference: data_type,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, YaSerialize, YaDeserialize)]
+#[yaserde(namespace = "http://www.omg.org/spec/ReqIF/20110401/reqif.xsd")]
+pub struct AttributeDefinitionDate {
+    #[yaserde(attribute, rename = "IDENTIFIER")]
+    pub identifier: String,
+    #[yaserde(attribute, rename = "LAST-CHANGE")]
+    pub last_change: Timestamp,
+    #[yaserde(attribute, rename = "LONG-NAME", default = "empty_string")]
+    pub long_name: String,
+    #[yaserde(rename = "TYPE")]
+    pub type_ref: TypeDefinitionDateRef,
+}
+
+impl AttributeDefinitionDate {
+    /// Creates an attribute definition typed by the date datatype
+    /// identified by `data_type`.
+    pub fn new(
+        identifier: String,
+        last_change: Timestamp,
+        long_name: String,
+        data_type: String,
+    ) -> Self {
+        AttributeDefinitionDate {
+            identifier,
+            last_change,
+            long_name,
+            type_ref: TypeDefinitionDateRef {
+                reference: data_type,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, YaSerialize, YaDeserialize)]
+#[yaserde(namespace = "http://www.omg.org/spec/ReqIF/20110401/reqif.xsd")]
+pub struct AttributeDefinitionEnumeration {
+    #[yaserde(attribute, rename = "IDENTIFIER")]
+    pub identifier: String,
+    #[yaserde(attribute, rename = "LAST-CHANGE")]
+    pub last_change: Timestamp,
+    #[yaserde(attribute, rename = "LONG-NAME", default = "empty_string")]
+    pub long_name: String,
+    /// Whether more than one literal can be chosen.
+    #[yaserde(attribute, rename = "MULTI-VALUED")]
+    pub multi_valued: bool,
+    #[yaserde(rename = "TYPE")]
+    pub type_ref: TypeDefinitionEnumerationRef,
+}
+
+impl AttributeDefinitionEnumeration {
+    /// Creates an attribute definition typed by the enumeration datatype
+    /// identified by `data_type`.
+    pub fn new(
+        identifier: String,
+        last_change: Timestamp,
+        long_name: String,
+        multi_valued: bool,
+        data_type: String,
+    ) -> Self {
+        AttributeDefinitionEnumeration {
+            identifier,
+            last_change,
+            long_name,
+            multi_valued,
+            type_ref: TypeDefinitionEnumerationRef {
+                reference: data_type,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, YaSerialize, YaDeserialize)]
+#[yaserde(namespace = "http://www.omg.org/spec/ReqIF/20110401/reqif.xsd")]
+pub struct AttributeDefinitionInteger {
+    #[yaserde(attribute, rename = "IDENTIFIER")]
+    pub identifier: String,
+    #[yaserde(attribute, rename = "LAST-CHANGE")]
+    pub last_change: Timestamp,
+    #[yaserde(attribute, rename = "LONG-NAME", default = "empty_string")]
+    pub long_name: String,
+    #[yaserde(rename = "TYPE")]
+    pub type_ref: TypeDefinitionIntegerRef,
+}
+
+impl AttributeDefinitionInteger {
+    /// Creates an attribute definition typed by the integer datatype
+    /// identified by `data_type`.
+    pub fn new(
+        identifier: String,
+        last_change: Timestamp,
+        long_name: String,
+        data_type: String,
+    ) -> Self {
+        AttributeDefinitionInteger {
+            identifier,
+            last_change,
+            long_name,
+            type_ref: TypeDefinitionIntegerRef {
+                reference: data_type,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, YaSerialize, YaDeserialize)]
+#[yaserde(namespace = "http://www.omg.org/spec/ReqIF/20110401/reqif.xsd")]
+pub struct AttributeDefinitionReal {
+    #[yaserde(attribute, rename = "IDENTIFIER")]
+    pub identifier: String,
+    #[yaserde(attribute, rename = "LAST-CHANGE")]
+    pub last_change: Timestamp,
+    #[yaserde(attribute, rename = "LONG-NAME", default = "empty_string")]
+    pub long_name: String,
+    #[yaserde(rename = "TYPE")]
+    pub type_ref: TypeDefinitionRealRef,
+}
+
+impl AttributeDefinitionReal {
+    /// Creates an attribute definition typed by the real datatype
+    /// identified by `data_type`.
+    pub fn new(
+        identifier: String,
+        last_change: Timestamp,
+        long_name: String,
+        data_type: String,
+    ) -> Self {
+        AttributeDefinitionReal {
+            identifier,
+            last_change,
+            long_name,
+            type_ref: TypeDefinitionRealRef {
+                reference: data_type,
             },
         }
     }
+}
+
+#[derive(Debug, Clone, PartialEq, YaSerialize, YaDeserialize)]
+#[yaserde(namespace = "http://www.omg.org/spec/ReqIF/20110401/reqif.xsd")]
+pub struct AttributeDefinitionString {
+    #[yaserde(attribute, rename = "IDENTIFIER")]
+    pub identifier: String,
+    #[yaserde(attribute, rename = "LAST-CHANGE")]
+    pub last_change: Timestamp,
+    #[yaserde(attribute, rename = "LONG-NAME", default = "empty_string")]
+    pub long_name: String,
+    #[yaserde(rename = "TYPE")]
+    pub type_ref: TypeDefinitionStringRef,
+}
+
+impl AttributeDefinitionString {
+    /// Creates an attribute definition typed by the string datatype
+    /// identified by `data_type`.
+    pub fn new(
+        identifier: String,
+        last_change: Timestamp,
+        long_name: String,
+        data_type: String,
+    ) -> Self {
+        AttributeDefinitionString {
+            identifier,
+            last_change,
+            long_name,
+            type_ref: TypeDefinitionStringRef {
+                reference: data_type,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, YaSerialize, YaDeserialize)]
+#[yaserde(namespace = "http://www.omg.org/spec/ReqIF/20110401/reqif.xsd")]
+pub struct AttributeDefinitionXHtml {
+    #[yaserde(attribute, rename = "IDENTIFIER")]
+    pub identifier: String,
+    #[yaserde(attribute, rename = "LAST-CHANGE")]
+    pub last_change: Timestamp,
+    #[yaserde(attribute, rename = "LONG-NAME", default = "empty_string")]
+    pub long_name: String,
+    #[yaserde(rename = "TYPE")]
+    pub type_ref: TypeDefinitionXHtmlRef,
+}
+
+impl AttributeDefinitionXHtml {
+    /// Creates an attribute definition typed by the xhtml datatype
+    /// identified by `data_type`.
+    pub fn new(
+        identifier: String,
+        last_change: Timestamp,
+        long_name: String,
+        data_type: String,
+    ) -> Self {
+        AttributeDefinitionXHtml {
+            identifier,
+            last_change,
+            long_name,
+            type_ref: TypeDefinitionXHtmlRef {
+                reference: data_type,
+            },
+        }
+    }
+}
+
+/// Any of the attribute definitions a spec type can declare.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AttributeDefinition {
+    Boolean(AttributeDefinitionBoolean),
+    Date(AttributeDefinitionDate),
+    Enumeration(AttributeDefinitionEnumeration),
+    Integer(AttributeDefinitionInteger),
+    Real(AttributeDefinitionReal),
+    String(AttributeDefinitionString),
+    XHtml(AttributeDefinitionXHtml),
+}
+
+impl AttributeDefinition {
+    pub fn identifier(&self) -> &String {
+        match self {
+            AttributeDefinition::Boolean(d) => &d.identifier,
+            AttributeDefinition::Date(d) => &d.identifier,
+            AttributeDefinition::Enumeration(d) => &d.identifier,
+            AttributeDefinition::Integer(d) => &d.identifier,
+            AttributeDefinition::Real(d) => &d.identifier,
+            AttributeDefinition::String(d) => &d.identifier,
+            AttributeDefinition::XHtml(d) => &d.identifier,
+        }
+    }
+
+    fn identifier_mut(&mut self) -> &mut String {
+        match self {
+            AttributeDefinition::Boolean(d) => &mut d.identifier,
+            AttributeDefinition::Date(d) => &mut d.identifier,
+            AttributeDefinition::Enumeration(d) => &mut d.identifier,
+            AttributeDefinition::Integer(d) => &mut d.identifier,
+            AttributeDefinition::Real(d) => &mut d.identifier,
+            AttributeDefinition::String(d) => &mut d.identifier,
+            AttributeDefinition::XHtml(d) => &mut d.identifier,
+        }
+    }
+
+    pub fn long_name(&self) -> &String {
+        match self {
+            AttributeDefinition::Boolean(d) => &d.long_name,
+            AttributeDefinition::Date(d) => &d.long_name,
+            AttributeDefinition::Enumeration(d) => &d.long_name,
+            AttributeDefinition::Integer(d) => &d.long_name,
+            AttributeDefinition::Real(d) => &d.long_name,
+            AttributeDefinition::String(d) => &d.long_name,
+            AttributeDefinition::XHtml(d) => &d.long_name,
+        }
+    }
+}
+
+impl From<AttributeDefinitionBoolean> for AttributeDefinition {
+    fn from(definition: AttributeDefinitionBoolean) -> Self {
+        AttributeDefinition::Boolean(definition)
+    }
+}
+
+impl From<AttributeDefinitionDate> for AttributeDefinition {
+    fn from(definition: AttributeDefinitionDate) -> Self {
+        AttributeDefinition::Date(definition)
+    }
+}
+
+impl From<AttributeDefinitionEnumeration> for AttributeDefinition {
+    fn from(definition: AttributeDefinitionEnumeration) -> Self {
+        AttributeDefinition::Enumeration(definition)
+    }
+}
+
+impl From<AttributeDefinitionInteger> for AttributeDefinition {
+    fn from(definition: AttributeDefinitionInteger) -> Self {
+        AttributeDefinition::Integer(definition)
+    }
+}
+
+impl From<AttributeDefinitionReal> for AttributeDefinition {
+    fn from(definition: AttributeDefinitionReal) -> Self {
+        AttributeDefinition::Real(definition)
+    }
+}
+
+impl From<AttributeDefinitionString> for AttributeDefinition {
+    fn from(definition: AttributeDefinitionString) -> Self {
+        AttributeDefinition::String(definition)
+    }
+}
+
+impl From<AttributeDefinitionXHtml> for AttributeDefinition {
+    fn from(definition: AttributeDefinitionXHtml) -> Self {
+        AttributeDefinition::XHtml(definition)
+    }
+}
+
+/// The SPEC-ATTRIBUTES section of a spec type.
+#[derive(Debug, Clone, PartialEq, YaSerialize, YaDeserialize)]
+#[yaserde(namespace = "http://www.omg.org/spec/ReqIF/20110401/reqif.xsd")]
+pub struct SpecAttributes {
+    #[yaserde(rename = "ATTRIBUTE-DEFINITION-BOOLEAN")]
+    pub boolean_attributes: Vec<AttributeDefinitionBoolean>,
+    #[yaserde(rename = "ATTRIBUTE-DEFINITION-DATE")]
+    pub date_attributes: Vec<AttributeDefinitionDate>,
+    #[yaserde(rename = "ATTRIBUTE-DEFINITION-ENUMERATION")]
+    pub enumeration_attributes: Vec<AttributeDefinitionEnumeration>,
+    #[yaserde(rename = "ATTRIBUTE-DEFINITION-INTEGER")]
+    pub integer_attributes: Vec<AttributeDefinitionInteger>,
+    #[yaserde(rename = "ATTRIBUTE-DEFINITION-REAL")]
+    pub real_attributes: Vec<AttributeDefinitionReal>,
+    #[yaserde(rename = "ATTRIBUTE-DEFINITION-STRING")]
+    pub string_attributes: Vec<AttributeDefinitionString>,
+    #[yaserde(rename = "ATTRIBUTE-DEFINITION-XHTML")]
+    pub xhtml_attributes: Vec<AttributeDefinitionXHtml>,
+}
+
+impl SpecAttributes {
+    pub fn new() -> Self {
+        SpecAttributes {
+            boolean_attributes: vec![],
+            date_attributes: vec![],
+            enumeration_attributes: vec![],
+            integer_attributes: vec![],
+            real_attributes: vec![],
+            string_attributes: vec![],
+            xhtml_attributes: vec![],
+        }
+    }
+
+    /// Returns `true` if any attribute definition uses `identifier`.
+    pub fn contains(&self, identifier: &str) -> bool {
+        self.boolean_attributes
+            .iter()
+            .any(|d| d.identifier == identifier)
+            || self
+                .date_attributes
+                .iter()
+                .any(|d| d.identifier == identifier)
+            || self
+                .enumeration_attributes
+                .iter()
+                .any(|d| d.identifier == identifier)
+            || self
+                .integer_attributes
+                .iter()
+                .any(|d| d.identifier == identifier)
+            || self
+                .real_attributes
+                .iter()
+                .any(|d| d.identifier == identifier)
+            || self
+                .string_attributes
+                .iter()
+                .any(|d| d.identifier == identifier)
+            || self
+                .xhtml_attributes
+                .iter()
+                .any(|d| d.identifier == identifier)
+    }
+
+    /// Adds an attribute definition of any kind.
+    /// Fails if its identifier is already declared in this section.
+    pub fn add_definition(&mut self, definition: impl Into<AttributeDefinition>) -> Result<()> {
+        let definition = definition.into();
+        if self.contains(definition.identifier()) {
+            bail!(
+                "Duplicated attribute definition identifier: {}",
+                definition.identifier()
+            );
+        }
+        match definition {
+            AttributeDefinition::Boolean(d) => self.boolean_attributes.push(d),
+            AttributeDefinition::Date(d) => self.date_attributes.push(d),
+            AttributeDefinition::Enumeration(d) => self.enumeration_attributes.push(d),
+            AttributeDefinition::Integer(d) => self.integer_attributes.push(d),
+            AttributeDefinition::Real(d) => self.real_attributes.push(d),
+            AttributeDefinition::String(d) => self.string_attributes.push(d),
+            AttributeDefinition::XHtml(d) => self.xhtml_attributes.push(d),
+        }
+        Ok(())
+    }
+
+    /// (identifier, long name) of every definition.
+    fn identifiers_mut(&mut self) -> Vec<(&mut String, &String)> {
+        let mut identifiers = vec![];
+        identifiers.extend(
+            self.boolean_attributes
+                .iter_mut()
+                .map(|d| (&mut d.identifier, &d.long_name)),
+        );
+        identifiers.extend(
+            self.date_attributes
+                .iter_mut()
+                .map(|d| (&mut d.identifier, &d.long_name)),
+        );
+        identifiers.extend(
+            self.enumeration_attributes
+                .iter_mut()
+                .map(|d| (&mut d.identifier, &d.long_name)),
+        );
+        identifiers.extend(
+            self.integer_attributes
+                .iter_mut()
+                .map(|d| (&mut d.identifier, &d.long_name)),
+        );
+        identifiers.extend(
+            self.real_attributes
+                .iter_mut()
+                .map(|d| (&mut d.identifier, &d.long_name)),
+        );
+        identifiers.extend(
+            self.string_attributes
+                .iter_mut()
+                .map(|d| (&mut d.identifier, &d.long_name)),
+        );
+        identifiers.extend(
+            self.xhtml_attributes
+                .iter_mut()
+                .map(|d| (&mut d.identifier, &d.long_name)),
+        );
+        identifiers
+    }
+
+    /// Sorts the definitions of each kind by identifier.
+    fn sort(&mut self) {
+        sort_by_identifier(&mut self.boolean_attributes, |d| &d.identifier);
+        sort_by_identifier(&mut self.date_attributes, |d| &d.identifier);
+        sort_by_identifier(&mut self.enumeration_attributes, |d| &d.identifier);
+        sort_by_identifier(&mut self.integer_attributes, |d| &d.identifier);
+        sort_by_identifier(&mut self.real_attributes, |d| &d.identifier);
+        sort_by_identifier(&mut self.string_attributes, |d| &d.identifier);
+        sort_by_identifier(&mut self.xhtml_attributes, |d| &d.identifier);
+    }
+}
+
+impl Default for SpecAttributes {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn no_attributes() -> SpecAttributes {
+    SpecAttributes::new()
+}
+
+const DEFAULT_REQUIREMENT_TYPE_IDENTIFIER: &str = "SPEC-OBJEC-TYPE-REQ-TYPE-IDENTIFIER";
+
+/// A SPEC-OBJECT-TYPE: the set of attributes spec objects of this type can hold.
+#[derive(Debug, Clone, PartialEq, YaSerialize, YaDeserialize)]
+#[yaserde(namespace = "http://www.omg.org/spec/ReqIF/20110401/reqif.xsd")]
+pub struct SpecObjectType {
+    #[yaserde(attribute, rename = "IDENTIFIER")]
+    pub identifier: String,
+    #[yaserde(attribute, rename = "LAST-CHANGE")]
+    pub last_change: Timestamp,
+    #[yaserde(attribute, rename = "LONG-NAME", default = "empty_string")]
+    pub long_name: String,
+    #[yaserde(rename = "SPEC-ATTRIBUTES", default = "no_attributes")]
+    pub attributes: SpecAttributes,
+}
+
+impl SpecObjectType {
+    pub fn new(identifier: String, last_change: Timestamp, long_name: String) -> Self {
+        SpecObjectType {
+            identifier,
+            last_change,
+            long_name,
+            attributes: SpecAttributes::new(),
+        }
+    }
+
+    /// The "Requirement Type" with the "ReqIF.Text" and "IE PUID" attributes.
+    fn requirement(last_change: Timestamp) -> Self {
+        SpecObjectType {
+            identifier: DEFAULT_REQUIREMENT_TYPE_IDENTIFIER.to_string(),
+            long_name: "Requirement Type".to_string(),
+            last_change,
+            attributes: SpecAttributes {
+                xhtml_attributes: vec![
+                    AttributeDefinitionXHtml::new(
+                        "ATTRIBUTE-DEFINITION-XHTML-REQIF.Text-ID".to_string(),
+                        last_change,
+                        "ReqIF.Text".to_string(),
+                        DEFAULT_XHTML_DATATYPE_IDENTIFIER.to_string(),
+                    ),
+                    AttributeDefinitionXHtml::new(
+                        "ATTRIBUTE-DEFINITION-XHTML-PUID-ID".to_string(),
+                        last_change,
+                        "IE PUID".to_string(),
+                        DEFAULT_XHTML_DATATYPE_IDENTIFIER.to_string(),
+                    ),
+                ],
+                ..SpecAttributes::new()
+            },
+        }
+    }
+
+    /// Declares a new attribute for spec objects of this type.
+    pub fn add_attribute(&mut self, definition: impl Into<AttributeDefinition>) -> Result<()> {
+        self.attributes.add_definition(definition)
+    }
+
+    fn get_attribute(&self, long_name: &str) -> Option<&AttributeDefinitionXHtml> {
+        self.attributes
+            .xhtml_attributes
+            .iter()
+            .find(|attribute| attribute.long_name == long_name)
+    }
+
+    /// Returns the "ReqIF.Text" attribute definition, if the type declares it.
+    pub fn text_attribute(&self) -> Option<&AttributeDefinitionXHtml> {
+        self.get_attribute("ReqIF.Text")
+    }
+
+    /// Returns the "IE PUID" attribute definition, if the type declares it.
+    pub fn id_attribute(&self) -> Option<&AttributeDefinitionXHtml> {
+        self.get_attribute("IE PUID")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, YaSerialize, YaDeserialize)]
+#[yaserde(namespace = "http://www.omg.org/spec/ReqIF/20110401/reqif.xsd")]
+pub struct SpecObjectTypeRef {
+    #[yaserde(rename = "SPEC-OBJECT-TYPE-REF")]
+    pub reference: String,
+}
+
+/// A SPEC-RELATION-TYPE such as "satisfies" or "verifies", with the
+/// attributes its relations can hold.
+#[derive(Debug, Clone, PartialEq, YaSerialize, YaDeserialize)]
+#[yaserde(namespace = "http://www.omg.org/spec/ReqIF/20110401/reqif.xsd")]
+pub struct SpecRelationType {
+    #[yaserde(attribute, rename = "IDENTIFIER")]
+    pub identifier: String,
+    #[yaserde(attribute, rename = "LAST-CHANGE")]
+    pub last_change: Timestamp,
+    #[yaserde(attribute, rename = "LONG-NAME", default = "empty_string")]
+    pub long_name: String,
+    #[yaserde(rename = "SPEC-ATTRIBUTES", default = "no_attributes")]
+    pub attributes: SpecAttributes,
+}
+
+impl SpecRelationType {
+    pub fn new(identifier: String, last_change: Timestamp, long_name: String) -> Self {
+        SpecRelationType {
+            identifier,
+            last_change,
+            long_name,
+            attributes: SpecAttributes::new(),
+        }
+    }
+
+    /// Declares a new attribute for relations of this type.
+    pub fn add_attribute(&mut self, definition: impl Into<AttributeDefinition>) -> Result<()> {
+        self.attributes.add_definition(definition)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, YaSerialize, YaDeserialize)]
+#[yaserde(namespace = "http://www.omg.org/spec/ReqIF/20110401/reqif.xsd")]
+pub struct SpecRelationTypeRef {
+    #[yaserde(rename = "SPEC-RELATION-TYPE-REF")]
+    pub reference: String,
+}
+
+/// A RELATION-GROUP-TYPE, the kind of link module a relation group belongs to.
+#[derive(Debug, Clone, PartialEq, YaSerialize, YaDeserialize)]
+#[yaserde(namespace = "http://www.omg.org/spec/ReqIF/20110401/reqif.xsd")]
+pub struct RelationGroupType {
+    #[yaserde(attribute, rename = "IDENTIFIER")]
+    pub identifier: String,
+    #[yaserde(attribute, rename = "LAST-CHANGE")]
+    pub last_change: Timestamp,
+    #[yaserde(attribute, rename = "LONG-NAME", default = "empty_string")]
+    pub long_name: String,
+    #[yaserde(rename = "SPEC-ATTRIBUTES", default = "no_attributes")]
+    pub attributes: SpecAttributes,
+}
+
+impl RelationGroupType {
+    pub fn new(identifier: String, last_change: Timestamp, long_name: String) -> Self {
+        RelationGroupType {
+            identifier,
+            last_change,
+            long_name,
+            attributes: SpecAttributes::new(),
+        }
+    }
+
+    /// Declares a new attribute for relation groups of this type.
+    pub fn add_attribute(&mut self, definition: impl Into<AttributeDefinition>) -> Result<()> {
+        self.attributes.add_definition(definition)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, YaSerialize, YaDeserialize)]
+#[yaserde(namespace = "http://www.omg.org/spec/ReqIF/20110401/reqif.xsd")]
+pub struct RelationGroupTypeRef {
+    #[yaserde(rename = "RELATION-GROUP-TYPE-REF")]
+    pub reference: String,
+}
+
+/// Any of the spec types of the SPEC-TYPES section.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SpecType {
+    SpecObject(SpecObjectType),
+    Specification(SpecificationType),
+    SpecRelation(SpecRelationType),
+    RelationGroup(RelationGroupType),
+}
+
+impl SpecType {
+    pub fn identifier(&self) -> &String {
+        match self {
+            SpecType::SpecObject(t) => &t.identifier,
+            SpecType::Specification(t) => &t.identifier,
+            SpecType::SpecRelation(t) => &t.identifier,
+            SpecType::RelationGroup(t) => &t.identifier,
+        }
+    }
+}
+
+impl From<SpecObjectType> for SpecType {
+    fn from(spec_type: SpecObjectType) -> Self {
+        SpecType::SpecObject(spec_type)
+    }
+}
+
+impl From<SpecificationType> for SpecType {
+    fn from(spec_type: SpecificationType) -> Self {
+        SpecType::Specification(spec_type)
+    }
+}
+
+impl From<SpecRelationType> for SpecType {
+    fn from(spec_type: SpecRelationType) -> Self {
+        SpecType::SpecRelation(spec_type)
+    }
+}
+
+impl From<RelationGroupType> for SpecType {
+    fn from(spec_type: RelationGroupType) -> Self {
+        SpecType::RelationGroup(spec_type)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, YaSerialize, YaDeserialize)]
+#[yaserde(namespace = "http://www.omg.org/spec/ReqIF/20110401/reqif.xsd")]
+pub struct AttributeValueBooleanDefinition {
+    #[yaserde(rename = "ATTRIBUTE-DEFINITION-BOOLEAN-REF")]
+    pub reference: String,
+}
+
+#[derive(Debug, Clone, PartialEq, YaSerialize, YaDeserialize)]
+#[yaserde(namespace = "http://www.omg.org/spec/ReqIF/20110401/reqif.xsd")]
+pub struct AttributeValueBoolean {
+    #[yaserde(attribute, rename = "THE-VALUE")]
+    pub the_value: bool,
+    #[yaserde(rename = "DEFINITION")]
+    pub definition: AttributeValueBooleanDefinition,
+}
+
+impl AttributeValueBoolean {
+    /// Creates a value of the boolean attribute `definition`.
+    pub fn new(definition: String, the_value: bool) -> Self {
+        AttributeValueBoolean {
+            the_value,
+            definition: AttributeValueBooleanDefinition {
+                reference: definition,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, YaSerialize, YaDeserialize)]
+#[yaserde(namespace = "http://www.omg.org/spec/ReqIF/20110401/reqif.xsd")]
+pub struct AttributeValueDateDefinition {
+    #[yaserde(rename = "ATTRIBUTE-DEFINITION-DATE-REF")]
+    pub reference: String,
+}
+
+#[derive(Debug, Clone, PartialEq, YaSerialize, YaDeserialize)]
+#[yaserde(namespace = "http://www.omg.org/spec/ReqIF/20110401/reqif.xsd")]
+pub struct AttributeValueDate {
+    #[yaserde(attribute, rename = "THE-VALUE")]
+    pub the_value: Timestamp,
+    #[yaserde(rename = "DEFINITION")]
+    pub definition: AttributeValueDateDefinition,
+}
+
+impl AttributeValueDate {
+    /// Creates a value of the date attribute `definition`.
+    pub fn new(definition: String, the_value: Timestamp) -> Self {
+        AttributeValueDate {
+            the_value,
+            definition: AttributeValueDateDefinition {
+                reference: definition,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, YaSerialize, YaDeserialize)]
+#[yaserde(namespace = "http://www.omg.org/spec/ReqIF/20110401/reqif.xsd")]
+pub struct AttributeValueIntegerDefinition {
+    #[yaserde(rename = "ATTRIBUTE-DEFINITION-INTEGER-REF")]
+    pub reference: String,
+}
+
+#[derive(Debug, Clone, PartialEq, YaSerialize, YaDeserialize)]
+#[yaserde(namespace = "http://www.omg.org/spec/ReqIF/20110401/reqif.xsd")]
+pub struct AttributeValueInteger {
+    #[yaserde(attribute, rename = "THE-VALUE")]
+    pub the_value: i64,
+    #[yaserde(rename = "DEFINITION")]
+    pub definition: AttributeValueIntegerDefinition,
+}
+
+impl AttributeValueInteger {
+    /// Creates a value of the integer attribute `definition`.
+    pub fn new(definition: String, the_value: i64) -> Self {
+        AttributeValueInteger {
+            the_value,
+            definition: AttributeValueIntegerDefinition {
+                reference: definition,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, YaSerialize, YaDeserialize)]
+#[yaserde(namespace = "http://www.omg.org/spec/ReqIF/20110401/reqif.xsd")]
+pub struct AttributeValueRealDefinition {
+    #[yaserde(rename = "ATTRIBUTE-DEFINITION-REAL-REF")]
+    pub reference: String,
+}
+
+#[derive(Debug, Clone, PartialEq, YaSerialize, YaDeserialize)]
+#[yaserde(namespace = "http://www.omg.org/spec/ReqIF/20110401/reqif.xsd")]
+pub struct AttributeValueReal {
+    #[yaserde(attribute, rename = "THE-VALUE")]
+    pub the_value: f64,
+    #[yaserde(rename = "DEFINITION")]
+    pub definition: AttributeValueRealDefinition,
+}
+
+impl AttributeValueReal {
+    /// Creates a value of the real attribute `definition`.
+    pub fn new(definition: String, the_value: f64) -> Self {
+        AttributeValueReal {
+            the_value,
+            definition: AttributeValueRealDefinition {
+                reference: definition,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, YaSerialize, YaDeserialize)]
+#[yaserde(namespace = "http://www.omg.org/spec/ReqIF/20110401/reqif.xsd")]
+pub struct AttributeValueStringDefinition {
+    #[yaserde(rename = "ATTRIBUTE-DEFINITION-STRING-REF")]
+    pub reference: String,
+}
+
+#[derive(Debug, Clone, PartialEq, YaSerialize, YaDeserialize)]
+#[yaserde(namespace = "http://www.omg.org/spec/ReqIF/20110401/reqif.xsd")]
+pub struct AttributeValueString {
+    #[yaserde(attribute, rename = "THE-VALUE")]
+    pub the_value: String,
+    #[yaserde(rename = "DEFINITION")]
+    pub definition: AttributeValueStringDefinition,
+}
+
+impl AttributeValueString {
+    /// Creates a value of the string attribute `definition`.
+    pub fn new(definition: String, the_value: String) -> Self {
+        AttributeValueString {
+            the_value,
+            definition: AttributeValueStringDefinition {
+                reference: definition,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, YaSerialize, YaDeserialize)]
+#[yaserde(namespace = "http://www.omg.org/spec/ReqIF/20110401/reqif.xsd")]
+pub struct AttributeValueEnumerationDefinition {
+    #[yaserde(rename = "ATTRIBUTE-DEFINITION-ENUMERATION-REF")]
+    pub reference: String,
+}
+
+#[derive(Debug, Clone, PartialEq, YaSerialize, YaDeserialize)]
+#[yaserde(namespace = "http://www.omg.org/spec/ReqIF/20110401/reqif.xsd")]
+pub struct EnumValueRefs {
+    #[yaserde(rename = "ENUM-VALUE-REF")]
+    pub references: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, YaSerialize, YaDeserialize)]
+#[yaserde(namespace = "http://www.omg.org/spec/ReqIF/20110401/reqif.xsd")]
+pub struct AttributeValueEnumeration {
+    #[yaserde(rename = "DEFINITION")]
+    pub definition: AttributeValueEnumerationDefinition,
+    #[yaserde(rename = "VALUES")]
+    pub values: EnumValueRefs,
+}
+
+impl AttributeValueEnumeration {
+    /// Creates a value of the enumeration attribute `definition` choosing the
+    /// ENUM-VALUEs identified by `values`.
+    pub fn new(definition: String, values: Vec<String>) -> Self {
+        AttributeValueEnumeration {
+            definition: AttributeValueEnumerationDefinition {
+                reference: definition,
+            },
+            values: EnumValueRefs { references: values },
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, YaSerialize, YaDeserialize)]
+#[yaserde(namespace = "http://www.omg.org/spec/ReqIF/20110401/reqif.xsd")]
+pub struct AttributeValueXHtmlDefinition {
+    #[yaserde(rename = "ATTRIBUTE-DEFINITION-XHTML-REF")]
+    pub reference: String,
+}
+
+#[derive(Debug, Clone, PartialEq, YaSerialize, YaDeserialize)]
+#[yaserde(namespace = "http://www.omg.org/spec/ReqIF/20110401/reqif.xsd")]
+pub struct AttributeValueXHtml {
+    #[yaserde(attribute, rename = "IS-SIMPLIFIED", default = "not_simplified")]
+    pub is_simplified: bool,
+    #[yaserde(rename = "THE-ORIGINAL-VALUE")]
+    pub the_original_value: Option<XHtmlValue>,
+    #[yaserde(rename = "THE-VALUE")]
+    pub the_value: XHtmlValue,
+    #[yaserde(rename = "DEFINITION")]
+    pub definition: AttributeValueXHtmlDefinition,
+}
+
+fn not_simplified() -> bool {
+    false
+}
+
+impl AttributeValueXHtml {
+    /// Creates a plain text value of the XHTML attribute `definition`.
+    pub fn new(definition: String, the_value: String) -> Self {
+        Self::from_xhtml(definition, XHtmlValue::new(the_value))
+    }
+
+    /// Creates a value of the XHTML attribute `definition` with markup.
+    pub fn from_xhtml(definition: String, the_value: XHtmlValue) -> Self {
+        AttributeValueXHtml {
+            is_simplified: false,
+            the_original_value: None,
+            the_value,
+            definition: AttributeValueXHtmlDefinition {
+                reference: definition,
+            },
+        }
+    }
+
+    /// Keeps `original` as THE-ORIGINAL-VALUE and writes its plain text as a
+    /// simplified THE-VALUE for tools that can't render the markup.
+    pub fn with_plain_text_fallback(definition: String, original: XHtmlValue) -> Self {
+        AttributeValueXHtml {
+            is_simplified: true,
+            the_value: XHtmlValue::new(original.to_plain_text()),
+            the_original_value: Some(original),
+            definition: AttributeValueXHtmlDefinition {
+                reference: definition,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AttributeValue {
+    Boolean(AttributeValueBoolean),
+    Date(AttributeValueDate),
+    Enumeration(AttributeValueEnumeration),
+    Integer(AttributeValueInteger),
+    Real(AttributeValueReal),
+    String(AttributeValueString),
+    XHtml(AttributeValueXHtml),
+}
+
+impl AttributeValue {
+    /// Identifier of the attribute definition this value belongs to.
+    pub fn definition(&self) -> &String {
+        match self {
+            AttributeValue::Boolean(value) => &value.definition.reference,
+            AttributeValue::Date(value) => &value.definition.reference,
+            AttributeValue::Enumeration(value) => &value.definition.reference,
+            AttributeValue::Integer(value) => &value.definition.reference,
+            AttributeValue::Real(value) => &value.definition.reference,
+            AttributeValue::String(value) => &value.definition.reference,
+            AttributeValue::XHtml(value) => &value.definition.reference,
+        }
+    }
+}
+
+impl From<AttributeValueBoolean> for AttributeValue {
+    fn from(value: AttributeValueBoolean) -> Self {
+        AttributeValue::Boolean(value)
+    }
+}
+
+impl From<AttributeValueDate> for AttributeValue {
+    fn from(value: AttributeValueDate) -> Self {
+        AttributeValue::Date(value)
+    }
+}
+
+impl From<AttributeValueEnumeration> for AttributeValue {
+    fn from(value: AttributeValueEnumeration) -> Self {
+        AttributeValue::Enumeration(value)
+    }
+}
+
+impl From<AttributeValueInteger> for AttributeValue {
+    fn from(value: AttributeValueInteger) -> Self {
+        AttributeValue::Integer(value)
+    }
+}
+
+impl From<AttributeValueReal> for AttributeValue {
+    fn from(value: AttributeValueReal) -> Self {
+        AttributeValue::Real(value)
+    }
+}
+
+impl From<AttributeValueString> for AttributeValue {
+    fn from(value: AttributeValueString) -> Self {
+        AttributeValue::String(value)
+    }
+}
+
+impl From<AttributeValueXHtml> for AttributeValue {
+    fn from(value: AttributeValueXHtml) -> Self {
+        AttributeValue::XHtml(value)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, YaSerialize, YaDeserialize)]
+#[yaserde(namespace = "http://www.omg.org/spec/ReqIF/20110401/reqif.xsd")]
+pub struct AttributeValues {
+    #[yaserde(rename = "ATTRIBUTE-VALUE-BOOLEAN")]
+    pub boolean_values: Vec<AttributeValueBoolean>,
+    #[yaserde(rename = "ATTRIBUTE-VALUE-DATE")]
+    pub date_values: Vec<AttributeValueDate>,
+    #[yaserde(rename = "ATTRIBUTE-VALUE-ENUMERATION")]
+    pub enumeration_values: Vec<AttributeValueEnumeration>,
+    #[yaserde(rename = "ATTRIBUTE-VALUE-INTEGER")]
+    pub integer_values: Vec<AttributeValueInteger>,
+    #[yaserde(rename = "ATTRIBUTE-VALUE-REAL")]
+    pub real_values: Vec<AttributeValueReal>,
+    #[yaserde(rename = "ATTRIBUTE-VALUE-STRING")]
+    pub string_values: Vec<AttributeValueString>,
+    #[yaserde(rename = "ATTRIBUTE-VALUE-XHTML")]
+    pub xhtml_values: Vec<AttributeValueXHtml>,
+}
+
+impl AttributeValues {
+    pub fn new() -> Self {
+        AttributeValues {
+            boolean_values: vec![],
+            date_values: vec![],
+            enumeration_values: vec![],
+            integer_values: vec![],
+            real_values: vec![],
+            string_values: vec![],
+            xhtml_values: vec![],
+        }
+    }
+
+    /// Identifiers of the attribute definitions that have a value.
+    pub fn definitions(&self) -> Vec<&String> {
+        self.boolean_values
+            .iter()
+            .map(|v| &v.definition.reference)
+            .chain(self.date_values.iter().map(|v| &v.definition.reference))
+            .chain(
+                self.enumeration_values
+                    .iter()
+                    .map(|v| &v.definition.reference),
+            )
+            .chain(self.integer_values.iter().map(|v| &v.definition.reference))
+            .chain(self.real_values.iter().map(|v| &v.definition.reference))
+            .chain(self.string_values.iter().map(|v| &v.definition.reference))
+            .chain(self.xhtml_values.iter().map(|v| &v.definition.reference))
+            .collect()
+    }
+
+    /// Returns a copy of the value of the attribute `definition`, if any.
+    pub fn get_value(&self, definition: &str) -> Option<AttributeValue> {
+        let found = self
+            .boolean_values
+            .iter()
+            .find(|v| v.definition.reference == definition)
+            .map(|v| v.clone().into())
+            .or_else(|| {
+                self.date_values
+                    .iter()
+                    .find(|v| v.definition.reference == definition)
+                    .map(|v| v.clone().into())
+            })
+            .or_else(|| {
+                self.enumeration_values
+                    .iter()
+                    .find(|v| v.definition.reference == definition)
+                    .map(|v| v.clone().into())
+            })
+            .or_else(|| {
+                self.integer_values
+                    .iter()
+                    .find(|v| v.definition.reference == definition)
+                    .map(|v| v.clone().into())
+            })
+            .or_else(|| {
+                self.real_values
+                    .iter()
+                    .find(|v| v.definition.reference == definition)
+                    .map(|v| v.clone().into())
+            })
+            .or_else(|| {
+                self.string_values
+                    .iter()
+                    .find(|v| v.definition.reference == definition)
+                    .map(|v| v.clone().into())
+            });
+        found.or_else(|| {
+            self.xhtml_values
+                .iter()
+                .find(|v| v.definition.reference == definition)
+                .map(|v| v.clone().into())
+        })
+    }
+
+    /// Removes the value of the attribute `definition`, returning `true` if
+    /// there was one.
+    pub fn remove_value(&mut self, definition: &str) -> bool {
+        let count = self.definitions().len();
+        self.boolean_values
+            .retain(|v| v.definition.reference != definition);
+        self.date_values
+            .retain(|v| v.definition.reference != definition);
+        self.enumeration_values
+            .retain(|v| v.definition.reference != definition);
+        self.integer_values
+            .retain(|v| v.definition.reference != definition);
+        self.real_values
+            .retain(|v| v.definition.reference != definition);
+        self.string_values
+            .retain(|v| v.definition.reference != definition);
+        self.xhtml_values
+            .retain(|v| v.definition.reference != definition);
+        count != self.definitions().len()
+    }
+
+    /// Sets the value of an attribute, replacing any previous value for the
+    /// same attribute definition.
+    pub fn set_value(&mut self, value: impl Into<AttributeValue>) {
+        let value = value.into();
+        self.remove_value(value.definition());
+        match value {
+            AttributeValue::Boolean(value) => self.boolean_values.push(value),
+            AttributeValue::Date(value) => self.date_values.push(value),
+            AttributeValue::Enumeration(value) => self.enumeration_values.push(value),
+            AttributeValue::Integer(value) => self.integer_values.push(value),
+            AttributeValue::Real(value) => self.real_values.push(value),
+            AttributeValue::String(value) => self.string_values.push(value),
+            AttributeValue::XHtml(value) => self.xhtml_values.push(value),
+        }
+    }
+
+    /// Sorts the values of each kind by their attribute definition.
+    fn sort(&mut self) {
+        sort_by_identifier(&mut self.boolean_values, |v| &v.definition.reference);
+        sort_by_identifier(&mut self.date_values, |v| &v.definition.reference);
+        sort_by_identifier(&mut self.enumeration_values, |v| &v.definition.reference);
+        sort_by_identifier(&mut self.integer_values, |v| &v.definition.reference);
+        sort_by_identifier(&mut self.real_values, |v| &v.definition.reference);
+        sort_by_identifier(&mut self.string_values, |v| &v.definition.reference);
+        sort_by_identifier(&mut self.xhtml_values, |v| &v.definition.reference);
+    }
+
+    fn is_sorted(&self) -> bool {
+        is_sorted_by_identifier(&self.boolean_values, |v| &v.definition.reference)
+            && is_sorted_by_identifier(&self.date_values, |v| &v.definition.reference)
+            && is_sorted_by_identifier(&self.enumeration_values, |v| &v.definition.reference)
+            && is_sorted_by_identifier(&self.integer_values, |v| &v.definition.reference)
+            && is_sorted_by_identifier(&self.real_values, |v| &v.definition.reference)
+            && is_sorted_by_identifier(&self.string_values, |v| &v.definition.reference)
+            && is_sorted_by_identifier(&self.xhtml_values, |v| &v.definition.reference)
+    }
+}
+
+impl Default for AttributeValues {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn no_values() -> AttributeValues {
+    AttributeValues::new()
+}
+
+/// A SPEC-OBJECT of any user defined SPEC-OBJECT-TYPE.
+#[derive(Debug, Clone, PartialEq, YaSerialize, YaDeserialize)]
+#[yaserde(namespace = "http://www.omg.org/spec/ReqIF/20110401/reqif.xsd")]
+pub struct SpecObject {
+    #[yaserde(attribute, rename = "IDENTIFIER")]
+    pub identifier: String,
+    #[yaserde(attribute, rename = "LAST-CHANGE")]
+    pub last_change: Timestamp,
+    #[yaserde(attribute, rename = "LONG-NAME", default = "empty_string")]
+    pub long_name: String,
+    #[yaserde(rename = "TYPE")]
+    pub spec_object_type: SpecObjectTypeRef,
+    #[yaserde(rename = "VALUES", default = "no_values")]
+    pub values: AttributeValues,
+}
+
+/// The former name of [`SpecObject`].
+#[deprecated(note = "use `SpecObject`")]
+pub type SpecObjectRequirement = SpecObject;
+
+impl SpecObject {
+    /// Creates a spec object without values of the type identified by
+    /// `spec_object_type`.
+    pub fn new(
+        identifier: String,
+        last_change: Timestamp,
+        long_name: String,
+        spec_object_type: String,
+    ) -> Self {
+        SpecObject {
+            identifier,
+            last_change,
+            long_name,
+            spec_object_type: SpecObjectTypeRef {
+                reference: spec_object_type,
+            },
+            values: AttributeValues::new(),
+        }
+    }
+
+    /// Creates an object of the default "Requirement Type" with its
+    /// "IE PUID" and "ReqIF.Text" values set.
+    /// Fails if `spec_types` lacks that type or one of those attributes.
+    pub fn requirement(
+        identifier: String,
+        last_change: Timestamp,
+        long_name: String,
+        text: String,
+        spec_types: &SpecTypes,
+    ) -> Result<Self> {
+        let Some(object_type) = spec_types.get_requirement_type() else {
+            bail!("Missing requirement spec object type");
+        };
+        let (Some(id_attribute), Some(text_attribute)) =
+            (object_type.id_attribute(), object_type.text_attribute())
+        else {
+            bail!("Missing IE PUID or ReqIF.Text attribute in the requirement spec object type");
+        };
+        let mut requirement = SpecObject::new(
+            identifier.clone(),
+            last_change,
+            long_name,
+            DEFAULT_REQUIREMENT_TYPE_IDENTIFIER.to_string(),
+        );
+        requirement.values.xhtml_values = vec![
+            AttributeValueXHtml::new(id_attribute.identifier.clone(), identifier),
+            AttributeValueXHtml::new(text_attribute.identifier.clone(), text),
+        ];
+        Ok(requirement)
+    }
+
+    /// See [`AttributeValues::set_value`].
+    pub fn set_value(&mut self, value: impl Into<AttributeValue>) {
+        self.values.set_value(value)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, YaSerialize, YaDeserialize)]
+#[yaserde(namespace = "http://www.omg.org/spec/ReqIF/20110401/reqif.xsd")]
+pub struct SpecObjects {
+    #[yaserde(rename = "SPEC-OBJECT")]
+    spec_objects: Vec<SpecObject>,
+}
+
+fn no_spec_objects() -> SpecObjects {
+    SpecObjects {
+        spec_objects: vec![],
+    }
+}
+
+impl SpecObjects {
+    pub fn get_spec_objects(&self) -> &Vec<SpecObject> {
+        &self.spec_objects
+    }
+
+    pub(crate) fn get_spec_objects_mut(&mut self) -> &mut Vec<SpecObject> {
+        &mut self.spec_objects
+    }
+
+    pub fn get_spec_object(&self, identifier: &str) -> Option<&SpecObject> {
+        self.spec_objects
+            .iter()
+            .find(|o| o.identifier == identifier)
+    }
+
+    pub fn get_spec_object_mut(&mut self, identifier: &str) -> Option<&mut SpecObject> {
+        self.spec_objects
+            .iter_mut()
+            .find(|o| o.identifier == identifier)
+    }
+}
+
+/// A SPEC-RELATION linking a SOURCE spec object to a TARGET spec object.
+#[derive(Debug, Clone, PartialEq, YaSerialize, YaDeserialize)]
+#[yaserde(namespace = "http://www.omg.org/spec/ReqIF/20110401/reqif.xsd")]
+pub struct SpecRelation {
+    #[yaserde(attribute, rename = "IDENTIFIER")]
+    pub identifier: String,
+    #[yaserde(attribute, rename = "LAST-CHANGE")]
+    pub last_change: Timestamp,
+    #[yaserde(attribute, rename = "LONG-NAME", default = "empty_string")]
+    pub long_name: String,
+    #[yaserde(rename = "TYPE")]
+    pub spec_relation_type: SpecRelationTypeRef,
+    #[yaserde(rename = "SOURCE")]
+    pub source: Object,
+    #[yaserde(rename = "TARGET")]
+    pub target: Object,
+    #[yaserde(rename = "VALUES", default = "no_values")]
+    pub values: AttributeValues,
+}
+
+impl SpecRelation {
+    /// Creates a relation of the type identified by `spec_relation_type`
+    /// from the spec object `source` to the spec object `target`.
+    pub fn new(
+        identifier: String,
+        last_change: Timestamp,
+        long_name: String,
+        spec_relation_type: String,
+        source: String,
+        target: String,
+    ) -> Self {
+        SpecRelation {
+            identifier,
+            last_change,
+            long_name,
+            spec_relation_type: SpecRelationTypeRef {
+                reference: spec_relation_type,
+            },
+            source: Object::new(source),
+            target: Object::new(target),
+            values: AttributeValues::new(),
+        }
+    }
+
+    /// See [`AttributeValues::set_value`].
+    pub fn set_value(&mut self, value: impl Into<AttributeValue>) {
+        self.values.set_value(value)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, YaSerialize, YaDeserialize)]
+#[yaserde(namespace = "http://www.omg.org/spec/ReqIF/20110401/reqif.xsd")]
+pub struct SpecRelations {
+    #[yaserde(rename = "SPEC-RELATION")]
+    spec_relations: Vec<SpecRelation>,
+}
+
+impl SpecRelations {
+    pub fn new() -> Self {
+        SpecRelations {
+            spec_relations: vec![],
+        }
+    }
+
+    pub fn get_spec_relations(&self) -> &Vec<SpecRelation> {
+        &self.spec_relations
+    }
+
+    pub(crate) fn get_spec_relations_mut(&mut self) -> &mut Vec<SpecRelation> {
+        &mut self.spec_relations
+    }
+}
+
+impl Default for SpecRelations {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn no_spec_relations() -> SpecRelations {
+    SpecRelations::new()
+}
+
+#[derive(Debug, Clone, PartialEq, YaSerialize, YaDeserialize)]
+#[yaserde(namespace = "http://www.omg.org/spec/ReqIF/20110401/reqif.xsd")]
+pub struct RelationGroupSpecification {
+    #[yaserde(rename = "SPECIFICATION-REF")]
+    pub specification_ref: String,
+}
+
+#[derive(Debug, Clone, PartialEq, YaSerialize, YaDeserialize)]
+#[yaserde(namespace = "http://www.omg.org/spec/ReqIF/20110401/reqif.xsd")]
+pub struct SpecRelationRefs {
+    #[yaserde(rename = "SPEC-RELATION-REF")]
+    pub references: Vec<String>,
+}
+
+fn no_spec_relation_refs() -> SpecRelationRefs {
+    SpecRelationRefs { references: vec![] }
+}
+
+/// A RELATION-GROUP bundling the relations between two specifications.
+#[derive(Debug, Clone, PartialEq, YaSerialize, YaDeserialize)]
+#[yaserde(namespace = "http://www.omg.org/spec/ReqIF/20110401/reqif.xsd")]
+pub struct RelationGroup {
+    #[yaserde(attribute, rename = "IDENTIFIER")]
+    pub identifier: String,
+    #[yaserde(attribute, rename = "LAST-CHANGE")]
+    pub last_change: Timestamp,
+    #[yaserde(attribute, rename = "LONG-NAME", default = "empty_string")]
+    pub long_name: String,
+    #[yaserde(rename = "TYPE")]
+    pub relation_group_type: RelationGroupTypeRef,
+    #[yaserde(rename = "SOURCE-SPECIFICATION")]
+    pub source_specification: RelationGroupSpecification,
+    #[yaserde(rename = "TARGET-SPECIFICATION")]
+    pub target_specification: RelationGroupSpecification,
+    #[yaserde(rename = "SPEC-RELATIONS", default = "no_spec_relation_refs")]
+    pub spec_relations: SpecRelationRefs,
+}
+
+impl RelationGroup {
+    /// Creates an empty group of the type identified by `relation_group_type`
+    /// for the relations from the specification `source_specification` to
+    /// the specification `target_specification`.
+    pub fn new(
+        identifier: String,
+        last_change: Timestamp,
+        long_name: String,
+        relation_group_type: String,
+        source_specification: String,
+        target_specification: String,
+    ) -> Self {
+        RelationGroup {
+            identifier,
+            last_change,
+            long_name,
+            relation_group_type: RelationGroupTypeRef {
+                reference: relation_group_type,
+            },
+            source_specification: RelationGroupSpecification {
+                specification_ref: source_specification,
+            },
+            target_specification: RelationGroupSpecification {
+                specification_ref: target_specification,
+            },
+            spec_relations: no_spec_relation_refs(),
+        }
+    }
+
+    /// Adds the relation identified by `spec_relation` to the group.
+    pub fn add_spec_relation(&mut self, spec_relation: String) {
+        if !self.spec_relations.references.contains(&spec_relation) {
+            self.spec_relations.references.push(spec_relation);
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, YaSerialize, YaDeserialize)]
+#[yaserde(namespace = "http://www.omg.org/spec/ReqIF/20110401/reqif.xsd")]
+pub struct RelationGroups {
+    #[yaserde(rename = "RELATION-GROUP")]
+    relation_groups: Vec<RelationGroup>,
+}
+
+impl RelationGroups {
+    pub fn new() -> Self {
+        RelationGroups {
+            relation_groups: vec![],
+        }
+    }
+
+    pub fn get_relation_groups(&self) -> &Vec<RelationGroup> {
+        &self.relation_groups
+    }
+
+    pub(crate) fn get_relation_groups_mut(&mut self) -> &mut Vec<RelationGroup> {
+        &mut self.relation_groups
+    }
+}
+
+impl Default for RelationGroups {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn no_relation_groups() -> RelationGroups {
+    RelationGroups::new()
+}
+
+#[derive(Debug, Clone, PartialEq, YaSerialize, YaDeserialize)]
+#[yaserde(namespace = "http://www.omg.org/spec/ReqIF/20110401/reqif.xsd")]
+pub struct SpecificationRef {
+    #[yaserde(rename = "SPECIFICATION-TYPE-REF")]
+    pub spec_ref: String,
+}
+
+#[derive(Debug, Clone, PartialEq, YaSerialize, YaDeserialize)]
+#[yaserde(namespace = "http://www.omg.org/spec/ReqIF/20110401/reqif.xsd")]
+pub struct Object {
+    #[yaserde(rename = "SPEC-OBJECT-REF")]
+    pub object_ref: String,
+}
+impl Object {
+    pub fn new(object_ref: String) -> Object {
+        Object { object_ref }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, YaSerialize, YaDeserialize)]
+#[yaserde(namespace = "http://www.omg.org/spec/ReqIF/20110401/reqif.xsd")]
+pub struct SpecHierarchy {
+    #[yaserde(attribute, rename = "IDENTIFIER")]
+    pub identifier: String,
+    #[yaserde(attribute, rename = "LAST-CHANGE")]
+    pub last_change: Timestamp,
+    #[yaserde(rename = "OBJECT")]
+    pub object: Object,
+    #[yaserde(rename = "CHILDREN")]
+    pub children: Option<Children>,
+}
+
+impl SpecHierarchy {
+    pub fn new(identifier: String, last_change: Timestamp, object: Object) -> Self {
+        SpecHierarchy {
+            identifier,
+            last_change,
+            object,
+            children: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, YaSerialize, YaDeserialize)]
+#[yaserde(namespace = "http://www.omg.org/spec/ReqIF/20110401/reqif.xsd")]
+pub struct Children {
+    #[yaserde(rename = "SPEC-HIERARCHY")]
+    spec_hierarchy: Vec<SpecHierarchy>,
+}
+
+impl Children {
+    /// Adds the `spec_hierarchy` as the last children in the given `depth`.
+    /// a `depth` of 0 means add the spec as direct children.
+    /// # Panics:
+    /// Panic will occur in case that any intermediate level is missing.
+    ///
+    /// The insertion methods fail if an identifier of the inserted subtree is
+    /// not a valid NCName, see [`ReqIf::insert_spec_hierarchy`] to also check
+    /// that it is unused.
+    pub fn add_spec_hierarchy(
+        &mut self,
+        spec_hierarchy: SpecHierarchy,
+        mut depth: i32,
+    ) -> Result<()> {
+        check_spec_hierarchy(&spec_hierarchy)?;
+        if depth == 0 {
+            self.spec_hierarchy.push(spec_hierarchy);
+        } else {
+            let spec = match self.spec_hierarchy.last_mut() {
+                Some(s) => s,
+                None => bail!("Missing spech hierarchy at level: {}", depth),
+            };
+
+            depth -= 1;
+            if spec.children.is_none() {
+                spec.children = Some(Children::new());
+            }
+
+            spec.children
+                .as_mut()
+                .unwrap()
+                .add_spec_hierarchy(spec_hierarchy, depth)?;
+        }
+        Ok(())
+    }
+
+    pub fn new() -> Self {
+        Children {
+            spec_hierarchy: Vec::new(),
+        }
+    }
+
+    pub fn get_spec_hierarchy(&self) -> &Vec<SpecHierarchy> {
+        &self.spec_hierarchy
+    }
+
+    pub(crate) fn get_spec_hierarchy_mut(&mut self) -> &mut Vec<SpecHierarchy> {
+        &mut self.spec_hierarchy
+    }
+
+    /// Returns the index path of the first node identified by `identifier`,
+    /// e.g. `[2, 0, 3]` for the 4th child of the 1st child of the 3rd node.
+    pub fn find_path(&self, identifier: &str) -> Option<Vec<usize>> {
+        for (index, node) in self.spec_hierarchy.iter().enumerate() {
+            if node.identifier == identifier {
+                return Some(vec![index]);
+            }
+            if let Some(mut path) = node.children.as_ref().and_then(|c| c.find_path(identifier)) {
+                path.insert(0, index);
+                return Some(path);
+            }
+        }
+        None
+    }
+
+    /// Returns the node at the index `path`.
+    pub fn get_at(&self, path: &[usize]) -> Option<&SpecHierarchy> {
+        let (index, rest) = path.split_first()?;
+        let node = self.spec_hierarchy.get(*index)?;
+        if rest.is_empty() {
+            Some(node)
+        } else {
+            node.children.as_ref()?.get_at(rest)
+        }
+    }
+
+    pub fn get_at_mut(&mut self, path: &[usize]) -> Option<&mut SpecHierarchy> {
+        let (index, rest) = path.split_first()?;
+        let node = self.spec_hierarchy.get_mut(*index)?;
+        if rest.is_empty() {
+            Some(node)
+        } else {
+            node.children.as_mut()?.get_at_mut(rest)
+        }
+    }
+
+    /// Children of the node at `path`, the empty path being `self`.
+    fn children_at_mut(&mut self, path: &[usize]) -> Result<&mut Children> {
+        if path.is_empty() {
+            return Ok(self);
+        }
+        match self.get_at_mut(path) {
+            Some(node) => Ok(node.children.get_or_insert_with(Children::new)),
+            None => bail!("Missing spec hierarchy at path: {:?}", path),
+        }
+    }
+
+    /// Inserts `spec_hierarchy` so that it ends at the index `path`: the last
+    /// index is its position among the children of the node at the rest of
+    /// the path.
+    pub fn insert_at(&mut self, path: &[usize], spec_hierarchy: SpecHierarchy) -> Result<()> {
+        let (index, parent) = match path.split_last() {
+            Some(split) => split,
+            None => bail!("Empty spec hierarchy path"),
+        };
+        check_spec_hierarchy(&spec_hierarchy)?;
+        let children = self.children_at_mut(parent)?;
+        if *index > children.spec_hierarchy.len() {
+            bail!("Spec hierarchy position out of range: {:?}", path);
+        }
+        children.spec_hierarchy.insert(*index, spec_hierarchy);
+        Ok(())
+    }
+
+    /// Adds `spec_hierarchy` as the last child of the node identified by
+    /// `parent`.
+    pub fn add_child(&mut self, parent: &str, spec_hierarchy: SpecHierarchy) -> Result<()> {
+        check_spec_hierarchy(&spec_hierarchy)?;
+        let path = match self.find_path(parent) {
+            Some(path) => path,
+            None => bail!("Unknown spec hierarchy: {}", parent),
+        };
+        let children = self.children_at_mut(&path)?;
+        children.spec_hierarchy.push(spec_hierarchy);
+        Ok(())
+    }
+
+    /// Inserts `spec_hierarchy` at `index` among the children of the node
+    /// identified by `parent`, or of `self` when `parent` is `None`.
+    pub fn insert_child(
+        &mut self,
+        parent: Option<&str>,
+        index: usize,
+        spec_hierarchy: SpecHierarchy,
+    ) -> Result<()> {
+        let mut path = match parent {
+            Some(parent) => match self.find_path(parent) {
+                Some(path) => path,
+                None => bail!("Unknown spec hierarchy: {}", parent),
+            },
+            None => vec![],
+        };
+        path.push(index);
+        self.insert_at(&path, spec_hierarchy)
+    }
+
+    /// Removes the node at `path` along with its subtree.
+    pub fn remove_at(&mut self, path: &[usize]) -> Result<SpecHierarchy> {
+        let (index, parent) = match path.split_last() {
+            Some(split) => split,
+            None => bail!("Empty spec hierarchy path"),
+        };
+        if self.get_at(path).is_none() {
+            bail!("Missing spec hierarchy at path: {:?}", path);
+        }
+        if parent.is_empty() {
+            return Ok(self.spec_hierarchy.remove(*index));
+        }
+        let node = self.get_at_mut(parent).unwrap();
+        let children = node.children.as_mut().unwrap();
+        let removed = children.spec_hierarchy.remove(*index);
+        if children.spec_hierarchy.is_empty() {
+            node.children = None;
+        }
+        Ok(removed)
+    }
+
+    /// Removes the node identified by `identifier` along with its subtree.
+    pub fn remove(&mut self, identifier: &str) -> Option<SpecHierarchy> {
+        let path = self.find_path(identifier)?;
+        self.remove_at(&path).ok()
+    }
+
+    /// Moves the subtree of the node identified by `identifier` to `index`
+    /// among the children of `parent` once it has been removed, see
+    /// [`insert_child`](Self::insert_child).
+    /// Fails if `parent` is part of the moved subtree.
+    pub fn move_to(&mut self, identifier: &str, parent: Option<&str>, index: usize) -> Result<()> {
+        let from = match self.find_path(identifier) {
+            Some(path) => path,
+            None => bail!("Unknown spec hierarchy: {}", identifier),
+        };
+        if let Some(parent) = parent {
+            match self.find_path(parent) {
+                Some(to) if to.starts_with(&from) => {
+                    bail!("Can not move {} into its own subtree", identifier)
+                }
+                Some(_) => {}
+                None => bail!("Unknown spec hierarchy: {}", parent),
+            }
+        }
+        let node = self.remove_at(&from)?;
+        if let Err(e) = self.insert_child(parent, index, node.clone()) {
+            self.insert_at(&from, node)?;
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    /// Removes every node for which `predicate` is `true`, moving its
+    /// children up in its place. Returns whether any node was removed.
+    fn remove_where(&mut self, predicate: &mut impl FnMut(&SpecHierarchy) -> bool) -> bool {
+        let mut removed = false;
+        let nodes = std::mem::take(&mut self.spec_hierarchy);
+        for mut node in nodes {
+            if let Some(children) = &mut node.children {
+                removed |= children.remove_where(predicate);
+                if children.spec_hierarchy.is_empty() {
+                    node.children = None;
+                }
+            }
+            if predicate(&node) {
+                removed = true;
+                if let Some(children) = node.children {
+                    self.spec_hierarchy.extend(children.spec_hierarchy);
+                }
+            } else {
+                self.spec_hierarchy.push(node);
+            }
+        }
+        removed
+    }
+
+    /// Calls `f` on every node in pre-order.
+    fn for_each_mut(&mut self, f: &mut impl FnMut(&mut SpecHierarchy)) {
+        for node in &mut self.spec_hierarchy {
+            f(node);
+            if let Some(children) = &mut node.children {
+                children.for_each_mut(f);
+            }
+        }
+    }
+
+    /// Iterates over every node in pre-order, yielding its depth (0 for the
+    /// direct children), index path and the node itself.
+    pub fn iter_depth_first(&self) -> DepthFirst<'_> {
+        DepthFirst {
+            stack: self
+                .spec_hierarchy
+                .iter()
+                .enumerate()
+                .rev()
+                .map(|(index, node)| (vec![index], node))
+                .collect(),
+        }
+    }
+
+    /// Iterates over every node level by level, yielding the same items as
+    /// [`iter_depth_first`](Self::iter_depth_first).
+    pub fn iter_breadth_first(&self) -> BreadthFirst<'_> {
+        BreadthFirst {
+            queue: self
+                .spec_hierarchy
+                .iter()
+                .enumerate()
+                .map(|(index, node)| (vec![index], node))
+                .collect(),
+        }
+    }
+}
+
+/// Fails if the identifier of `spec_hierarchy`, or of one of its
+/// descendants, is not a valid NCName.
+fn check_spec_hierarchy(spec_hierarchy: &SpecHierarchy) -> Result<()> {
+    if !is_valid_ncname(&spec_hierarchy.identifier) {
+        bail!(
+            "Invalid identifier {:?}: not an NCName",
+            spec_hierarchy.identifier
+        );
+    }
+    for child in spec_hierarchy
+        .children
+        .iter()
+        .flat_map(|c| c.spec_hierarchy.iter())
+    {
+        check_spec_hierarchy(child)?;
+    }
+    Ok(())
+}
+
+/// (path, node) of the children of `node`, whose path is `path`.
+fn child_entries<'a>(
+    path: &[usize],
+    node: &'a SpecHierarchy,
+) -> impl DoubleEndedIterator<Item = (Vec<usize>, &'a SpecHierarchy)> + 'a {
+    let path = path.to_vec();
+    node.children
+        .iter()
+        .flat_map(|c| c.spec_hierarchy.iter().enumerate())
+        .map(move |(index, child)| {
+            let mut child_path = path.clone();
+            child_path.push(index);
+            (child_path, child)
+        })
+}
+
+/// Pre-order iterator over a hierarchy, see [`Children::iter_depth_first`].
+pub struct DepthFirst<'a> {
+    stack: Vec<(Vec<usize>, &'a SpecHierarchy)>,
+}
+
+impl<'a> Iterator for DepthFirst<'a> {
+    type Item = (usize, Vec<usize>, &'a SpecHierarchy);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (path, node) = self.stack.pop()?;
+        self.stack.extend(child_entries(&path, node).rev());
+        Some((path.len() - 1, path, node))
+    }
+}
+
+/// Level order iterator over a hierarchy, see [`Children::iter_breadth_first`].
+pub struct BreadthFirst<'a> {
+    queue: VecDeque<(Vec<usize>, &'a SpecHierarchy)>,
+}
+
+impl<'a> Iterator for BreadthFirst<'a> {
+    type Item = (usize, Vec<usize>, &'a SpecHierarchy);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (path, node) = self.queue.pop_front()?;
+        self.queue.extend(child_entries(&path, node));
+        Some((path.len() - 1, path, node))
+    }
+}
+
+impl Default for Children {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn no_children() -> Children {
+    Children::new()
+}
+
+#[derive(Debug, Clone, PartialEq, YaSerialize, YaDeserialize)]
+#[yaserde(namespace = "http://www.omg.org/spec/ReqIF/20110401/reqif.xsd")]
+pub struct Specification {
+    #[yaserde(attribute, rename = "IDENTIFIER")]
+    pub identifier: String,
+    #[yaserde(attribute, rename = "LAST-CHANGE")]
+    pub last_change: Timestamp,
+    #[yaserde(attribute, rename = "LONG-NAME", default = "empty_string")]
+    pub long_name: String,
+    #[yaserde(rename = "TYPE")]
+    pub type_ref: SpecificationRef,
+    #[yaserde(rename = "VALUES", default = "no_values")]
+    pub values: AttributeValues,
+    #[yaserde(rename = "CHILDREN", default = "no_children")]
+    pub children: Children,
+}
+
+impl Specification {
+    /// Creates an empty specification of the type identified by
+    /// `specification_type`.
+    pub fn new(
+        identifier: String,
+        last_change: Timestamp,
+        long_name: String,
+        specification_type: String,
+    ) -> Self {
+        Specification {
+            identifier,
+            last_change,
+            long_name,
+            type_ref: SpecificationRef {
+                spec_ref: specification_type,
+            },
+            values: AttributeValues::new(),
+            children: Children::new(),
+        }
+    }
+
+    /// See [`AttributeValues::set_value`].
+    pub fn set_value(&mut self, value: impl Into<AttributeValue>) {
+        self.values.set_value(value)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, YaSerialize, YaDeserialize)]
+#[yaserde(namespace = "http://www.omg.org/spec/ReqIF/20110401/reqif.xsd")]
+pub struct Specifications {
+    #[yaserde(rename = "SPECIFICATION")]
+    specifications: Vec<Specification>,
+}
+
+fn no_specifications() -> Specifications {
+    Specifications {
+        specifications: vec![],
+    }
+}
+
+impl Specifications {
+    pub fn get_specifications(&self) -> &Vec<Specification> {
+        &self.specifications
+    }
+
+    pub(crate) fn get_specifications_mut(&mut self) -> &mut Vec<Specification> {
+        &mut self.specifications
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, YaSerialize, YaDeserialize)]
+#[yaserde(namespace = "http://www.omg.org/spec/ReqIF/20110401/reqif.xsd")]
+pub struct ReqIfContent {
+    #[yaserde(rename = "DATATYPES", default = "no_data_types")]
+    pub data_types: DataTypes,
+    #[yaserde(rename = "SPEC-TYPES", default = "no_spec_types")]
+    pub spec_types: SpecTypes,
+    #[yaserde(rename = "SPEC-OBJECTS", default = "no_spec_objects")]
+    pub spec_object: SpecObjects,
+    #[yaserde(rename = "SPEC-RELATIONS", default = "no_spec_relations")]
+    pub spec_relations: SpecRelations,
+    #[yaserde(rename = "SPECIFICATIONS", default = "no_specifications")]
+    pub specifications: Specifications,
+    #[yaserde(rename = "SPEC-RELATION-GROUPS", default = "no_relation_groups")]
+    pub spec_relation_groups: RelationGroups,
+}
+
+#[derive(Debug, Clone, PartialEq, YaSerialize, YaDeserialize)]
+#[yaserde(namespace = "http://www.omg.org/spec/ReqIF/20110401/reqif.xsd")]
+pub struct CoreContent {
+    #[yaserde(rename = "REQ-IF-CONTENT")]
+    pub req_if_content: ReqIfContent,
+}
+
+impl CoreContent {
+    pub fn new() -> Self {
+        Self::with_last_change(clock::now())
+    }
+
+    fn with_last_change(last_change: Timestamp) -> Self {
+        let data_types = DataTypes::with_last_change(last_change);
+        let spec_types = SpecTypes::new(last_change);
+        CoreContent {
+            req_if_content: ReqIfContent {
+                spec_object: no_spec_objects(),
+                spec_relations: SpecRelations::new(),
+                specifications: no_specifications(),
+                spec_relation_groups: RelationGroups::new(),
+                spec_types,
+                data_types,
+            },
+        }
+    }
+}
+
+impl Default for CoreContent {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, YaSerialize, YaDeserialize)]
+#[yaserde(
+    rename = "REQ-IF",
+    namespace = "http://www.omg.org/spec/ReqIF/20110401/reqif.xsd",
+    namespace = "xhtml: http://www.w3.org/1999/xhtml"
+)]
+pub struct ReqIf {
+    #[yaserde(rename = "THE-HEADER")]
+    pub the_header: TheHeader,
+    #[yaserde(rename = "CORE-CONTENT")]
+    pub core_content: CoreContent,
+    #[yaserde(skip_serializing, default = "no_validation_on_write")]
+    validate_on_write: bool,
+    #[yaserde(skip_serializing, default = "not_deterministic")]
+    deterministic: bool,
+    #[yaserde(skip_serializing, default = "no_identifier_sanitizing")]
+    sanitize_identifiers: bool,
+    #[yaserde(skip_serializing, default = "no_change_tracking")]
+    track_changes: bool,
+    #[yaserde(skip_serializing, default = "no_change_tracking")]
+    track_container_changes: bool,
+    #[yaserde(skip_serializing, default = "no_identifiers")]
+    identifiers: IdentifierIndex,
+}
+
+fn not_deterministic() -> bool {
+    false
+}
+
+fn no_change_tracking() -> bool {
+    false
+}
+
+fn no_identifiers() -> IdentifierIndex {
+    IdentifierIndex(None)
+}
+
+/// The identifiers used in a [`ReqIf`], built the first time an element is
+/// added and kept up to date by the methods adding, removing or renaming
+/// elements. It is never read from or written to XML.
+#[derive(Debug, Clone)]
+struct IdentifierIndex(Option<HashSet<String>>);
+
+impl PartialEq for IdentifierIndex {
+    /// Documents are compared by content, whether or not they are indexed.
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl yaserde::YaSerialize for IdentifierIndex {
+    fn serialize<W: Write>(&self, _writer: &mut Serializer<W>) -> std::result::Result<(), String> {
+        Ok(())
+    }
+
+    fn serialize_attributes(
+        &self,
+        attributes: Vec<xml::attribute::OwnedAttribute>,
+        namespace: xml::namespace::Namespace,
+    ) -> std::result::Result<
+        (
+            Vec<xml::attribute::OwnedAttribute>,
+            xml::namespace::Namespace,
+        ),
+        String,
+    > {
+        Ok((attributes, namespace))
+    }
+}
+
+impl yaserde::YaDeserialize for IdentifierIndex {
+    fn deserialize<R: Read>(_reader: &mut Deserializer<R>) -> std::result::Result<Self, String> {
+        Ok(no_identifiers())
+    }
+}
+
+/// An element being modified by one of the `update_*` methods of [`ReqIf`].
+///
+/// It dereferences to the element, and counts as modified once it has been
+/// mutably dereferenced, even if the values written are unchanged.
+pub struct Tracked<'a, T> {
+    item: &'a mut T,
+    modified: bool,
+}
+
+impl<T> Deref for Tracked<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.item
+    }
+}
+
+impl<T> DerefMut for Tracked<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.modified = true;
+        self.item
+    }
+}
+
+/// Calls `f` on `item`, then sets the LAST-CHANGE returned by `last_change`
+/// to the current time if `track` is set, `f` modified the item and didn't
+/// set the LAST-CHANGE itself. Returns the result of `f` and whether the
+/// item was bumped.
+fn track_change<T, R>(
+    item: &mut T,
+    last_change: fn(&mut T) -> &mut Timestamp,
+    track: bool,
+    f: impl FnOnce(&mut Tracked<T>) -> R,
+) -> (R, bool) {
+    let before = *last_change(item);
+    let mut tracked = Tracked {
+        item,
+        modified: false,
+    };
+    let result = f(&mut tracked);
+    let bumped = track && tracked.modified && *last_change(tracked.item) == before;
+    if bumped {
+        *last_change(tracked.item) = clock::now();
+    }
+    (result, bumped)
+}
+
+fn no_identifier_sanitizing() -> bool {
+    false
+}
+
+fn no_validation_on_write() -> bool {
+    false
+}
+
+impl ReqIf {
+    pub fn new(
+        identifier: String,
+        creation_time: impl Into<Timestamp>,
+        repository_id: String,
+        req_if_tool_id: String,
+        source_tool_id: String,
+        title: String,
+    ) -> Self {
+        Self::with_core_content(
+            identifier,
+            creation_time.into(),
+            repository_id,
+            req_if_tool_id,
+            source_tool_id,
+            title,
+            CoreContent::new(),
+        )
+    }
+
+    fn with_core_content(
+        identifier: String,
+        creation_time: Timestamp,
+        repository_id: String,
+        req_if_tool_id: String,
+        source_tool_id: String,
+        title: String,
+        core_content: CoreContent,
+    ) -> Self {
+        let req_if_header = ReqIfHeader {
+            identifier,
+            creation_time,
+            repository_id,
+            req_if_tool_id,
+            req_if_version: "1.0".to_string(),
+            source_tool_id,
+            title,
+        };
+
+        let the_header = TheHeader { req_if_header };
+
+        ReqIf {
+            the_header,
+            core_content,
+            validate_on_write: false,
+            deterministic: false,
+            sanitize_identifiers: false,
+            track_changes: false,
+            track_container_changes: false,
+            identifiers: no_identifiers(),
+        }
+    }
+
+    /// Creates a document whose output only depends on its content.
+    ///
+    /// `creation_time` is used as the LAST-CHANGE of the generated datatype
+    /// and spec types instead of the current time, and elements are written
+    /// sorted as by [`sort`](Self::sort).
+    pub fn new_deterministic(
+        identifier: String,
+        creation_time: impl Into<Timestamp>,
+        repository_id: String,
+        req_if_tool_id: String,
+        source_tool_id: String,
+        title: String,
+    ) -> Self {
+        let creation_time = creation_time.into();
+        let mut req_if = Self::with_core_content(
+            identifier,
+            creation_time,
+            repository_id,
+            req_if_tool_id,
+            source_tool_id,
+            title,
+            CoreContent::with_last_change(creation_time),
+        );
+        req_if.deterministic = true;
+        req_if
+    }
+
+    /// When enabled, the document is written as by [`sort`](Self::sort)
+    /// without modifying it.
+    pub fn set_deterministic(&mut self, deterministic: bool) {
+        self.deterministic = deterministic;
+    }
+
+    /// Sorts datatypes, spec types, attribute definitions, spec objects,
+    /// relations, relation groups and attribute values by identifier.
+    ///
+    /// Specifications and their hierarchies are kept in document order.
+    pub fn sort(&mut self) {
+        let content = &mut self.core_content.req_if_content;
+        content.data_types.sort();
+        content.spec_types.sort();
+        sort_by_identifier(&mut content.spec_object.spec_objects, |o| &o.identifier);
+        for spec_object in &mut content.spec_object.spec_objects {
+            spec_object.values.sort();
+        }
+        sort_by_identifier(&mut content.spec_relations.spec_relations, |r| {
+            &r.identifier
+        });
+        for spec_relation in &mut content.spec_relations.spec_relations {
+            spec_relation.values.sort();
+        }
+        sort_by_identifier(&mut content.spec_relation_groups.relation_groups, |g| {
+            &g.identifier
+        });
+        for relation_group in &mut content.spec_relation_groups.relation_groups {
+            relation_group.spec_relations.references.sort();
+        }
+        for specification in &mut content.specifications.specifications {
+            specification.values.sort();
+        }
+    }
+
+    /// Checks the referential integrity of the document: duplicated
+    /// identifiers, references to missing elements and values of attributes
+    /// that don't belong to the element's type.
+    pub fn validate(&self) -> Vec<Diagnostic> {
+        validation::validate(self)
+    }
+
+    /// When enabled, writing a document that doesn't [`validate`](Self::validate)
+    /// fails with a [`ValidationError`].
+    pub fn set_validate_on_write(&mut self, validate_on_write: bool) {
+        self.validate_on_write = validate_on_write;
+    }
+
+    /// When enabled, identifiers that are not valid NCNames are sanitized
+    /// with [`sanitize_ncname`] instead of rejected by the `add_*` methods.
+    pub fn set_sanitize_identifiers(&mut self, sanitize_identifiers: bool) {
+        self.sanitize_identifiers = sanitize_identifiers;
+    }
+
+    /// When enabled, the `update_*` methods, [`rename_spec_object`](Self::rename_spec_object)
+    /// and [`remove_spec_object`](Self::remove_spec_object) set the LAST-CHANGE
+    /// of the elements they modify from [`clock::now`].
+    ///
+    /// With `containers`, the specifications holding a modified hierarchy
+    /// node or pointing at a modified spec object are updated as well.
+    ///
+    /// Changes made through the public fields or the `*_mut` getters are not
+    /// tracked.
+    pub fn set_track_changes(&mut self, track_changes: bool, containers: bool) {
+        self.track_changes = track_changes;
+        self.track_container_changes = track_changes && containers;
+    }
+
+    /// Sets the LAST-CHANGE of the specifications for which `predicate` is
+    /// `true`, when tracking changes of containers.
+    fn bump_specifications(&mut self, predicate: impl Fn(&Specification) -> bool) {
+        if !self.track_container_changes {
+            return;
+        }
+        let now = clock::now();
+        for specification in &mut self
+            .core_content
+            .req_if_content
+            .specifications
+            .specifications
+        {
+            if predicate(specification) {
+                specification.last_change = now;
+            }
+        }
+    }
+
+    /// Returns `true` if any element of the document uses `identifier`.
+    ///
+    /// The identifiers are indexed the first time an element is added,
+    /// identifiers changed afterwards through the public fields or the
+    /// `*_mut` getters are not seen by the index.
+    pub fn contains_identifier(&self, identifier: &str) -> bool {
+        match &self.identifiers.0 {
+            Some(used) => used.contains(identifier),
+            None => validation::identifiers(self).contains(&identifier),
+        }
+    }
+
+    /// The identifiers used in the document, indexed on first use.
+    fn used_identifiers(&mut self) -> &mut HashSet<String> {
+        let used = match self.identifiers.0.take() {
+            Some(used) => used,
+            None => validation::identifiers(self)
+                .into_iter()
+                .map(str::to_string)
+                .collect(),
+        };
+        self.identifiers.0.insert(used)
+    }
+
+    /// Drops the identifier index after changes it can't follow, it is
+    /// rebuilt when needed.
+    pub(crate) fn forget_identifiers(&mut self) {
+        self.identifiers.0 = None;
+    }
+
+    /// Removes `identifiers` from the index.
+    fn release_identifiers(&mut self, identifiers: &[String]) {
+        if let Some(used) = &mut self.identifiers.0 {
+            for identifier in identifiers {
+                used.remove(identifier);
+            }
+        }
+    }
+
+    /// Replaces `old` by `new` in the index after an element was re-identified.
+    fn reindex_identifier(&mut self, old: &str, new: &str) {
+        if let Some(used) = &mut self.identifiers.0 {
+            used.remove(old);
+            used.insert(new.to_string());
+        }
+    }
+
+    /// Returns an identifier of the thread's [`IdGenerator`](identifier::IdGenerator)
+    /// not used in the document.
+    fn generate_identifier(&mut self, kind: &str, content: &str) -> String {
+        let used = self.used_identifiers();
+        let mut generated = identifier::generate(kind, content);
+        while used.contains(&generated) {
+            let next = identifier::generate(kind, content);
+            if next == generated {
+                // Content based generators return the same identifier for
+                // equal elements, those are numbered.
+                let mut suffix = 2;
+                while used.contains(&format!("{}-{}", generated, suffix)) {
+                    suffix += 1;
+                }
+                return format!("{}-{}", generated, suffix);
+            }
+            generated = next;
+        }
+        generated
+    }
+
+    /// Generates `identifier` when it is empty, otherwise checks that it is
+    /// a valid NCName not used in the document.
+    fn prepare_identifier(
+        &mut self,
+        identifier: &mut String,
+        kind: &str,
+        content: impl FnOnce() -> String,
+    ) -> Result<()> {
+        if identifier.is_empty() {
+            *identifier = self.generate_identifier(kind, &content());
+            return Ok(());
+        }
+        if !is_valid_ncname(identifier) {
+            if !self.sanitize_identifiers {
+                bail!("Invalid identifier {:?}: not an NCName", identifier);
+            }
+            *identifier = sanitize_ncname(identifier);
+        }
+        if self.used_identifiers().contains(identifier.as_str()) {
+            bail!("Duplicated identifier: {}", identifier);
+        }
+        Ok(())
+    }
+
+    /// Prepares `identifier` as [`prepare_identifier`](Self::prepare_identifier)
+    /// and marks it as used, recording it in `claimed`.
+    fn claim_identifier(
+        &mut self,
+        identifier: &mut String,
+        kind: &str,
+        content: impl FnOnce() -> String,
+        claimed: &mut Vec<String>,
+    ) -> Result<()> {
+        self.prepare_identifier(identifier, kind, content)?;
+        self.used_identifiers().insert(identifier.clone());
+        claimed.push(identifier.clone());
+        Ok(())
+    }
+
+    /// Releases the `claimed` identifiers when adding their element failed.
+    fn settle_claims(&mut self, claimed: Vec<String>, result: Result<()>) -> Result<()> {
+        if result.is_err() {
+            self.release_identifiers(&claimed);
+        }
+        result
+    }
+
+    /// Claims the identifiers of a datatype and of its enumeration values.
+    fn claim_datatype(
+        &mut self,
+        definition: &mut DataTypeDefinition,
+        claimed: &mut Vec<String>,
+    ) -> Result<()> {
+        let long_name = definition.long_name().clone();
+        self.claim_identifier(
+            definition.identifier_mut(),
+            "DATATYPE-DEFINITION",
+            || long_name,
+            claimed,
+        )?;
+        if let DataTypeDefinition::Enumeration(enumeration) = definition {
+            for value in &mut enumeration.specified_values.values {
+                self.claim_identifier(
+                    &mut value.identifier,
+                    "ENUM-VALUE",
+                    || value.long_name.clone(),
+                    claimed,
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Claims the identifiers of a spec type and of its attribute definitions.
+    fn claim_spec_type(
+        &mut self,
+        identifier: &mut String,
+        long_name: &str,
+        kind: &str,
+        attributes: &mut SpecAttributes,
+        claimed: &mut Vec<String>,
+    ) -> Result<()> {
+        self.claim_identifier(identifier, kind, || long_name.to_string(), claimed)?;
+        for (identifier, long_name) in attributes.identifiers_mut() {
+            self.claim_identifier(
+                identifier,
+                "ATTRIBUTE-DEFINITION",
+                || long_name.clone(),
+                claimed,
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Claims the identifiers of a hierarchy node and of its descendants.
+    fn claim_spec_hierarchy(
+        &mut self,
+        spec_hierarchy: &mut SpecHierarchy,
+        claimed: &mut Vec<String>,
+    ) -> Result<()> {
+        self.claim_identifier(
+            &mut spec_hierarchy.identifier,
+            "SPEC-HIERARCHY",
+            || spec_hierarchy.object.object_ref.clone(),
+            claimed,
+        )?;
+        if let Some(children) = &mut spec_hierarchy.children {
+            for child in children.get_spec_hierarchy_mut() {
+                self.claim_spec_hierarchy(child, claimed)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Declares a new datatype in the DATATYPES section.
+    /// Fails if its identifier, or the one of an enumeration value, is
+    /// already used or not a valid NCName, an identifier is generated for the
+    /// ones that are empty.
+    pub fn add_datatype(&mut self, definition: impl Into<DataTypeDefinition>) -> Result<()> {
+        let mut definition = definition.into();
+        let mut claimed = vec![];
+        let result = self
+            .claim_datatype(&mut definition, &mut claimed)
+            .and_then(|_| {
+                self.core_content
+                    .req_if_content
+                    .data_types
+                    .add_definition(definition)
+            });
+        self.settle_claims(claimed, result)
+    }
+
+    /// Registers a new spec object type, see [`SpecTypes::add_spec_object_type`].
+    /// Identifiers are checked and generated as by [`add_datatype`](Self::add_datatype),
+    /// including the ones of its attribute definitions.
+    pub fn add_spec_object_type(&mut self, mut spec_object_type: SpecObjectType) -> Result<()> {
+        let mut claimed = vec![];
+        let result = self
+            .claim_spec_type(
+                &mut spec_object_type.identifier,
+                &spec_object_type.long_name,
+                "SPEC-OBJECT-TYPE",
+                &mut spec_object_type.attributes,
+                &mut claimed,
+            )
+            .and_then(|_| {
+                self.core_content
+                    .req_if_content
+                    .spec_types
+                    .add_spec_object_type(spec_object_type)
+            });
+        self.settle_claims(claimed, result)
+    }
+
+    pub fn get_spec_object_type(&self, identifier: &str) -> Option<&SpecObjectType> {
+        self.core_content
+            .req_if_content
+            .spec_types
+            .get_spec_object_type(identifier)
+    }
+
+    /// Declares a new attribute on the requirement spec object type.
+    /// Its identifier is checked and generated as by [`add_datatype`](Self::add_datatype).
+    pub fn add_requirement_attribute(
+        &mut self,
+        definition: impl Into<AttributeDefinition>,
+    ) -> Result<()> {
+        if self
+            .get_spec_object_type(DEFAULT_REQUIREMENT_TYPE_IDENTIFIER)
+            .is_none()
+        {
+            bail!("Missing requirement spec object type");
+        }
+        let mut definition = definition.into();
+        let long_name = definition.long_name().clone();
+        self.prepare_identifier(definition.identifier_mut(), "ATTRIBUTE-DEFINITION", || {
+            long_name
+        })?;
+        let identifier = definition.identifier().clone();
+        self.core_content
+            .req_if_content
+            .spec_types
+            .get_spec_object_type_mut(DEFAULT_REQUIREMENT_TYPE_IDENTIFIER)
+            .unwrap()
+            .add_attribute(definition)?;
+        self.used_identifiers().insert(identifier);
+        Ok(())
+    }
+
+    /// Adds a requirement, see [`ReqIf::add_spec_object`].
+    pub fn add_requirement(&mut self, requirement: SpecObject) -> Result<()> {
+        self.add_spec_object(requirement)
+    }
+
+    /// Adds a spec object of any registered type.
+    /// Fails if its identifier is already used by any element or not a valid
+    /// NCName, or if its type is unknown.
+    /// An identifier is generated if it has none.
+    pub fn add_spec_object(&mut self, mut spec_object: SpecObject) -> Result<()> {
+        if self
+            .get_spec_object_type(&spec_object.spec_object_type.reference)
+            .is_none()
+        {
+            bail!(
+                "Unknown spec object type: {}",
+                spec_object.spec_object_type.reference
+            );
+        }
+        let mut identifier = std::mem::take(&mut spec_object.identifier);
+        self.prepare_identifier(&mut identifier, "SPEC-OBJECT", || {
+            format!(
+                "{}\n{}\n{}",
+                spec_object.spec_object_type.reference,
+                spec_object.long_name,
+                yaserde::ser::to_string_content(&spec_object.values).unwrap_or_default()
+            )
+        })?;
+        self.used_identifiers().insert(identifier.clone());
+        spec_object.identifier = identifier;
+        self.core_content
+            .req_if_content
+            .spec_object
+            .spec_objects
+            .push(spec_object);
+        Ok(())
+    }
+
+    /// Registers a new spec relation type, see [`SpecTypes::add_spec_relation_type`].
+    /// Identifiers are checked and generated as by [`add_spec_object_type`](Self::add_spec_object_type).
+    pub fn add_spec_relation_type(
+        &mut self,
+        mut spec_relation_type: SpecRelationType,
+    ) -> Result<()> {
+        let mut claimed = vec![];
+        let result = self
+            .claim_spec_type(
+                &mut spec_relation_type.identifier,
+                &spec_relation_type.long_name,
+                "SPEC-RELATION-TYPE",
+                &mut spec_relation_type.attributes,
+                &mut claimed,
+            )
+            .and_then(|_| {
+                self.core_content
+                    .req_if_content
+                    .spec_types
+                    .add_spec_relation_type(spec_relation_type)
+            });
+        self.settle_claims(claimed, result)
+    }
+
+    /// Adds a link between two spec objects.
+    /// Fails if its identifier is already used by any element or not a valid
+    /// NCName, or if its type is unknown.
+    /// An identifier is generated if it has none.
+    pub fn add_spec_relation(&mut self, mut spec_relation: SpecRelation) -> Result<()> {
+        if self
+            .core_content
+            .req_if_content
+            .spec_types
+            .get_spec_relation_type(&spec_relation.spec_relation_type.reference)
+            .is_none()
+        {
+            bail!(
+                "Unknown spec relation type: {}",
+                spec_relation.spec_relation_type.reference
+            );
+        }
+        let summary = format!(
+            "{}\n{}\n{}",
+            spec_relation.spec_relation_type.reference,
+            spec_relation.source.object_ref,
+            spec_relation.target.object_ref
+        );
+        self.prepare_identifier(&mut spec_relation.identifier, "SPEC-RELATION", || summary)?;
+        self.used_identifiers()
+            .insert(spec_relation.identifier.clone());
+        self.core_content
+            .req_if_content
+            .spec_relations
+            .spec_relations
+            .push(spec_relation);
+        Ok(())
+    }
+
+    /// Registers a new relation group type, see [`SpecTypes::add_relation_group_type`].
+    /// Identifiers are checked and generated as by [`add_spec_object_type`](Self::add_spec_object_type).
+    pub fn add_relation_group_type(
+        &mut self,
+        mut relation_group_type: RelationGroupType,
+    ) -> Result<()> {
+        let mut claimed = vec![];
+        let result = self
+            .claim_spec_type(
+                &mut relation_group_type.identifier,
+                &relation_group_type.long_name,
+                "RELATION-GROUP-TYPE",
+                &mut relation_group_type.attributes,
+                &mut claimed,
+            )
+            .and_then(|_| {
+                self.core_content
+                    .req_if_content
+                    .spec_types
+                    .add_relation_group_type(relation_group_type)
+            });
+        self.settle_claims(claimed, result)
+    }
+
+    /// Adds a group of relations between two specifications.
+    /// Fails if its identifier is already used by any element or not a valid
+    /// NCName, if its type is unknown or if it references a relation that was
+    /// not added.
+    /// An identifier is generated if it has none.
+    pub fn add_relation_group(&mut self, mut relation_group: RelationGroup) -> Result<()> {
+        let content = &self.core_content.req_if_content;
+        if content
+            .spec_types
+            .get_relation_group_type(&relation_group.relation_group_type.reference)
+            .is_none()
+        {
+            bail!(
+                "Unknown relation group type: {}",
+                relation_group.relation_group_type.reference
+            );
+        }
+        let spec_relations: HashSet<&str> = content
+            .spec_relations
+            .get_spec_relations()
+            .iter()
+            .map(|r| r.identifier.as_str())
+            .collect();
+        for reference in &relation_group.spec_relations.references {
+            if !spec_relations.contains(reference.as_str()) {
+                bail!("Unknown spec relation: {}", reference);
+            }
+        }
+        let summary = format!(
+            "{}\n{}\n{}",
+            relation_group.relation_group_type.reference,
+            relation_group.source_specification.specification_ref,
+            relation_group.target_specification.specification_ref
+        );
+        self.prepare_identifier(&mut relation_group.identifier, "RELATION-GROUP", || summary)?;
+        self.used_identifiers()
+            .insert(relation_group.identifier.clone());
+        self.core_content
+            .req_if_content
+            .spec_relation_groups
+            .relation_groups
+            .push(relation_group);
+        Ok(())
+    }
+
+    /// Registers a new specification type, see [`SpecTypes::add_specification_type`].
+    /// Identifiers are checked and generated as by [`add_spec_object_type`](Self::add_spec_object_type).
+    pub fn add_specification_type(
+        &mut self,
+        mut specification_type: SpecificationType,
+    ) -> Result<()> {
+        let mut claimed = vec![];
+        let result = self
+            .claim_spec_type(
+                &mut specification_type.identifier,
+                &specification_type.long_name,
+                "SPECIFICATION-TYPE",
+                &mut specification_type.attributes,
+                &mut claimed,
+            )
+            .and_then(|_| {
+                self.core_content
+                    .req_if_content
+                    .spec_types
+                    .add_specification_type(specification_type)
+            });
+        self.settle_claims(claimed, result)
+    }
+
+    /// Creates a specification of the module type with its "ReqIF.Name"
+    /// value set to `long_name`, an identifier is generated if `identifier`
+    /// is empty.
+    ///
+    /// Other module attributes can be set with [`Specification::set_value`].
+    /// Fails if the document has no module specification type or
+    /// `identifier` is not a valid NCName.
+    pub fn build_module_specification(
+        &mut self,
+        identifier: String,
+        last_change: Timestamp,
+        long_name: String,
+    ) -> Result<Specification> {
+        let Some(specification_type) = self.get_module_specification_type().cloned() else {
+            bail!("Missing module specification type");
+        };
+        self.new_specification(identifier, last_change, long_name, specification_type)
+    }
+
+    /// Creates a specification of the type identified by
+    /// `specification_type`, as [`build_module_specification`](Self::build_module_specification).
+    /// Fails if the type is unknown.
+    pub fn build_specification(
+        &mut self,
+        identifier: String,
+        last_change: Timestamp,
+        long_name: String,
+        specification_type: String,
+    ) -> Result<Specification> {
+        if self
+            .core_content
+            .req_if_content
+            .spec_types
+            .get_specification_type(&specification_type)
+            .is_none()
+        {
+            bail!("Unknown specification type: {}", specification_type);
+        }
+        self.new_specification(identifier, last_change, long_name, specification_type)
+    }
+
+    fn new_specification(
+        &mut self,
+        mut identifier: String,
+        last_change: Timestamp,
+        long_name: String,
+        specification_type: String,
+    ) -> Result<Specification> {
+        self.prepare_identifier(&mut identifier, "SPECIFICATION", || long_name.clone())?;
+        let name_attribute = self
+            .core_content
+            .req_if_content
+            .spec_types
+            .get_specification_type(&specification_type)
+            .and_then(|t| t.name_attribute())
+            .map(|a| a.identifier.clone());
+        let mut specification =
+            Specification::new(identifier, last_change, long_name, specification_type);
+        if let Some(definition) = name_attribute {
+            specification.set_value(AttributeValueXHtml::new(
+                definition,
+                specification.long_name.clone(),
+            ));
+        }
+        Ok(specification)
+    }
+
+    /// Adds a specification along with its hierarchy.
+    /// Fails if its identifier, or the one of a hierarchy node, is already
+    /// used or not a valid NCName, an identifier is generated for the ones
+    /// that are empty.
+    pub fn add_specification(&mut self, mut specification: Specification) -> Result<()> {
+        let mut claimed = vec![];
+        let long_name = specification.long_name.clone();
+        let mut result = self.claim_identifier(
+            &mut specification.identifier,
+            "SPECIFICATION",
+            || long_name,
+            &mut claimed,
+        );
+        for node in specification.children.get_spec_hierarchy_mut() {
+            result = result.and_then(|_| self.claim_spec_hierarchy(node, &mut claimed));
+        }
+        if result.is_ok() {
+            self.core_content
+                .req_if_content
+                .specifications
+                .specifications
+                .push(specification);
+        }
+        self.settle_claims(claimed, result)
+    }
+
+    /// Inserts `spec_hierarchy` in the specification identified by
+    /// `specification`, see [`Children::insert_child`].
+    /// Identifiers of the inserted nodes are checked and generated as by
+    /// [`add_specification`](Self::add_specification).
+    /// See [`set_track_changes`](Self::set_track_changes).
+    pub fn insert_spec_hierarchy(
+        &mut self,
+        specification: &str,
+        parent: Option<&str>,
+        index: usize,
+        mut spec_hierarchy: SpecHierarchy,
+    ) -> Result<()> {
+        if self.get_specification(specification).is_none() {
+            bail!("Unknown specification: {}", specification);
+        }
+        let track = self.track_changes;
+        let mut claimed = vec![];
+        let result = self
+            .claim_spec_hierarchy(&mut spec_hierarchy, &mut claimed)
+            .and_then(|_| {
+                let specification = self
+                    .core_content
+                    .req_if_content
+                    .specifications
+                    .specifications
+                    .iter_mut()
+                    .find(|s| s.identifier == specification)
+                    .unwrap();
+                specification
+                    .children
+                    .insert_child(parent, index, spec_hierarchy)?;
+                if track {
+                    specification.last_change = clock::now();
+                }
+                Ok(())
+            });
+        self.settle_claims(claimed, result)
+    }
+
+    pub fn get_spec_objects(&self) -> &Vec<SpecObject> {
+        self.core_content
+            .req_if_content
+            .spec_object
+            .get_spec_objects()
+    }
+
+    pub fn get_spec_object(&self, identifier: &str) -> Option<&SpecObject> {
+        self.core_content
+            .req_if_content
+            .spec_object
+            .get_spec_object(identifier)
+    }
+
+    /// Changes made through the returned reference are not tracked, see
+    /// [`update_spec_object`](Self::update_spec_object).
+    pub fn get_spec_object_mut(&mut self, identifier: &str) -> Option<&mut SpecObject> {
+        self.core_content
+            .req_if_content
+            .spec_object
+            .get_spec_object_mut(identifier)
+    }
+
+    /// Removes the spec object identified by `identifier`.
+    ///
+    /// With `prune`, the hierarchy nodes pointing at it are removed and their
+    /// children moved up to their parent, and the relations from or to it
+    /// are removed along with their references in relation groups. Otherwise
+    /// those are left dangling.
+    pub fn remove_spec_object(&mut self, identifier: &str, prune: bool) -> Option<SpecObject> {
+        let content = &mut self.core_content.req_if_content;
+        let spec_objects = &mut content.spec_object.spec_objects;
+        let index = spec_objects
+            .iter()
+            .position(|o| o.identifier == identifier)?;
+        let removed = spec_objects.remove(index);
+        let mut released = vec![removed.identifier.clone()];
+        if prune {
+            let track = self.track_container_changes;
+            let now = clock::now();
+            for specification in &mut content.specifications.specifications {
+                let pruned = specification.children.remove_where(&mut |node| {
+                    let pruned = node.object.object_ref == identifier;
+                    if pruned {
+                        released.push(node.identifier.clone());
+                    }
+                    pruned
+                });
+                if track && pruned {
+                    specification.last_change = now;
+                }
+            }
+            let spec_relations = &mut content.spec_relations.spec_relations;
+            let pruned: HashSet<String> = spec_relations
+                .iter()
+                .filter(|r| r.source.object_ref == identifier || r.target.object_ref == identifier)
+                .map(|r| r.identifier.clone())
+                .collect();
+            spec_relations.retain(|r| !pruned.contains(&r.identifier));
+            for group in &mut content.spec_relation_groups.relation_groups {
+                group
+                    .spec_relations
+                    .references
+                    .retain(|r| !pruned.contains(r));
+            }
+            released.extend(pruned);
+        }
+        self.release_identifiers(&released);
+        Some(removed)
+    }
+
+    /// Changes the identifier of a spec object from `identifier` to
+    /// `new_identifier`, rewriting the hierarchy nodes and relations that
+    /// reference it, and its "IE PUID" value when it holds `identifier`.
+    /// Fails if the spec object is unknown or `new_identifier` is already
+    /// used or not a valid NCName, an identifier is generated if it is empty.
+    pub fn rename_spec_object(&mut self, identifier: &str, new_identifier: &str) -> Result<()> {
+        if self.get_spec_object(identifier).is_none() {
+            bail!("Unknown spec object: {}", identifier);
+        }
+        let mut new_identifier = new_identifier.to_string();
+        self.prepare_identifier(&mut new_identifier, "SPEC-OBJECT", || {
+            identifier.to_string()
+        })?;
+        self.reindex_identifier(identifier, &new_identifier);
+
+        let track = self.track_changes;
+        let now = clock::now();
+        let content = &mut self.core_content.req_if_content;
+        if let Some(spec_object) = content.spec_object.get_spec_object_mut(identifier) {
+            let id_attribute = content
+                .spec_types
+                .get_spec_object_type(&spec_object.spec_object_type.reference)
+                .and_then(|t| t.id_attribute());
+            for value in &mut spec_object.values.xhtml_values {
+                if Some(&value.definition.reference) == id_attribute.map(|a| &a.identifier)
+                    && value.the_value.to_plain_text() == identifier
+                {
+                    value.the_value = XHtmlValue::new(new_identifier.clone());
+                }
+            }
+            spec_object.identifier = new_identifier.clone();
+            if track {
+                spec_object.last_change = now;
+            }
+        }
+        for specification in &mut content.specifications.specifications {
+            specification.children.for_each_mut(&mut |node| {
+                if node.object.object_ref == identifier {
+                    node.object.object_ref = new_identifier.clone();
+                    if track {
+                        node.last_change = now;
+                    }
+                }
+            });
+        }
+        for relation in &mut content.spec_relations.spec_relations {
+            let mut changed = false;
+            for end in [
+                &mut relation.source.object_ref,
+                &mut relation.target.object_ref,
+            ] {
+                if end == identifier {
+                    *end = new_identifier.clone();
+                    changed = true;
+                }
+            }
+            if changed && track {
+                relation.last_change = now;
+            }
+        }
+        self.bump_specifications(|s| {
+            s.children
+                .iter_depth_first()
+                .any(|(_, _, node)| node.object.object_ref == new_identifier)
+        });
+        Ok(())
+    }
+
+    /// Modifies the spec object identified by `identifier` with `f`.
+    /// See [`set_track_changes`](Self::set_track_changes).
+    pub fn update_spec_object<R>(
+        &mut self,
+        identifier: &str,
+        f: impl FnOnce(&mut Tracked<SpecObject>) -> R,
+    ) -> Result<R> {
+        let track = self.track_changes;
+        let spec_object = match self.get_spec_object_mut(identifier) {
+            Some(spec_object) => spec_object,
+            None => bail!("Unknown spec object: {}", identifier),
+        };
+        let (result, bumped) = track_change(spec_object, |o| &mut o.last_change, track, f);
+        let current = spec_object.identifier.clone();
+        if current != identifier {
+            self.reindex_identifier(identifier, &current);
+        }
+        if bumped {
+            self.bump_specifications(|s| {
+                s.children
+                    .iter_depth_first()
+                    .any(|(_, _, node)| node.object.object_ref == current)
+            });
+        }
+        Ok(result)
+    }
+
+    /// Modifies the spec relation identified by `identifier` with `f`.
+    /// See [`set_track_changes`](Self::set_track_changes).
+    pub fn update_spec_relation<R>(
+        &mut self,
+        identifier: &str,
+        f: impl FnOnce(&mut Tracked<SpecRelation>) -> R,
+    ) -> Result<R> {
+        let track = self.track_changes;
+        let relation = match self
+            .core_content
+            .req_if_content
+            .spec_relations
+            .spec_relations
+            .iter_mut()
+            .find(|r| r.identifier == identifier)
+        {
+            Some(relation) => relation,
+            None => bail!("Unknown spec relation: {}", identifier),
+        };
+        let (result, _) = track_change(relation, |r| &mut r.last_change, track, f);
+        if relation.identifier != identifier {
+            let current = relation.identifier.clone();
+            self.reindex_identifier(identifier, &current);
+        }
+        Ok(result)
+    }
+
+    /// Modifies the specification identified by `identifier` with `f`,
+    /// including its hierarchy.
+    /// See [`set_track_changes`](Self::set_track_changes).
+    pub fn update_specification<R>(
+        &mut self,
+        identifier: &str,
+        f: impl FnOnce(&mut Tracked<Specification>) -> R,
+    ) -> Result<R> {
+        let track = self.track_changes;
+        match self
+            .core_content
+            .req_if_content
+            .specifications
+            .specifications
+            .iter_mut()
+            .find(|s| s.identifier == identifier)
+        {
+            Some(specification) => {
+                let (result, _) = track_change(specification, |s| &mut s.last_change, track, f);
+                self.forget_identifiers();
+                Ok(result)
+            }
+            None => bail!("Unknown specification: {}", identifier),
+        }
+    }
+
+    /// Modifies the hierarchy node identified by `identifier`, in any
+    /// specification, with `f`.
+    /// See [`set_track_changes`](Self::set_track_changes).
+    pub fn update_spec_hierarchy<R>(
+        &mut self,
+        identifier: &str,
+        f: impl FnOnce(&mut Tracked<SpecHierarchy>) -> R,
+    ) -> Result<R> {
+        let track = self.track_changes;
+        let track_container = self.track_container_changes;
+        for specification in &mut self
+            .core_content
+            .req_if_content
+            .specifications
+            .specifications
+        {
+            let node = match specification.children.find_path(identifier) {
+                Some(path) => specification.children.get_at_mut(&path).unwrap(),
+                None => continue,
+            };
+            let (result, bumped) = track_change(node, |n| &mut n.last_change, track, f);
+            if bumped && track_container {
+                specification.last_change = clock::now();
+            }
+            self.forget_identifiers();
+            return Ok(result);
+        }
+        bail!("Unknown spec hierarchy: {}", identifier)
+    }
+
+    /// Modifies the spec object type identified by `identifier` with `f`.
+    /// See [`set_track_changes`](Self::set_track_changes).
+    pub fn update_spec_object_type<R>(
+        &mut self,
+        identifier: &str,
+        f: impl FnOnce(&mut Tracked<SpecObjectType>) -> R,
+    ) -> Result<R> {
+        let track = self.track_changes;
+        let spec_types = &mut self.core_content.req_if_content.spec_types;
+        let spec_type = match spec_types.get_spec_object_type_mut(identifier) {
+            Some(spec_type) => spec_type,
+            None => bail!("Unknown spec object type: {}", identifier),
+        };
+        let (result, _) = track_change(spec_type, |t| &mut t.last_change, track, f);
+        self.forget_identifiers();
+        Ok(result)
+    }
+
+    /// Modifies the specification type identified by `identifier` with `f`.
+    /// See [`set_track_changes`](Self::set_track_changes).
+    pub fn update_specification_type<R>(
+        &mut self,
+        identifier: &str,
+        f: impl FnOnce(&mut Tracked<SpecificationType>) -> R,
+    ) -> Result<R> {
+        let track = self.track_changes;
+        let spec_types = &mut self.core_content.req_if_content.spec_types;
+        let spec_type = match spec_types.get_specification_type_mut(identifier) {
+            Some(spec_type) => spec_type,
+            None => bail!("Unknown specification type: {}", identifier),
+        };
+        let (result, _) = track_change(spec_type, |t| &mut t.last_change, track, f);
+        self.forget_identifiers();
+        Ok(result)
+    }
+
+    /// Modifies the spec relation type identified by `identifier` with `f`.
+    /// See [`set_track_changes`](Self::set_track_changes).
+    pub fn update_spec_relation_type<R>(
+        &mut self,
+        identifier: &str,
+        f: impl FnOnce(&mut Tracked<SpecRelationType>) -> R,
+    ) -> Result<R> {
+        let track = self.track_changes;
+        let spec_types = &mut self.core_content.req_if_content.spec_types;
+        let spec_type = match spec_types.get_spec_relation_type_mut(identifier) {
+            Some(spec_type) => spec_type,
+            None => bail!("Unknown spec relation type: {}", identifier),
+        };
+        let (result, _) = track_change(spec_type, |t| &mut t.last_change, track, f);
+        self.forget_identifiers();
+        Ok(result)
+    }
+
+    /// Modifies the relation group type identified by `identifier` with `f`.
+    /// See [`set_track_changes`](Self::set_track_changes).
+    pub fn update_relation_group_type<R>(
+        &mut self,
+        identifier: &str,
+        f: impl FnOnce(&mut Tracked<RelationGroupType>) -> R,
+    ) -> Result<R> {
+        let track = self.track_changes;
+        let spec_types = &mut self.core_content.req_if_content.spec_types;
+        let spec_type = match spec_types.get_relation_group_type_mut(identifier) {
+            Some(spec_type) => spec_type,
+            None => bail!("Unknown relation group type: {}", identifier),
+        };
+        let (result, _) = track_change(spec_type, |t| &mut t.last_change, track, f);
+        self.forget_identifiers();
+        Ok(result)
+    }
+
+    /// Returns the spec object `node` points at.
+    pub fn resolve(&self, node: &SpecHierarchy) -> Option<&SpecObject> {
+        self.get_spec_object(&node.object.object_ref)
+    }
+
+    /// Returns the spec objects for which `predicate` is `true`, in document
+    /// order.
+    pub fn find_spec_objects(&self, predicate: impl Fn(&SpecObject) -> bool) -> Vec<&SpecObject> {
+        self.get_spec_objects()
+            .iter()
+            .filter(|o| predicate(o))
+            .collect()
+    }
+
+    /// Returns the spec objects holding a value equal to `value` for its
+    /// attribute definition.
+    pub fn find_spec_objects_by_value(&self, value: impl Into<AttributeValue>) -> Vec<&SpecObject> {
+        let value = value.into();
+        self.find_spec_objects(|o| o.values.get_value(value.definition()).as_ref() == Some(&value))
+    }
+
+    pub fn get_specifications(&self) -> &Vec<Specification> {
+        self.core_content
+            .req_if_content
+            .specifications
+            .get_specifications()
+    }
+
+    pub fn get_specification(&self, identifier: &str) -> Option<&Specification> {
+        self.get_specifications()
+            .iter()
+            .find(|s| s.identifier == identifier)
+    }
+
+    /// Returns the identifier of [`SpecTypes::get_module_type`].
+    pub fn get_module_specification_type(&self) -> Option<&String> {
+        self.core_content
+            .req_if_content
+            .spec_types
+            .get_module_type()
+            .map(|t| &t.identifier)
+    }
+
+    /// Writes the document as indented XML to `writer`.
+    ///
+    /// I/O errors of `writer` are returned as the original [`io::Error`].
+    pub fn write<W: Write>(&self, writer: W) -> anyhow::Result<()> {
+        if self.validate_on_write {
+            let diagnostics = self.validate();
+            if !diagnostics.is_empty() {
+                return Err(ValidationError { diagnostics }.into());
+            }
+        }
+        if self.deterministic {
+            return self.write_sorted(writer);
+        }
+        let yaserde_cfg = yaserde::ser::Config {
+            perform_indent: true,
+            ..Default::default()
+        };
+
+        let mut writer = IoErrorKeeper::new(writer);
+        let error = writer.error();
+        if let Err(s) = yaserde::ser::serialize_with_writer(self, &mut writer, &yaserde_cfg) {
+            return Err(take_error(&error, s));
+        }
+        writer
+            .flush()
+            .map_err(|e| take_error(&error, e.to_string()))
+    }
+
+    /// Writes the document as [`sort`](Self::sort) would order it, visiting
+    /// the elements in sorted order. Only the type sections and the elements
+    /// whose content is out of order are copied.
+    fn write_sorted<W: Write>(&self, writer: W) -> anyhow::Result<()> {
+        fn sorted<T: Clone>(item: &T, is_sorted: bool, sort: fn(&mut T)) -> Cow<'_, T> {
+            if is_sorted {
+                return Cow::Borrowed(item);
+            }
+            let mut item = item.clone();
+            sort(&mut item);
+            Cow::Owned(item)
+        }
+
+        let content = &self.core_content.req_if_content;
+        let mut data_types = content.data_types.clone();
+        data_types.sort();
+        let mut spec_types = content.spec_types.clone();
+        spec_types.sort();
+        let mut writer = ReqIfWriter::new(writer, &self.the_header, &data_types, &spec_types)?;
+        for spec_object in
+            sorted_by_identifier(content.spec_object.get_spec_objects(), |o| &o.identifier)
+        {
+            writer.write_spec_object(&sorted(
+                spec_object,
+                spec_object.values.is_sorted(),
+                |o| o.values.sort(),
+            ))?;
+        }
+        for spec_relation in
+            sorted_by_identifier(content.spec_relations.get_spec_relations(), |r| {
+                &r.identifier
+            })
+        {
+            writer.write_spec_relation(&sorted(
+                spec_relation,
+                spec_relation.values.is_sorted(),
+                |r| r.values.sort(),
+            ))?;
+        }
+        for specification in content.specifications.get_specifications() {
+            writer.write_specification(&sorted(
+                specification,
+                specification.values.is_sorted(),
+                |s| s.values.sort(),
+            ))?;
+        }
+        for relation_group in
+            sorted_by_identifier(content.spec_relation_groups.get_relation_groups(), |g| {
+                &g.identifier
+            })
+        {
+            writer.write_relation_group(&sorted(
+                relation_group,
+                is_sorted_by_identifier(&relation_group.spec_relations.references, |r| r),
+                |g| g.spec_relations.references.sort(),
+            ))?;
+        }
+        writer.finish()?;
+        Ok(())
+    }
+
+    pub fn to_xml_string(&self) -> anyhow::Result<String> {
+        let mut buffer = vec![];
+        self.write(&mut buffer)?;
+        Ok(String::from_utf8(buffer)?)
+    }
+
+    pub fn write_to(&self, filename: &str) -> anyhow::Result<()> {
+        let file = File::create(filename)?;
+        self.write(BufWriter::new(file))
+    }
+
+    /// Reads a ReqIF document from `reader`.
+    ///
+    /// Elements that are not part of the model are skipped.
+    pub fn from_reader<R: Read>(reader: R) -> anyhow::Result<Self> {
+        match <Self as yaserde::YaDeserialize>::deserialize(&mut deserializer(reader)) {
+            Ok(req_if) => Ok(req_if),
+            Err(s) => bail!(s),
+        }
+    }
+
+    /// Reads the ReqIF document stored in `filename`.
+    pub fn read_from(filename: &str) -> anyhow::Result<Self> {
+        let file = File::open(filename)?;
+        Self::from_reader(BufReader::new(file))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        AttributeDefinition, AttributeDefinitionBoolean, AttributeDefinitionDate,
+        AttributeDefinitionEnumeration, AttributeDefinitionInteger, AttributeDefinitionReal,
+        AttributeDefinitionString, AttributeDefinitionXHtml, AttributeValueBoolean,
+        AttributeValueDate, AttributeValueEnumeration, AttributeValueInteger, AttributeValueReal,
+        AttributeValueString, AttributeValueXHtml, Children, DataTypeDefinitionBoolean,
+        DataTypeDefinitionDate, DataTypeDefinitionEnumeration, DataTypeDefinitionInteger,
+        DataTypeDefinitionReal, DataTypeDefinitionString, DataTypeDefinitionXHtml, DataTypes,
+        EnumValue, Object, RelationGroup, RelationGroupType, ReqIf, SpecHierarchy, SpecObject,
+        SpecObjectType, SpecRelation, SpecRelationType, Specification, SpecificationType,
+        DEFAULT_REQUIREMENT_TYPE_IDENTIFIER,
+    };
+    use crate::clock::{self, FixedClock, Timestamp};
+    use crate::fixture::{self, document};
+    use crate::identifier::{self, is_valid_ncname, CounterGenerator, UuidV5Generator};
+    use chrono::{Local, TimeZone};
+
+    #[test]
+    fn test_add_spec_hierarchy() {
+        let mut children = Children::new();
+        for _ in 0..4 {
+            children
+                .add_spec_hierarchy(
+                    SpecHierarchy::new(
+                        "H-2.1.2".to_string(),
+                        clock::now(),
+                        Object::new("REQ001".to_string()),
+                    ),
+                    0,
+                )
+                .expect("Error");
+        }
+        assert_eq!(children.spec_hierarchy.len(), 4);
+    }
+
+    #[test]
+    fn test_add_spec_panic() {
+        let mut children = Children::new();
+        children
+            .add_spec_hierarchy(
+                SpecHierarchy::new(
+                    "H-2.1.2".to_string(),
+                    clock::now(),
+                    Object::new("REQ001".to_string()),
+                ),
+                0,
+            )
+            .expect("error");
+        let res = children.add_spec_hierarchy(
+            SpecHierarchy::new(
+                "H-2.1.2".to_string(),
+                clock::now(),
+                Object::new("REQ001".to_string()),
+            ),
+            2,
+        );
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn test_add_spec_hierarchy_2() {
+        let mut children = Children::new();
+        children
+            .add_spec_hierarchy(
+                SpecHierarchy::new(
+                    "H-2.1.2".to_string(),
+                    clock::now(),
+                    Object::new("REQ001".to_string()),
+                ),
+                0,
+            )
+            .expect("error");
+        children
+            .add_spec_hierarchy(
+                SpecHierarchy::new(
+                    "H-2.1.2".to_string(),
+                    clock::now(),
+                    Object::new("REQ001".to_string()),
+                ),
+                1,
+            )
+            .expect("error");
+
+        children
+            .add_spec_hierarchy(
+                SpecHierarchy::new(
+                    "H-2.1.2".to_string(),
+                    clock::now(),
+                    Object::new("REQ001".to_string()),
+                ),
+                1,
+            )
+            .expect("error");
+
+        assert_eq!(children.spec_hierarchy.len(), 1);
+        let spec = children.get_spec_hierarchy().last().unwrap();
+        let len = spec.children.as_ref().unwrap().spec_hierarchy.len();
+        assert_eq!(len, 2)
+    }
+
+    #[test]
+    fn test_edit_spec_hierarchy() {
+        let node = |identifier: &str| {
+            SpecHierarchy::new(
+                identifier.to_string(),
+                clock::now(),
+                Object::new("REQ001".to_string()),
+            )
+        };
+        let identifiers = |children: &Children| {
+            children
+                .get_spec_hierarchy()
+                .iter()
+                .map(|n| n.identifier.clone())
+                .collect::<Vec<String>>()
+        };
+        let mut children = Children::new();
+        children.insert_at(&[0], node("H-2")).expect("error");
+        children.insert_child(None, 0, node("H-1")).expect("error");
+        children.add_child("H-2", node("H-2.2")).expect("error");
+        children.insert_at(&[1, 0], node("H-2.1")).expect("error");
+        children.add_child("H-2.1", node("H-2.1.1")).expect("error");
+        children
+            .insert_child(Some("H-2"), 2, node("H-2.3"))
+            .expect("error");
+        assert!(children.insert_at(&[1, 5], node("H-X")).is_err());
+        assert!(children.insert_at(&[4, 0], node("H-X")).is_err());
+        assert!(children.add_child("H-X", node("H-Y")).is_err());
+
+        assert_eq!(identifiers(&children), vec!["H-1", "H-2"]);
+        let h2 = children.get_at(&[1]).unwrap();
+        assert_eq!(
+            identifiers(h2.children.as_ref().unwrap()),
+            vec!["H-2.1", "H-2.2", "H-2.3"]
+        );
+        assert_eq!(children.find_path("H-2.1.1"), Some(vec![1, 0, 0]));
+        assert_eq!(children.get_at(&[1, 0, 0]).unwrap().identifier, "H-2.1.1");
+        assert_eq!(children.find_path("H-X"), None);
+
+        children.move_to("H-2.1", Some("H-1"), 0).expect("error");
+        assert_eq!(children.find_path("H-2.1.1"), Some(vec![0, 0, 0]));
+        children.move_to("H-2.3", None, 0).expect("error");
+        assert_eq!(identifiers(&children), vec!["H-2.3", "H-1", "H-2"]);
+        assert!(children.move_to("H-1", Some("H-2.1.1"), 0).is_err());
+        assert!(children.move_to("H-2.2", Some("H-1"), 5).is_err());
+        assert_eq!(children.find_path("H-2.2"), Some(vec![2, 0]));
+
+        let removed = children.remove("H-2.1").unwrap();
+        assert_eq!(
+            identifiers(removed.children.as_ref().unwrap()),
+            vec!["H-2.1.1"]
+        );
+        assert!(children.get_at(&[1]).unwrap().children.is_none());
+        assert!(children.remove("H-2.1.1").is_none());
+        assert_eq!(children.remove_at(&[0]).unwrap().identifier, "H-2.3");
+        assert!(children.remove_at(&[3]).is_err());
+    }
+
+    #[test]
+    fn test_traverse_and_query() {
+        let mut reqif = document("Query");
+        let now = clock::now();
+        reqif
+            .add_datatype(DataTypeDefinitionBoolean::new(
+                "DT-BOOLEAN".to_string(),
+                now,
+                "Boolean".to_string(),
+            ))
+            .expect("error");
+        reqif
+            .add_requirement_attribute(AttributeDefinitionBoolean::new(
+                "AD-NORMATIVE".to_string(),
+                now,
+                "Normative".to_string(),
+                "DT-BOOLEAN".to_string(),
+            ))
+            .expect("error");
+        for (id, normative) in [("REQ-1", true), ("REQ-2", false), ("REQ-3", true)] {
+            let mut requirement = SpecObject::requirement(
+                id.to_string(),
+                now,
+                id.to_string(),
+                format!("Text of {}.", id),
+                &reqif.core_content.req_if_content.spec_types,
+            )
+            .expect("error");
+            requirement.set_value(AttributeValueBoolean::new(
+                "AD-NORMATIVE".to_string(),
+                normative,
+            ));
+            reqif.add_requirement(requirement).expect("error");
+        }
+
+        let mut specification = reqif
+            .build_module_specification("SPEC-1".to_string(), now, "Module".to_string())
+            .expect("error");
+        let children = &mut specification.children;
+        for (path, id, object) in [
+            (vec![0], "H-1", "REQ-1"),
+            (vec![1], "H-2", "REQ-2"),
+            (vec![0, 0], "H-1.1", "REQ-2"),
+        ] {
+            let object = Object::new(object.to_string());
+            children
+                .insert_at(&path, SpecHierarchy::new(id.to_string(), now, object))
+                .expect("error");
+        }
+        children
+            .add_child(
+                "H-1.1",
+                SpecHierarchy::new("H-1.1.1".to_string(), now, Object::new("REQ-3".to_string())),
+            )
+            .expect("error");
+        reqif.add_specification(specification).expect("error");
+
+        let children = &reqif.get_specification("SPEC-1").unwrap().children;
+        let depth_first: Vec<(usize, Vec<usize>, &str)> = children
+            .iter_depth_first()
+            .map(|(depth, path, node)| (depth, path, node.identifier.as_str()))
+            .collect();
+        assert_eq!(
+            depth_first,
+            vec![
+                (0, vec![0], "H-1"),
+                (1, vec![0, 0], "H-1.1"),
+                (2, vec![0, 0, 0], "H-1.1.1"),
+                (0, vec![1], "H-2"),
+            ]
+        );
+        let breadth_first: Vec<&str> = children
+            .iter_breadth_first()
+            .map(|(_, _, node)| node.identifier.as_str())
+            .collect();
+        assert_eq!(breadth_first, vec!["H-1", "H-2", "H-1.1", "H-1.1.1"]);
+
+        let (_, _, node) = children.iter_depth_first().nth(2).unwrap();
+        assert_eq!(reqif.resolve(node).unwrap().identifier, "REQ-3");
+        assert_eq!(reqif.get_spec_object("REQ-2").unwrap().long_name, "REQ-2");
+        assert!(reqif.get_spec_object("REQ-9").is_none());
+
+        let normative: Vec<&str> = reqif
+            .find_spec_objects_by_value(AttributeValueBoolean::new(
+                "AD-NORMATIVE".to_string(),
+                true,
+            ))
+            .iter()
+            .map(|o| o.identifier.as_str())
+            .collect();
+        assert_eq!(normative, vec!["REQ-1", "REQ-3"]);
+        assert_eq!(
+            reqif
+                .find_spec_objects(|o| o.long_name.ends_with('2'))
+                .len(),
+            1
+        );
+        assert!(reqif.validate().is_empty());
+    }
+
+    #[test]
+    fn test_update_remove_and_rename() {
+        let now = clock::now();
+        let mut reqif = build_deterministic(&["REQ-1", "REQ-2", "REQ-3"]);
+        reqif
+            .add_spec_relation_type(SpecRelationType::new(
+                "SRT-DERIVES".to_string(),
+                now,
+                "derives from".to_string(),
+            ))
+            .expect("error");
+        for (id, source, target) in [("REL-1", "REQ-2", "REQ-1"), ("REL-2", "REQ-3", "REQ-2")] {
+            reqif
+                .add_spec_relation(SpecRelation::new(
+                    id.to_string(),
+                    now,
+                    String::new(),
+                    "SRT-DERIVES".to_string(),
+                    source.to_string(),
+                    target.to_string(),
+                ))
+                .expect("error");
+        }
+        let mut specification = reqif
+            .build_module_specification("SPEC-1".to_string(), now, "Module".to_string())
+            .expect("error");
+        for (path, id, object) in [
+            (vec![0], "H-1", "REQ-1"),
+            (vec![0, 0], "H-2", "REQ-2"),
+            (vec![0, 0, 0], "H-3", "REQ-3"),
+            (vec![1], "H-4", "REQ-3"),
+        ] {
+            specification
+                .children
+                .insert_at(
+                    &path,
+                    SpecHierarchy::new(id.to_string(), now, Object::new(object.to_string())),
+                )
+                .expect("error");
+        }
+        reqif.add_specification(specification).expect("error");
+
+        let requirement = reqif.get_spec_object_mut("REQ-1").unwrap();
+        requirement.long_name = "Updated".to_string();
+        assert_eq!(reqif.get_spec_object("REQ-1").unwrap().long_name, "Updated");
+
+        reqif.rename_spec_object("REQ-2", "SYS-2").expect("error");
+        assert!(reqif.rename_spec_object("REQ-2", "SYS-3").is_err());
+        assert!(reqif.rename_spec_object("SYS-2", "REQ-1").is_err());
+        assert!(reqif.rename_spec_object("SYS-2", "2 SYS").is_err());
+        assert_eq!(reqif.validate(), vec![]);
+        let children = &reqif.get_specification("SPEC-1").unwrap().children;
+        assert_eq!(children.get_at(&[0, 0]).unwrap().object.object_ref, "SYS-2");
+        let relations = reqif
+            .core_content
+            .req_if_content
+            .spec_relations
+            .get_spec_relations();
+        assert_eq!(relations[0].source.object_ref, "SYS-2");
+        assert_eq!(relations[1].target.object_ref, "SYS-2");
+        let puid = "ATTRIBUTE-DEFINITION-XHTML-PUID-ID";
+        assert_eq!(
+            reqif
+                .get_spec_object("SYS-2")
+                .unwrap()
+                .values
+                .get_value(puid),
+            Some(AttributeValueXHtml::new(puid.to_string(), "SYS-2".to_string()).into())
+        );
+
+        let mut kept = reqif.clone();
+        assert!(kept.remove_spec_object("SYS-2", false).is_some());
+        assert_eq!(kept.validate().len(), 3);
+
+        assert!(reqif.remove_spec_object("SYS-2", true).is_some());
+        assert!(reqif.remove_spec_object("SYS-2", true).is_none());
+        assert_eq!(reqif.validate(), vec![]);
+        let nodes: Vec<String> = reqif
+            .get_specification("SPEC-1")
+            .unwrap()
+            .children
+            .iter_depth_first()
+            .map(|(_, path, node)| format!("{:?} {}", path, node.identifier))
+            .collect();
+        assert_eq!(nodes, vec!["[0] H-1", "[0, 0] H-3", "[1] H-4"]);
+        assert!(reqif
+            .core_content
+            .req_if_content
+            .spec_relations
+            .get_spec_relations()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_read_back_written_document() {
+        let mut reqif = document("Round trip");
+        reqif
+            .add_requirement(
+                SpecObject::requirement(
+                    "REQ-1".to_string(),
+                    clock::now(),
+                    "First".to_string(),
+                    "The first requirement.".to_string(),
+                    &reqif.core_content.req_if_content.spec_types,
+                )
+                .expect("error"),
+            )
+            .expect("error");
+        let mut specification = reqif
+            .build_module_specification(
+                "SPEC-1".to_string(),
+                clock::now(),
+                "Requirements".to_string(),
+            )
+            .expect("error");
+        specification
+            .children
+            .add_spec_hierarchy(
+                SpecHierarchy::new(
+                    "H-1".to_string(),
+                    clock::now(),
+                    Object::new("REQ-1".to_string()),
+                ),
+                0,
+            )
+            .expect("error");
+        reqif.add_specification(specification).expect("error");
+
+        let xml = yaserde::ser::to_string(&reqif).expect("error");
+        let read = ReqIf::from_reader(xml.as_bytes()).expect("error");
+        assert_eq!(read, reqif);
+    }
+
+    #[test]
+    fn test_read_foreign_document() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<REQ-IF xmlns="http://www.omg.org/spec/ReqIF/20110401/reqif.xsd" xmlns:xhtml="http://www.w3.org/1999/xhtml">
+  <THE-HEADER>
+    <REQ-IF-HEADER IDENTIFIER="header">
+      <CREATION-TIME>2024-04-01T10:00:00.000+02:00</CREATION-TIME>
+      <REQ-IF-TOOL-ID>Other tool</REQ-IF-TOOL-ID>
+      <REQ-IF-VERSION>1.0</REQ-IF-VERSION>
+      <SOURCE-TOOL-ID>Other tool</SOURCE-TOOL-ID>
+      <TITLE>Imported</TITLE>
+    </REQ-IF-HEADER>
+  </THE-HEADER>
+  <CORE-CONTENT>
+    <REQ-IF-CONTENT>
+      <DATATYPES>
+        <DATATYPE-DEFINITION-XHTML IDENTIFIER="xhtml" LAST-CHANGE="2024-04-01T10:00:00.000+02:00"/>
+      </DATATYPES>
+      <SPEC-TYPES>
+        <SPEC-OBJECT-TYPE IDENTIFIER="req-type" LAST-CHANGE="2024-04-01T10:00:00.000+02:00" LONG-NAME="Requirement">
+          <SPEC-ATTRIBUTES>
+            <ATTRIBUTE-DEFINITION-XHTML IDENTIFIER="text" LAST-CHANGE="2024-04-01T10:00:00.000+02:00" LONG-NAME="ReqIF.Text">
+              <TYPE><DATATYPE-DEFINITION-XHTML-REF>xhtml</DATATYPE-DEFINITION-XHTML-REF></TYPE>
+            </ATTRIBUTE-DEFINITION-XHTML>
+          </SPEC-ATTRIBUTES>
+        </SPEC-OBJECT-TYPE>
+        <SPECIFICATION-TYPE IDENTIFIER="module-type" LAST-CHANGE="2024-04-01T10:00:00.000+02:00">
+          <SPEC-ATTRIBUTES>
+            <ATTRIBUTE-DEFINITION-XHTML IDENTIFIER="name" LAST-CHANGE="2024-04-01T10:00:00.000+02:00" LONG-NAME="ReqIF.Name">
+              <TYPE><DATATYPE-DEFINITION-XHTML-REF>xhtml</DATATYPE-DEFINITION-XHTML-REF></TYPE>
+            </ATTRIBUTE-DEFINITION-XHTML>
+          </SPEC-ATTRIBUTES>
+        </SPECIFICATION-TYPE>
+      </SPEC-TYPES>
+      <SPEC-OBJECTS>
+        <SPEC-OBJECT IDENTIFIER="obj-1" LAST-CHANGE="2024-04-01T10:00:00.000+02:00">
+          <VALUES>
+            <ATTRIBUTE-VALUE-XHTML>
+              <DEFINITION><ATTRIBUTE-DEFINITION-XHTML-REF>text</ATTRIBUTE-DEFINITION-XHTML-REF></DEFINITION>
+              <THE-ORIGINAL-VALUE><xhtml:div>Original</xhtml:div></THE-ORIGINAL-VALUE>
+              <THE-VALUE><xhtml:div><xhtml:p>The <xhtml:b>system</xhtml:b></xhtml:p></xhtml:div></THE-VALUE>
+            </ATTRIBUTE-VALUE-XHTML>
+          </VALUES>
+          <TYPE><SPEC-OBJECT-TYPE-REF>req-type</SPEC-OBJECT-TYPE-REF></TYPE>
+        </SPEC-OBJECT>
+      </SPEC-OBJECTS>
+      <SPECIFICATIONS>
+        <SPECIFICATION IDENTIFIER="spec-1" LAST-CHANGE="2024-04-01T10:00:00.000+02:00" LONG-NAME="Empty">
+          <TYPE><SPECIFICATION-TYPE-REF>module-type</SPECIFICATION-TYPE-REF></TYPE>
+        </SPECIFICATION>
+      </SPECIFICATIONS>
+    </REQ-IF-CONTENT>
+  </CORE-CONTENT>
+</REQ-IF>"#;
+
+        let reqif = ReqIf::from_reader(xml.as_bytes()).expect("error");
+        let header = &reqif.the_header.req_if_header;
+        assert_eq!(header.title, "Imported");
+        assert_eq!(header.repository_id, "");
+
+        let content = &reqif.core_content.req_if_content;
+        let requirement = &content.spec_object.spec_objects[0];
+        assert_eq!(requirement.identifier, "obj-1");
+        assert_eq!(requirement.long_name, "");
+        let value = &requirement.values.xhtml_values[0];
+        assert_eq!(value.definition.reference, "text");
+        assert_eq!(value.the_value.to_plain_text(), "The system");
+        assert_eq!(
+            value.the_original_value.as_ref().map(|v| v.to_plain_text()),
+            Some("Original".to_string())
+        );
+
+        let specification = &content.specifications.specifications[0];
+        assert!(specification.children.get_spec_hierarchy().is_empty());
+    }
+
+    /// A minimal document whose REQ-IF-CONTENT holds `content`.
+    fn read_content(content: &str) -> ReqIf {
+        let xml = format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<REQ-IF xmlns="http://www.omg.org/spec/ReqIF/20110401/reqif.xsd">
+  <THE-HEADER>
+    <REQ-IF-HEADER IDENTIFIER="header">
+      <CREATION-TIME>2024-04-01T10:00:00.000+02:00</CREATION-TIME>
+      <REQ-IF-TOOL-ID>Other tool</REQ-IF-TOOL-ID>
+      <REQ-IF-VERSION>1.0</REQ-IF-VERSION>
+      <SOURCE-TOOL-ID>Other tool</SOURCE-TOOL-ID>
+      <TITLE>Minimal</TITLE>
+    </REQ-IF-HEADER>
+  </THE-HEADER>
+  <CORE-CONTENT>
+    <REQ-IF-CONTENT>{}</REQ-IF-CONTENT>
+  </CORE-CONTENT>
+</REQ-IF>"#,
+            content
+        );
+        ReqIf::from_reader(xml.as_bytes()).expect("error")
+    }
+
+    #[test]
+    fn test_read_minimal_document() {
+        let reqif = read_content("");
+        let content = &reqif.core_content.req_if_content;
+        assert!(content.data_types.xhtml_definitions.is_empty());
+        assert!(content.spec_types.get_spec_object_types().is_empty());
+        assert!(content.spec_object.get_spec_objects().is_empty());
+        assert!(content.specifications.get_specifications().is_empty());
+
+        let written = reqif.to_xml_string().expect("error");
+        assert_eq!(
+            ReqIf::from_reader(written.as_bytes()).expect("error"),
+            reqif
+        );
+    }
+
+    #[test]
+    fn test_build_without_default_types() {
+        let requirement = |reqif: &ReqIf| {
+            SpecObject::requirement(
+                "REQ-1".to_string(),
+                clock::now(),
+                "First".to_string(),
+                "Text.".to_string(),
+                &reqif.core_content.req_if_content.spec_types,
+            )
+        };
+        let mut reqif = read_content("");
+        assert!(requirement(&reqif).is_err());
+        assert!(reqif
+            .build_module_specification(String::new(), clock::now(), "Module".to_string())
+            .is_err());
+
+        reqif = read_content(&format!(
+            r#"<SPEC-TYPES>
+  <SPEC-OBJECT-TYPE IDENTIFIER="{}" LAST-CHANGE="2024-04-01T10:00:00+02:00"/>
+</SPEC-TYPES>"#,
+            DEFAULT_REQUIREMENT_TYPE_IDENTIFIER
+        ));
+        assert!(requirement(&reqif).is_err());
+
+        let spec_object = SpecObject::new(
+            "REQ-1".to_string(),
+            clock::now(),
+            "First".to_string(),
+            "unknown".to_string(),
+        );
+        assert!(reqif.add_requirement(spec_object).is_err());
+        assert!(reqif.get_spec_objects().is_empty());
+    }
 
-    pub fn add_specification(&mut self, specification: Specification) {
-        self.core_content
+    #[test]
+    fn test_read_types_without_attributes() {
+        let reqif = read_content(
+            r#"<SPEC-TYPES>
+  <SPEC-OBJECT-TYPE IDENTIFIER="object-type" LAST-CHANGE="2024-04-01T10:00:00+02:00"/>
+  <SPECIFICATION-TYPE IDENTIFIER="specification-type" LAST-CHANGE="2024-04-01T10:00:00+02:00"/>
+  <SPEC-RELATION-TYPE IDENTIFIER="relation-type" LAST-CHANGE="2024-04-01T10:00:00+02:00"/>
+  <RELATION-GROUP-TYPE IDENTIFIER="group-type" LAST-CHANGE="2024-04-01T10:00:00+02:00"/>
+</SPEC-TYPES>"#,
+        );
+        let spec_types = &reqif.core_content.req_if_content.spec_types;
+        let attributes = &spec_types.get_spec_object_types()[0].attributes;
+        assert!(attributes.xhtml_attributes.is_empty());
+        assert_eq!(spec_types.get_specification_types().len(), 1);
+        assert_eq!(spec_types.get_spec_relation_types().len(), 1);
+        assert_eq!(spec_types.get_relation_group_types().len(), 1);
+    }
+
+    #[test]
+    fn test_read_relation_group_without_relations() {
+        let reqif = read_content(
+            r#"<SPEC-RELATION-GROUPS>
+  <RELATION-GROUP IDENTIFIER="group" LAST-CHANGE="2024-04-01T10:00:00+02:00">
+    <TYPE><RELATION-GROUP-TYPE-REF>group-type</RELATION-GROUP-TYPE-REF></TYPE>
+    <SOURCE-SPECIFICATION><SPECIFICATION-REF>spec-1</SPECIFICATION-REF></SOURCE-SPECIFICATION>
+    <TARGET-SPECIFICATION><SPECIFICATION-REF>spec-2</SPECIFICATION-REF></TARGET-SPECIFICATION>
+  </RELATION-GROUP>
+</SPEC-RELATION-GROUPS>"#,
+        );
+        let content = &reqif.core_content.req_if_content;
+        let group = &content.spec_relation_groups.get_relation_groups()[0];
+        assert!(group.spec_relations.references.is_empty());
+    }
+
+    #[test]
+    fn test_data_types() {
+        let now = clock::now();
+        let mut data_types = DataTypes::new();
+        data_types
+            .add_definition(DataTypeDefinitionBoolean::new(
+                "DT-BOOLEAN".to_string(),
+                now,
+                "Boolean".to_string(),
+            ))
+            .expect("error");
+        data_types
+            .add_definition(DataTypeDefinitionDate::new(
+                "DT-DATE".to_string(),
+                now,
+                "Date".to_string(),
+            ))
+            .expect("error");
+        data_types
+            .add_definition(DataTypeDefinitionInteger::new(
+                "DT-INTEGER".to_string(),
+                now,
+                "Effort".to_string(),
+                0,
+                100,
+            ))
+            .expect("error");
+        data_types
+            .add_definition(DataTypeDefinitionReal::new(
+                "DT-REAL".to_string(),
+                now,
+                "Weight".to_string(),
+                -1.5,
+                1.5,
+                2,
+            ))
+            .expect("error");
+        data_types
+            .add_definition(DataTypeDefinitionString::new(
+                "DT-STRING".to_string(),
+                now,
+                "String".to_string(),
+                255,
+            ))
+            .expect("error");
+        assert!(data_types
+            .add_definition(DataTypeDefinitionXHtml::new(
+                "DT-STRING".to_string(),
+                now,
+                "Duplicated".to_string(),
+            ))
+            .is_err());
+
+        let xml = yaserde::ser::to_string(&data_types).expect("error");
+        assert!(xml.contains(r#"<DATATYPE-DEFINITION-INTEGER IDENTIFIER="DT-INTEGER""#));
+        assert!(xml.contains(r#"MAX="100" MIN="0""#));
+        assert!(xml.contains(r#"ACCURACY="2" MAX="1.5" MIN="-1.5""#));
+        assert!(xml.contains(r#"MAX-LENGTH="255""#));
+        assert!(xml.contains(
+            r#"<DATATYPE-DEFINITION-XHTML IDENTIFIER="DATATYPE-DEFINITION-XHTML-IDENTIFIER""#
+        ));
+
+        let read: DataTypes = yaserde::de::from_str(&xml).expect("error");
+        assert_eq!(read, data_types);
+    }
+
+    #[test]
+    fn test_enumeration_attribute() {
+        let mut reqif = document("Enumerations");
+        let now = clock::now();
+        let values = ["Draft", "Reviewed", "Approved"]
+            .iter()
+            .enumerate()
+            .map(|(key, name)| {
+                EnumValue::new(
+                    format!("STATUS-{}", name.to_uppercase()),
+                    now,
+                    name.to_string(),
+                    key as i64,
+                    String::new(),
+                )
+            })
+            .collect();
+        reqif
+            .add_datatype(DataTypeDefinitionEnumeration::new(
+                "DT-STATUS".to_string(),
+                now,
+                "Status".to_string(),
+                values,
+            ))
+            .expect("error");
+        reqif
+            .add_requirement_attribute(AttributeDefinitionEnumeration::new(
+                "AD-STATUS".to_string(),
+                now,
+                "Status".to_string(),
+                false,
+                "DT-STATUS".to_string(),
+            ))
+            .expect("error");
+
+        let status = reqif
+            .core_content
             .req_if_content
-            .specifications
-            .specifications
-            .push(specification);
+            .data_types
+            .get_enumeration_definition("DT-STATUS")
+            .and_then(|d| d.get_value_by_name("Reviewed"))
+            .expect("error");
+        let mut requirement = SpecObject::requirement(
+            "REQ-1".to_string(),
+            now,
+            "First".to_string(),
+            "The first requirement.".to_string(),
+            &reqif.core_content.req_if_content.spec_types,
+        )
+        .expect("error");
+        requirement.set_value(AttributeValueEnumeration::new(
+            "AD-STATUS".to_string(),
+            vec![status.identifier.clone()],
+        ));
+        reqif.add_requirement(requirement).expect("error");
+
+        let xml = yaserde::ser::to_string(&reqif).expect("error");
+        assert!(
+            xml.contains(r#"<PROPERTIES><EMBEDDED-VALUE KEY="1" OTHER-CONTENT="" /></PROPERTIES>"#)
+        );
+        assert!(xml.contains(r#"LONG-NAME="Status" MULTI-VALUED="false"><TYPE><DATATYPE-DEFINITION-ENUMERATION-REF>DT-STATUS</DATATYPE-DEFINITION-ENUMERATION-REF>"#));
+        assert!(
+            xml.contains(r#"<VALUES><ENUM-VALUE-REF>STATUS-REVIEWED</ENUM-VALUE-REF></VALUES>"#)
+        );
+
+        let read = ReqIf::from_reader(xml.as_bytes()).expect("error");
+        assert_eq!(read, reqif);
+    }
+
+    #[test]
+    fn test_spec_object_types() {
+        let mut reqif = document("Types");
+        let now = clock::now();
+        reqif
+            .add_datatype(DataTypeDefinitionBoolean::new(
+                "DT-BOOLEAN".to_string(),
+                now,
+                "Boolean".to_string(),
+            ))
+            .expect("error");
+        reqif
+            .add_datatype(DataTypeDefinitionInteger::new(
+                "DT-INTEGER".to_string(),
+                now,
+                "Integer".to_string(),
+                0,
+                100,
+            ))
+            .expect("error");
+
+        let mut heading =
+            SpecObjectType::new("SOT-HEADING".to_string(), now, "Heading".to_string());
+        heading
+            .add_attribute(AttributeDefinitionXHtml::new(
+                "AD-HEADING-TEXT".to_string(),
+                now,
+                "ReqIF.ChapterName".to_string(),
+                "DATATYPE-DEFINITION-XHTML-IDENTIFIER".to_string(),
+            ))
+            .expect("error");
+        let mut test_case =
+            SpecObjectType::new("SOT-TEST-CASE".to_string(), now, "Test Case".to_string());
+        test_case
+            .add_attribute(AttributeDefinitionBoolean::new(
+                "AD-AUTOMATED".to_string(),
+                now,
+                "Automated".to_string(),
+                "DT-BOOLEAN".to_string(),
+            ))
+            .expect("error");
+        test_case
+            .add_attribute(AttributeDefinitionInteger::new(
+                "AD-DURATION".to_string(),
+                now,
+                "Duration".to_string(),
+                "DT-INTEGER".to_string(),
+            ))
+            .expect("error");
+        assert!(test_case
+            .add_attribute(AttributeDefinitionInteger::new(
+                "AD-DURATION".to_string(),
+                now,
+                "Duration".to_string(),
+                "DT-INTEGER".to_string(),
+            ))
+            .is_err());
+
+        reqif.add_spec_object_type(heading).expect("error");
+        reqif.add_spec_object_type(test_case).expect("error");
+        assert!(reqif
+            .add_spec_object_type(SpecObjectType::new(
+                "SOT-HEADING".to_string(),
+                now,
+                "Heading".to_string(),
+            ))
+            .is_err());
+        assert_eq!(
+            reqif
+                .core_content
+                .req_if_content
+                .spec_types
+                .get_spec_object_types()
+                .len(),
+            3
+        );
+
+        let xml = yaserde::ser::to_string(&reqif).expect("error");
+        assert!(xml.contains(r#"<ATTRIBUTE-DEFINITION-BOOLEAN IDENTIFIER="AD-AUTOMATED""#));
+        let read = ReqIf::from_reader(xml.as_bytes()).expect("error");
+        assert_eq!(read, reqif);
+        assert_eq!(
+            read.get_spec_object_type("SOT-TEST-CASE")
+                .map(|t| t.long_name.as_str()),
+            Some("Test Case")
+        );
+    }
+
+    #[test]
+    fn test_spec_object_values() {
+        let mut reqif = document("Values");
+        let now = clock::now();
+        reqif
+            .add_datatype(DataTypeDefinitionBoolean::new(
+                "DT-BOOLEAN".to_string(),
+                now,
+                "Boolean".to_string(),
+            ))
+            .expect("error");
+        reqif
+            .add_datatype(DataTypeDefinitionDate::new(
+                "DT-DATE".to_string(),
+                now,
+                "Date".to_string(),
+            ))
+            .expect("error");
+        reqif
+            .add_datatype(DataTypeDefinitionInteger::new(
+                "DT-INTEGER".to_string(),
+                now,
+                "Integer".to_string(),
+                0,
+                10,
+            ))
+            .expect("error");
+        reqif
+            .add_datatype(DataTypeDefinitionReal::new(
+                "DT-REAL".to_string(),
+                now,
+                "Real".to_string(),
+                0.0,
+                100.0,
+                2,
+            ))
+            .expect("error");
+        reqif
+            .add_datatype(DataTypeDefinitionString::new(
+                "DT-STRING".to_string(),
+                now,
+                "String".to_string(),
+                64,
+            ))
+            .expect("error");
+
+        let mut item = SpecObjectType::new("SOT-ITEM".to_string(), now, "Item".to_string());
+        item.add_attribute(AttributeDefinitionBoolean::new(
+            "AD-NORMATIVE".to_string(),
+            now,
+            "normative".to_string(),
+            "DT-BOOLEAN".to_string(),
+        ))
+        .expect("error");
+        item.add_attribute(AttributeDefinitionDate::new(
+            "AD-REVIEWED-ON".to_string(),
+            now,
+            "reviewed on".to_string(),
+            "DT-DATE".to_string(),
+        ))
+        .expect("error");
+        item.add_attribute(AttributeDefinitionInteger::new(
+            "AD-LEVEL".to_string(),
+            now,
+            "level".to_string(),
+            "DT-INTEGER".to_string(),
+        ))
+        .expect("error");
+        item.add_attribute(AttributeDefinitionReal::new(
+            "AD-EFFORT".to_string(),
+            now,
+            "effort".to_string(),
+            "DT-REAL".to_string(),
+        ))
+        .expect("error");
+        item.add_attribute(AttributeDefinitionString::new(
+            "AD-REVIEWED".to_string(),
+            now,
+            "reviewed".to_string(),
+            "DT-STRING".to_string(),
+        ))
+        .expect("error");
+        item.add_attribute(AttributeDefinitionXHtml::new(
+            "AD-TEXT".to_string(),
+            now,
+            "text".to_string(),
+            "DATATYPE-DEFINITION-XHTML-IDENTIFIER".to_string(),
+        ))
+        .expect("error");
+        reqif.add_spec_object_type(item).expect("error");
+
+        let mut object = SpecObject::new(
+            "REQ-1".to_string(),
+            now,
+            "First".to_string(),
+            "SOT-ITEM".to_string(),
+        );
+        object.set_value(AttributeValueBoolean::new("AD-NORMATIVE".to_string(), true));
+        object.set_value(AttributeValueDate::new(
+            "AD-REVIEWED-ON".to_string(),
+            "2023-03-01T10:00:00.000+01:00".parse().expect("error"),
+        ));
+        object.set_value(AttributeValueInteger::new("AD-LEVEL".to_string(), 1));
+        object.set_value(AttributeValueInteger::new("AD-LEVEL".to_string(), 2));
+        object.set_value(AttributeValueReal::new("AD-EFFORT".to_string(), 1.5));
+        object.set_value(AttributeValueString::new(
+            "AD-REVIEWED".to_string(),
+            "a1b2c3".to_string(),
+        ));
+        object.set_value(AttributeValueXHtml::new(
+            "AD-TEXT".to_string(),
+            "The text.".to_string(),
+        ));
+        assert_eq!(object.values.integer_values.len(), 1);
+        assert_eq!(object.values.integer_values[0].the_value, 2);
+        assert_eq!(object.values.definitions().len(), 6);
+
+        assert!(reqif
+            .add_spec_object(SpecObject::new(
+                "REQ-2".to_string(),
+                now,
+                "Second".to_string(),
+                "SOT-UNKNOWN".to_string(),
+            ))
+            .is_err());
+        reqif.add_spec_object(object).expect("error");
+        assert!(reqif
+            .add_spec_object(SpecObject::new(
+                "REQ-1".to_string(),
+                now,
+                "Again".to_string(),
+                "SOT-ITEM".to_string(),
+            ))
+            .is_err());
+
+        let xml = yaserde::ser::to_string(&reqif).expect("error");
+        assert!(xml.contains(r#"<ATTRIBUTE-VALUE-BOOLEAN THE-VALUE="true"><DEFINITION><ATTRIBUTE-DEFINITION-BOOLEAN-REF>AD-NORMATIVE</ATTRIBUTE-DEFINITION-BOOLEAN-REF></DEFINITION></ATTRIBUTE-VALUE-BOOLEAN>"#));
+        assert!(xml.contains(r#"<ATTRIBUTE-VALUE-REAL THE-VALUE="1.5">"#));
+        let read = ReqIf::from_reader(xml.as_bytes()).expect("error");
+        assert_eq!(read, reqif);
     }
 
-    pub fn get_module_specification_type(&self) -> &String {
-        &self
+    #[test]
+    fn test_spec_relations() {
+        let mut reqif = document("Relations");
+        let now = clock::now();
+        reqif
+            .add_datatype(DataTypeDefinitionString::new(
+                "DT-STRING".to_string(),
+                now,
+                "String".to_string(),
+                256,
+            ))
+            .expect("error");
+        let mut satisfies =
+            SpecRelationType::new("SRT-SATISFIES".to_string(), now, "satisfies".to_string());
+        satisfies
+            .add_attribute(AttributeDefinitionString::new(
+                "AD-RATIONALE".to_string(),
+                now,
+                "Rationale".to_string(),
+                "DT-STRING".to_string(),
+            ))
+            .expect("error");
+        reqif.add_spec_relation_type(satisfies).expect("error");
+        assert!(reqif
+            .add_spec_relation_type(SpecRelationType::new(
+                "SPEC-OBJEC-TYPE-REQ-TYPE-IDENTIFIER".to_string(),
+                now,
+                "clash".to_string(),
+            ))
+            .is_err());
+
+        for id in ["SYS-1", "SW-1"] {
+            fixture::add_requirement(&mut reqif, id, "Text.");
+        }
+        let mut relation = SpecRelation::new(
+            "REL-1".to_string(),
+            now,
+            String::new(),
+            "SRT-SATISFIES".to_string(),
+            "SW-1".to_string(),
+            "SYS-1".to_string(),
+        );
+        relation.set_value(AttributeValueString::new(
+            "AD-RATIONALE".to_string(),
+            "Refines the system need.".to_string(),
+        ));
+        reqif.add_spec_relation(relation).expect("error");
+        assert!(reqif
+            .add_spec_relation(SpecRelation::new(
+                "REL-1".to_string(),
+                now,
+                String::new(),
+                "SRT-SATISFIES".to_string(),
+                "SW-1".to_string(),
+                "SYS-1".to_string(),
+            ))
+            .is_err());
+        assert!(reqif
+            .add_spec_relation(SpecRelation::new(
+                "REL-2".to_string(),
+                now,
+                String::new(),
+                "SRT-UNKNOWN".to_string(),
+                "SW-1".to_string(),
+                "SYS-1".to_string(),
+            ))
+            .is_err());
+        reqif
+            .add_spec_relation(SpecRelation::new(
+                "REL-3".to_string(),
+                now,
+                String::new(),
+                "SRT-SATISFIES".to_string(),
+                "SYS-1".to_string(),
+                "SW-1".to_string(),
+            ))
+            .expect("error");
+
+        let xml = yaserde::ser::to_string(&reqif).expect("error");
+        assert!(xml.contains(r#"<TYPE><SPEC-RELATION-TYPE-REF>SRT-SATISFIES</SPEC-RELATION-TYPE-REF></TYPE><SOURCE><SPEC-OBJECT-REF>SW-1</SPEC-OBJECT-REF></SOURCE><TARGET><SPEC-OBJECT-REF>SYS-1</SPEC-OBJECT-REF></TARGET>"#));
+        let read = ReqIf::from_reader(xml.as_bytes()).expect("error");
+        assert_eq!(read, reqif);
+        let relations = read
             .core_content
             .req_if_content
-            .spec_types
-            .specification_type_module
-            .identifier
+            .spec_relations
+            .get_spec_relations();
+        assert_eq!(relations.len(), 2);
+        assert!(relations[1].values.definitions().is_empty());
     }
 
-    pub fn write_to(&self, filename: &str) -> anyhow::Result<()> {
-        let yaserde_cfg = yaserde::ser::Config {
-            perform_indent: true,
-            ..Default::default()
-        };
+    #[test]
+    fn test_specification_values() {
+        let mut reqif = document("Specifications");
+        let now = clock::now();
+        reqif
+            .add_datatype(DataTypeDefinitionString::new(
+                "DT-STRING".to_string(),
+                now,
+                "String".to_string(),
+                64,
+            ))
+            .expect("error");
+        let mut document_type =
+            SpecificationType::new("ST-DOCUMENT".to_string(), now, "Document".to_string());
+        document_type
+            .add_attribute(AttributeDefinitionString::new(
+                "AD-VERSION".to_string(),
+                now,
+                "Version".to_string(),
+                "DT-STRING".to_string(),
+            ))
+            .expect("error");
+        reqif.add_specification_type(document_type).expect("error");
+        assert!(reqif
+            .add_specification_type(SpecificationType::new(
+                "ST-DOCUMENT".to_string(),
+                now,
+                "Clash".to_string(),
+            ))
+            .is_err());
 
-        let s = match yaserde::ser::to_string_with_config(self, &yaserde_cfg) {
-            Ok(s) => s,
-            Err(s) => bail!(s),
-        };
+        let module = reqif
+            .build_module_specification("SPEC-1".to_string(), now, "System".to_string())
+            .expect("error");
+        assert_eq!(
+            module.values.xhtml_values,
+            vec![AttributeValueXHtml::new(
+                "ATTRIBUTE-DEFINITION-XHTML-REQIF.NAME-ID".to_string(),
+                "System".to_string(),
+            )]
+        );
+        reqif.add_specification(module).expect("error");
 
-        let mut file = File::create(filename)?;
-        let _ = file.write_all(s.as_bytes());
-        Ok(())
+        let mut document = reqif
+            .build_specification(
+                "SPEC-2".to_string(),
+                now,
+                "Software".to_string(),
+                "ST-DOCUMENT".to_string(),
+            )
+            .expect("error");
+        assert!(document.values.definitions().is_empty());
+        document.set_value(AttributeValueString::new(
+            "AD-VERSION".to_string(),
+            "1.2".to_string(),
+        ));
+        reqif.add_specification(document).expect("error");
+        assert!(reqif
+            .build_specification(
+                "SPEC-3".to_string(),
+                now,
+                "Other".to_string(),
+                "ST-UNKNOWN".to_string(),
+            )
+            .is_err());
+        assert_eq!(reqif.validate(), vec![]);
+
+        let xml = reqif.to_xml_string().expect("error");
+        let read = ReqIf::from_reader(xml.as_bytes()).expect("error");
+        assert_eq!(read, reqif);
+        let spec_types = &read.core_content.req_if_content.spec_types;
+        assert_eq!(spec_types.get_specification_types().len(), 2);
+        assert_eq!(
+            spec_types.get_module_type().map(|t| t.long_name.as_str()),
+            Some("Module Type")
+        );
     }
-}
 
-#[cfg(test)]
-mod test {
-    use super::{get_default_last_change_date, Children, Object, SpecHierarchy};
+    #[test]
+    fn test_relation_groups() {
+        let mut reqif = document("Relation groups");
+        let now = clock::now();
+        for (specification, requirement) in [("SYS", "SYS-1"), ("SW", "SW-1")] {
+            fixture::add_requirement(&mut reqif, requirement, "Text.");
+            let specification = reqif
+                .build_module_specification(
+                    specification.to_string(),
+                    now,
+                    specification.to_string(),
+                )
+                .expect("error");
+            reqif.add_specification(specification).expect("error");
+        }
+        reqif
+            .add_spec_relation_type(SpecRelationType::new(
+                "SRT-SATISFIES".to_string(),
+                now,
+                "satisfies".to_string(),
+            ))
+            .expect("error");
+        reqif
+            .add_spec_relation(SpecRelation::new(
+                "REL-1".to_string(),
+                now,
+                String::new(),
+                "SRT-SATISFIES".to_string(),
+                "SW-1".to_string(),
+                "SYS-1".to_string(),
+            ))
+            .expect("error");
+        reqif
+            .add_relation_group_type(RelationGroupType::new(
+                "RGT-LINKS".to_string(),
+                now,
+                "Links".to_string(),
+            ))
+            .expect("error");
+
+        let mut group = RelationGroup::new(
+            "RG-SW-SYS".to_string(),
+            now,
+            "Software to system".to_string(),
+            "RGT-LINKS".to_string(),
+            "SW".to_string(),
+            "SYS".to_string(),
+        );
+        group.add_spec_relation("REL-1".to_string());
+        group.add_spec_relation("REL-1".to_string());
+        assert_eq!(group.spec_relations.references.len(), 1);
+        group.add_spec_relation("REL-2".to_string());
+        assert!(reqif.add_relation_group(group).is_err());
+
+        let mut group = RelationGroup::new(
+            "RG-SW-SYS".to_string(),
+            now,
+            "Software to system".to_string(),
+            "RGT-LINKS".to_string(),
+            "SW".to_string(),
+            "SYS".to_string(),
+        );
+        group.add_spec_relation("REL-1".to_string());
+        reqif.add_relation_group(group).expect("error");
+
+        let xml = yaserde::ser::to_string(&reqif).expect("error");
+        assert!(xml.contains(r#"<SPEC-RELATION-GROUPS><RELATION-GROUP IDENTIFIER="RG-SW-SYS""#));
+        assert!(xml.contains(r#"<SOURCE-SPECIFICATION><SPECIFICATION-REF>SW</SPECIFICATION-REF></SOURCE-SPECIFICATION><TARGET-SPECIFICATION><SPECIFICATION-REF>SYS</SPECIFICATION-REF></TARGET-SPECIFICATION><SPEC-RELATIONS><SPEC-RELATION-REF>REL-1</SPEC-RELATION-REF></SPEC-RELATIONS>"#));
+        let read = ReqIf::from_reader(xml.as_bytes()).expect("error");
+        assert_eq!(read, reqif);
+    }
+
+    /// Accepts `capacity` bytes, then fails like a full disk.
+    struct FullDisk {
+        capacity: usize,
+    }
+
+    impl std::io::Write for FullDisk {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            if self.capacity < buf.len() {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::WriteZero,
+                    "No space left on device",
+                ));
+            }
+            self.capacity -= buf.len();
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
 
     #[test]
-    fn test_add_spec_hierarchy() {
-        let mut children = Children::new();
-        for _ in 0..4 {
-            children
+    fn test_write_errors() {
+        let reqif = document("Writing");
+        let mut buffer = vec![];
+        reqif.write(&mut buffer).expect("error");
+        let xml = reqif.to_xml_string().expect("error");
+        assert_eq!(xml.as_bytes(), buffer.as_slice());
+
+        let error = reqif.write(FullDisk { capacity: 100 }).expect_err("error");
+        let error = error.downcast::<std::io::Error>().expect("error");
+        assert_eq!(error.kind(), std::io::ErrorKind::WriteZero);
+        assert_eq!(error.to_string(), "No space left on device");
+
+        assert!(reqif.write_to("missing-directory/out.reqif").is_err());
+    }
+
+    fn build_deterministic(ids: &[&str]) -> ReqIf {
+        let creation_time = fixture::created();
+        let mut reqif = ReqIf::new_deterministic(
+            "ID-1".to_string(),
+            creation_time,
+            "repository".to_string(),
+            "reqif-rs".to_string(),
+            "Doorstop".to_string(),
+            "Baseline".to_string(),
+        );
+        for id in ids {
+            let requirement = SpecObject::requirement(
+                id.to_string(),
+                creation_time.into(),
+                id.to_string(),
+                "Text.".to_string(),
+                &reqif.core_content.req_if_content.spec_types,
+            )
+            .expect("error");
+            reqif.add_spec_object(requirement).expect("error");
+        }
+        reqif
+    }
+
+    #[test]
+    fn test_deterministic_output() {
+        let first_run = Local.with_ymd_and_hms(2024, 6, 1, 9, 0, 0).unwrap();
+        let second_run = Local.with_ymd_and_hms(2024, 6, 2, 9, 0, 0).unwrap();
+        clock::set_clock(FixedClock::new(first_run));
+        let first = build_deterministic(&["REQ-1", "REQ-2", "REQ-3"]);
+        clock::set_clock(FixedClock::new(second_run));
+        let mut second = build_deterministic(&["REQ-3", "REQ-1", "REQ-2"]);
+        clock::reset_clock();
+        for id in ["REQ-1", "REQ-2", "REQ-3"] {
+            let spec_object = second.get_spec_object_mut(id).unwrap();
+            spec_object.values.xhtml_values.reverse();
+        }
+
+        let xml = first.to_xml_string().expect("error");
+        assert_eq!(xml, second.to_xml_string().expect("error"));
+        assert!(crate::schema::validate(&xml).is_empty());
+        for run in [first_run, second_run] {
+            assert!(!xml.contains(&Timestamp::from(run).to_string()));
+        }
+
+        let positions: Vec<usize> = ["REQ-1", "REQ-2", "REQ-3"]
+            .iter()
+            .map(|id| xml.find(&format!("IDENTIFIER=\"{}\"", id)).unwrap())
+            .collect();
+        assert!(positions.windows(2).all(|w| w[0] < w[1]));
+
+        // Writing doesn't reorder the document itself.
+        let ids: Vec<&str> = second
+            .core_content
+            .req_if_content
+            .spec_object
+            .get_spec_objects()
+            .iter()
+            .map(|o| o.identifier.as_str())
+            .collect();
+        assert_eq!(ids, ["REQ-3", "REQ-1", "REQ-2"]);
+    }
+
+    #[test]
+    fn test_frozen_clock_timestamps() {
+        let reqif = document("Frozen");
+        let time = fixture::created();
+
+        let data_types = &reqif.core_content.req_if_content.data_types;
+        assert_eq!(data_types.xhtml_definitions[0].last_change, time.into());
+        let xml = reqif.to_xml_string().expect("error");
+        let expected = format!("LAST-CHANGE=\"{}\"", Timestamp::from(time));
+        assert_eq!(xml.matches(&expected).count(), 6);
+
+        let garbage = xml.replacen(&expected, "LAST-CHANGE=\"last tuesday\"", 1);
+        assert!(ReqIf::from_reader(garbage.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_track_changes() {
+        let created = fixture::created();
+        let edited = Local.with_ymd_and_hms(2024, 6, 1, 9, 30, 0).unwrap();
+        let mut reqif = build_deterministic(&["REQ-1", "REQ-2"]);
+        let mut specification = reqif
+            .build_module_specification("SPEC-1".to_string(), created.into(), "Module".to_string())
+            .expect("error");
+        for (id, object) in [("H-1", "REQ-1"), ("H-2", "REQ-2")] {
+            specification
+                .children
                 .add_spec_hierarchy(
-                    SpecHierarchy::new(
-                        "2.1.2".to_string(),
-                        get_default_last_change_date(),
-                        Object::new("REQ001".to_string()),
-                    ),
+                    SpecHierarchy::new(id.to_string(), created.into(), Object::new(object.into())),
                     0,
                 )
-                .expect("Error");
+                .expect("error");
         }
-        assert_eq!(children.spec_hierarchy.len(), 4);
+        reqif.add_specification(specification).expect("error");
+        let last_change = |reqif: &ReqIf| {
+            (
+                reqif.get_spec_object("REQ-1").unwrap().last_change,
+                reqif.get_specification("SPEC-1").unwrap().last_change,
+            )
+        };
+
+        clock::set_clock(FixedClock::new(edited));
+        reqif
+            .update_spec_object("REQ-1", |o| o.long_name = "Untracked".to_string())
+            .expect("error");
+        assert_eq!(last_change(&reqif), (created.into(), created.into()));
+
+        reqif.set_track_changes(true, false);
+        reqif
+            .update_spec_object("REQ-1", |o| o.long_name.len())
+            .expect("error");
+        assert_eq!(last_change(&reqif), (created.into(), created.into()));
+        reqif.get_spec_object_mut("REQ-1").unwrap().long_name = "Raw".to_string();
+        assert_eq!(last_change(&reqif), (created.into(), created.into()));
+        reqif
+            .update_spec_object("REQ-1", |o| o.long_name = "Tracked".to_string())
+            .expect("error");
+        assert_eq!(last_change(&reqif), (edited.into(), created.into()));
+
+        reqif.set_track_changes(true, true);
+        reqif
+            .update_spec_hierarchy("H-2", |n| n.object.object_ref = "REQ-1".to_string())
+            .expect("error");
+        let node = reqif
+            .get_specification("SPEC-1")
+            .unwrap()
+            .children
+            .get_at(&[1]);
+        assert_eq!(node.unwrap().last_change, edited.into());
+        assert_eq!(last_change(&reqif), (edited.into(), edited.into()));
+
+        reqif
+            .update_spec_object_type(DEFAULT_REQUIREMENT_TYPE_IDENTIFIER, |t| {
+                t.long_name = "Requirement".to_string()
+            })
+            .expect("error");
+        let explicit = Timestamp::from(created);
+        reqif
+            .update_specification("SPEC-1", |s| {
+                s.long_name = "Renamed".to_string();
+                s.last_change = explicit;
+            })
+            .expect("error");
+        clock::reset_clock();
+
+        let spec_types = &reqif.core_content.req_if_content.spec_types;
+        assert_eq!(
+            spec_types.get_requirement_type().unwrap().last_change,
+            edited.into()
+        );
+        assert_eq!(last_change(&reqif).1, explicit);
+        assert!(reqif.update_spec_object("REQ-9", |_| ()).is_err());
     }
 
     #[test]
-    fn test_add_spec_panic() {
-        let mut children = Children::new();
-        children
-            .add_spec_hierarchy(
-                SpecHierarchy::new(
-                    "2.1.2".to_string(),
-                    get_default_last_change_date(),
-                    Object::new("REQ001".to_string()),
-                ),
-                0,
+    fn test_identifiers() {
+        let mut reqif = document("Identifiers");
+        let requirement = |id: &str, text: &str, reqif: &ReqIf| {
+            SpecObject::requirement(
+                id.to_string(),
+                clock::now(),
+                String::new(),
+                text.to_string(),
+                &reqif.core_content.req_if_content.spec_types,
             )
+            .expect("error")
+        };
+
+        identifier::set_id_generator(CounterGenerator::new("REQ-".to_string()));
+        reqif
+            .add_spec_object(requirement("REQ-2", "Taken.", &reqif))
             .expect("error");
-        let res = children.add_spec_hierarchy(
-            SpecHierarchy::new(
-                "2.1.2".to_string(),
-                get_default_last_change_date(),
-                Object::new("REQ001".to_string()),
-            ),
-            2,
-        );
-        assert!(res.is_err());
+        for text in ["First.", "Second."] {
+            reqif
+                .add_spec_object(requirement("", text, &reqif))
+                .expect("error");
+        }
+        identifier::set_id_generator(UuidV5Generator::default());
+        for _ in 0..2 {
+            reqif
+                .add_spec_object(requirement("", "Same.", &reqif))
+                .expect("error");
+        }
+        identifier::reset_id_generator();
+
+        let ids: Vec<&str> = reqif
+            .core_content
+            .req_if_content
+            .spec_object
+            .get_spec_objects()
+            .iter()
+            .map(|o| o.identifier.as_str())
+            .collect();
+        assert_eq!(ids[..3], ["REQ-2", "REQ-1", "REQ-3"]);
+        assert_eq!(ids[4], format!("{}-2", ids[3]));
+        assert!(reqif.contains_identifier(ids[3]));
+
+        assert!(reqif
+            .add_spec_object(requirement("1.2", "Digit.", &reqif))
+            .is_err());
+        reqif.set_sanitize_identifiers(true);
+        reqif
+            .add_spec_object(requirement("1.2", "Digit.", &reqif))
+            .expect("error");
+        assert!(reqif.contains_identifier("_1.2"));
+        reqif.set_sanitize_identifiers(false);
+        assert!(reqif
+            .build_module_specification("2 M".to_string(), clock::now(), "Module".to_string())
+            .is_err());
+
+        let specification = reqif
+            .build_module_specification(String::new(), clock::now(), "Module".to_string())
+            .expect("error");
+        assert!(is_valid_ncname(&specification.identifier));
+        assert!(reqif.validate().is_empty());
     }
 
     #[test]
-    fn test_add_spec_hierarchy_2() {
-        let mut children = Children::new();
-        children
-            .add_spec_hierarchy(
-                SpecHierarchy::new(
-                    "2.1.2".to_string(),
-                    get_default_last_change_date(),
-                    Object::new("REQ001".to_string()),
-                ),
-                0,
-            )
+    fn test_identifiers_across_sections() {
+        let mut reqif = fixture::module("Sections", &["REQ-1", "REQ-2"]);
+        let now = clock::now();
+        reqif
+            .add_spec_relation_type(SpecRelationType::new(
+                "SRT-TRACE".to_string(),
+                now,
+                "trace".to_string(),
+            ))
             .expect("error");
-        children
-            .add_spec_hierarchy(
-                SpecHierarchy::new(
-                    "2.1.2".to_string(),
-                    get_default_last_change_date(),
-                    Object::new("REQ001".to_string()),
-                ),
-                1,
+        reqif
+            .add_relation_group_type(RelationGroupType::new(
+                "RGT-LINKS".to_string(),
+                now,
+                "links".to_string(),
+            ))
+            .expect("error");
+        let relation = |identifier: &str| {
+            SpecRelation::new(
+                identifier.to_string(),
+                now,
+                String::new(),
+                "SRT-TRACE".to_string(),
+                "REQ-1".to_string(),
+                "REQ-2".to_string(),
+            )
+        };
+        reqif.add_spec_relation(relation("REL-1")).expect("error");
+
+        for taken in [
+            "DATATYPE-DEFINITION-XHTML-IDENTIFIER",
+            "REQ-1",
+            "SPEC-1",
+            "H-REQ-1",
+            "REL-1",
+        ] {
+            let requirement = SpecObject::requirement(
+                taken.to_string(),
+                now,
+                "Taken".to_string(),
+                "Text.".to_string(),
+                &reqif.core_content.req_if_content.spec_types,
             )
             .expect("error");
+            assert!(reqif.add_spec_object(requirement).is_err(), "{}", taken);
+            assert!(
+                reqif.add_spec_relation(relation(taken)).is_err(),
+                "{}",
+                taken
+            );
+            let group = RelationGroup::new(
+                taken.to_string(),
+                now,
+                "Group".to_string(),
+                "RGT-LINKS".to_string(),
+                "SPEC-1".to_string(),
+                "SPEC-1".to_string(),
+            );
+            assert!(reqif.add_relation_group(group).is_err(), "{}", taken);
+        }
 
-        children
-            .add_spec_hierarchy(
-                SpecHierarchy::new(
-                    "2.1.2".to_string(),
-                    get_default_last_change_date(),
-                    Object::new("REQ001".to_string()),
-                ),
-                1,
+        let module = reqif.get_module_specification_type().cloned().unwrap();
+        let clash = Specification::new(
+            "SPEC-1".to_string(),
+            now,
+            "Clash".to_string(),
+            module.clone(),
+        );
+        assert!(reqif.add_specification(clash).is_err());
+        let mut specification =
+            Specification::new("SPEC-2".to_string(), now, "Nodes".to_string(), module);
+        for (node, object) in [("H-NEW", "REQ-2"), ("H-REQ-1", "REQ-1")] {
+            specification
+                .children
+                .add_spec_hierarchy(
+                    SpecHierarchy::new(node.to_string(), now, Object::new(object.to_string())),
+                    0,
+                )
+                .expect("error");
+        }
+        assert!(reqif.add_specification(specification.clone()).is_err());
+        assert!(!reqif.contains_identifier("SPEC-2"));
+        assert!(!reqif.contains_identifier("H-NEW"));
+        specification.children.remove("H-REQ-1");
+        reqif.add_specification(specification).expect("error");
+        assert!(reqif.contains_identifier("H-NEW"));
+
+        let node = |identifier: &str| {
+            SpecHierarchy::new(
+                identifier.to_string(),
+                now,
+                Object::new("REQ-1".to_string()),
             )
+        };
+        assert!(Children::new().add_spec_hierarchy(node("2 H"), 0).is_err());
+        assert!(reqif
+            .insert_spec_hierarchy("SPEC-2", None, 0, node("REL-1"))
+            .is_err());
+        reqif
+            .insert_spec_hierarchy("SPEC-2", Some("H-NEW"), 0, node(""))
             .expect("error");
+        let generated = &reqif
+            .get_specification("SPEC-2")
+            .unwrap()
+            .children
+            .get_at(&[0, 0]);
+        let generated = generated.unwrap().identifier.clone();
+        assert!(is_valid_ncname(&generated));
+        assert!(reqif.contains_identifier(&generated));
 
-        assert_eq!(children.spec_hierarchy.len(), 1);
-        let spec = children.get_spec_hierarchy().last().unwrap();
-        let len = spec.children.as_ref().unwrap().spec_hierarchy.len();
-        assert_eq!(len, 2)
+        reqif.rename_spec_object("REQ-2", "REQ-3").expect("error");
+        reqif.remove_spec_object("REQ-1", true);
+        for released in ["REQ-2", "REL-1", "H-REQ-1", generated.as_str()] {
+            assert!(!reqif.contains_identifier(released), "{}", released);
+        }
+        reqif
+            .add_datatype(DataTypeDefinitionString::new(
+                "REQ-2".to_string(),
+                now,
+                "String".to_string(),
+                10,
+            ))
+            .expect("error");
+        assert!(reqif.validate().is_empty());
+    }
+
+    #[test]
+    fn test_datatype_schema_rules() {
+        let mut reqif = document("Datatypes");
+        let now = clock::now();
+        reqif
+            .add_datatype(DataTypeDefinitionBoolean::new(
+                "DT-BOOLEAN".to_string(),
+                now,
+                "Boolean".to_string(),
+            ))
+            .expect("error");
+        reqif
+            .add_datatype(DataTypeDefinitionDate::new(
+                "DT-DATE".to_string(),
+                now,
+                "Date".to_string(),
+            ))
+            .expect("error");
+        reqif
+            .add_datatype(DataTypeDefinitionEnumeration::new(
+                "DT-ENUMERATION".to_string(),
+                now,
+                "Status".to_string(),
+                vec![EnumValue::new(
+                    "STATUS-DRAFT".to_string(),
+                    now,
+                    "Draft".to_string(),
+                    0,
+                    String::new(),
+                )],
+            ))
+            .expect("error");
+        reqif
+            .add_datatype(DataTypeDefinitionInteger::new(
+                "DT-INTEGER".to_string(),
+                now,
+                "Integer".to_string(),
+                -10,
+                10,
+            ))
+            .expect("error");
+        reqif
+            .add_datatype(DataTypeDefinitionReal::new(
+                "DT-REAL".to_string(),
+                now,
+                "Real".to_string(),
+                0.0,
+                1.0,
+                3,
+            ))
+            .expect("error");
+        reqif
+            .add_datatype(DataTypeDefinitionString::new(
+                "DT-STRING".to_string(),
+                now,
+                "String".to_string(),
+                32,
+            ))
+            .expect("error");
+        let mut object_type =
+            SpecObjectType::new("SOT-ALL".to_string(), now, "All kinds".to_string());
+        let definitions: Vec<AttributeDefinition> = vec![
+            AttributeDefinitionBoolean::new(
+                "AD-BOOLEAN".to_string(),
+                now,
+                "b".to_string(),
+                "DT-BOOLEAN".to_string(),
+            )
+            .into(),
+            AttributeDefinitionDate::new(
+                "AD-DATE".to_string(),
+                now,
+                "d".to_string(),
+                "DT-DATE".to_string(),
+            )
+            .into(),
+            AttributeDefinitionEnumeration::new(
+                "AD-ENUMERATION".to_string(),
+                now,
+                "e".to_string(),
+                false,
+                "DT-ENUMERATION".to_string(),
+            )
+            .into(),
+            AttributeDefinitionInteger::new(
+                "AD-INTEGER".to_string(),
+                now,
+                "i".to_string(),
+                "DT-INTEGER".to_string(),
+            )
+            .into(),
+            AttributeDefinitionReal::new(
+                "AD-REAL".to_string(),
+                now,
+                "r".to_string(),
+                "DT-REAL".to_string(),
+            )
+            .into(),
+            AttributeDefinitionString::new(
+                "AD-STRING".to_string(),
+                now,
+                "s".to_string(),
+                "DT-STRING".to_string(),
+            )
+            .into(),
+        ];
+        for definition in definitions {
+            object_type.add_attribute(definition).expect("error");
+        }
+        reqif.add_spec_object_type(object_type).expect("error");
+        assert!(reqif.validate().is_empty());
+        let xml = reqif.to_xml_string().expect("error");
+
+        assert_eq!(crate::schema::validate(&xml), vec![]);
+        let identifier = xml
+            .split("<DATATYPE-DEFINITION-XHTML ")
+            .nth(1)
+            .and_then(|element| element.split("IDENTIFIER=\"").nth(1))
+            .and_then(|value| value.split('"').next())
+            .expect("error");
+        let references: Vec<&str> = xml
+            .split("<DATATYPE-DEFINITION-XHTML-REF>")
+            .skip(1)
+            .filter_map(|reference| reference.split('<').next())
+            .collect();
+        assert!(!references.is_empty());
+        for reference in references {
+            assert_eq!(reference, identifier);
+        }
+        for kind in [
+            "BOOLEAN",
+            "DATE",
+            "ENUMERATION",
+            "INTEGER",
+            "REAL",
+            "STRING",
+            "XHTML",
+        ] {
+            assert!(xml.contains(&format!("<DATATYPE-DEFINITION-{} ", kind)));
+        }
     }
 }
@@ -0,0 +1,1620 @@
+// reqif-rs: Help library to write reqif files implemented in Rust.
+// Copyright (C) <2024>  INVAP S.E.
+//
+// This file is part of reqif-rs.
+//
+// reqif-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//! Offline checking of ReqIF XML against the content model of the OMG ReqIF
+//! 1.0.1 schema (`reqif.xsd`).
+//!
+//! The rules of the schema are embedded in this module: allowed and required
+//! elements and attributes, attribute and text datatypes, unique IDENTIFIERs
+//! and resolvable references. Content models written as `xsd:sequence` are
+//! checked in order, `xsd:all` and `xsd:choice` ones in any order.
+//!
+//! XHTML content must be a single `xhtml:div` or `xhtml:p`, built from the
+//! elements of the XHTML 1.1 modules the ReqIF XHTML driver includes, each
+//! nested as its module allows.
+//!
+//! The rules are written by hand after the schema, not generated from it.
+//! Known deviations:
+//!
+//! - Attributes of other namespaces, like `xml:lang`, are allowed on every
+//!   element, not only on REQ-IF.
+//! - The content of REQ-IF-TOOL-EXTENSION is not checked.
+//! - Attributes of XHTML elements are not checked, nor the exclusions of
+//!   the XHTML DTDs, like no `a` within `a` or no `sub` within `pre`.
+//! - xsd:integer values must fit in 128 bits. xsd:dateTime values are
+//!   parsed as RFC 3339: years have four digits, and a space is accepted
+//!   instead of `T`.
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use xml::common::{Position, TextPosition};
+use xml::reader::{EventReader, XmlEvent};
+
+use crate::clock::Timestamp;
+use crate::identifier::is_valid_ncname;
+use crate::writer::REQIF_NAMESPACE;
+use crate::xhtml::XHTML_NAMESPACE;
+
+/// A schema violation found by [`validate`], with its 1-based position.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SchemaError {
+    pub line: u64,
+    pub column: u64,
+    pub message: String,
+}
+
+impl fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}: {}", self.line, self.column, self.message)
+    }
+}
+
+impl std::error::Error for SchemaError {}
+
+/// Simple types of attributes and text content.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Value {
+    String,
+    Boolean,
+    Integer,
+    Double,
+    DateTime,
+    Id,
+    IdRef,
+}
+
+impl Value {
+    fn accepts(self, value: &str) -> bool {
+        let value = value.trim();
+        match self {
+            Value::String => true,
+            Value::Boolean => matches!(value, "true" | "false" | "1" | "0"),
+            Value::Integer => value.parse::<i128>().is_ok(),
+            Value::Double => {
+                matches!(value, "INF" | "-INF" | "NaN")
+                    || (!value.contains(char::is_alphabetic) || value.contains(['e', 'E']))
+                        && value.parse::<f64>().is_ok()
+            }
+            Value::DateTime => value.parse::<Timestamp>().is_ok(),
+            Value::Id | Value::IdRef => is_valid_ncname(value),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Value::String => "xsd:string",
+            Value::Boolean => "xsd:boolean",
+            Value::Integer => "xsd:integer",
+            Value::Double => "xsd:double",
+            Value::DateTime => "xsd:dateTime",
+            Value::Id => "xsd:ID",
+            Value::IdRef => "xsd:IDREF",
+        }
+    }
+}
+
+struct Attribute {
+    name: &'static str,
+    value: Value,
+    required: bool,
+}
+
+const fn required(name: &'static str, value: Value) -> Attribute {
+    Attribute {
+        name,
+        value,
+        required: true,
+    }
+}
+
+const fn optional(name: &'static str, value: Value) -> Attribute {
+    Attribute {
+        name,
+        value,
+        required: false,
+    }
+}
+
+struct Child {
+    name: &'static str,
+    min: usize,
+    max: Option<usize>,
+}
+
+const fn one(name: &'static str) -> Child {
+    Child {
+        name,
+        min: 1,
+        max: Some(1),
+    }
+}
+
+const fn maybe(name: &'static str) -> Child {
+    Child {
+        name,
+        min: 0,
+        max: Some(1),
+    }
+}
+
+const fn many(name: &'static str) -> Child {
+    Child {
+        name,
+        min: 0,
+        max: None,
+    }
+}
+
+enum Content {
+    Text(Value),
+    /// Child elements, in the given order when `ordered`.
+    Elements {
+        ordered: bool,
+        children: &'static [Child],
+    },
+    /// A single `xhtml:div` or `xhtml:p`.
+    XHtml,
+    /// Anything, as in TOOL-EXTENSIONS.
+    Any,
+}
+
+/// Rule for the elements named `key`, or `PARENT/NAME` for the elements
+/// whose content depends on where they appear.
+struct Element {
+    key: &'static str,
+    /// Whether it has the DESC, IDENTIFIER, LAST-CHANGE and LONG-NAME
+    /// attributes of an Identifiable.
+    identifiable: bool,
+    attributes: &'static [Attribute],
+    content: Content,
+}
+
+const IDENTIFIABLE_ATTRIBUTES: [Attribute; 4] = [
+    optional("DESC", Value::String),
+    required("IDENTIFIER", Value::Id),
+    required("LAST-CHANGE", Value::DateTime),
+    optional("LONG-NAME", Value::String),
+];
+
+const fn elements(children: &'static [Child]) -> Content {
+    Content::Elements {
+        ordered: false,
+        children,
+    }
+}
+
+const fn sequence(children: &'static [Child]) -> Content {
+    Content::Elements {
+        ordered: true,
+        children,
+    }
+}
+
+const fn element(key: &'static str, content: Content) -> Element {
+    Element {
+        key,
+        identifiable: false,
+        attributes: &[],
+        content,
+    }
+}
+
+const fn identifiable(
+    key: &'static str,
+    attributes: &'static [Attribute],
+    children: &'static [Child],
+) -> Element {
+    Element {
+        key,
+        identifiable: true,
+        attributes,
+        content: elements(children),
+    }
+}
+
+/// Rules of one datatype kind: its definition, attribute definition and
+/// attribute value.
+macro_rules! datatype_rules {
+    ($kind:literal, $datatype_attributes:expr, $datatype_children:expr, $value_attributes:expr, $value_children:expr) => {
+        [
+            identifiable(
+                concat!("DATATYPE-DEFINITION-", $kind),
+                $datatype_attributes,
+                $datatype_children,
+            ),
+            identifiable(
+                concat!("ATTRIBUTE-DEFINITION-", $kind),
+                ATTRIBUTE_DEFINITION_ATTRIBUTES,
+                &[maybe("ALTERNATIVE-ID"), maybe("DEFAULT-VALUE"), one("TYPE")],
+            ),
+            element(
+                concat!("ATTRIBUTE-DEFINITION-", $kind, "/TYPE"),
+                elements(&[one(concat!("DATATYPE-DEFINITION-", $kind, "-REF"))]),
+            ),
+            element(
+                concat!("ATTRIBUTE-DEFINITION-", $kind, "/DEFAULT-VALUE"),
+                elements(&[one(concat!("ATTRIBUTE-VALUE-", $kind))]),
+            ),
+            Element {
+                key: concat!("ATTRIBUTE-VALUE-", $kind),
+                identifiable: false,
+                attributes: $value_attributes,
+                content: elements($value_children),
+            },
+            element(
+                concat!("ATTRIBUTE-VALUE-", $kind, "/DEFINITION"),
+                elements(&[one(concat!("ATTRIBUTE-DEFINITION-", $kind, "-REF"))]),
+            ),
+        ]
+    };
+}
+
+const ATTRIBUTE_DEFINITION_ATTRIBUTES: &[Attribute] = &[optional("IS-EDITABLE", Value::Boolean)];
+const NO_ATTRIBUTES: &[Attribute] = &[];
+const ALTERNATIVE_ID: &[Child] = &[maybe("ALTERNATIVE-ID")];
+const DEFINITION: &[Child] = &[one("DEFINITION")];
+
+const DATATYPE_RULES: [[Element; 6]; 7] = [
+    datatype_rules!(
+        "BOOLEAN",
+        NO_ATTRIBUTES,
+        ALTERNATIVE_ID,
+        &[required("THE-VALUE", Value::Boolean)],
+        DEFINITION
+    ),
+    datatype_rules!(
+        "DATE",
+        NO_ATTRIBUTES,
+        ALTERNATIVE_ID,
+        &[required("THE-VALUE", Value::DateTime)],
+        DEFINITION
+    ),
+    datatype_rules!(
+        "ENUMERATION",
+        NO_ATTRIBUTES,
+        &[maybe("ALTERNATIVE-ID"), maybe("SPECIFIED-VALUES")],
+        NO_ATTRIBUTES,
+        &[one("DEFINITION"), maybe("VALUES")]
+    ),
+    datatype_rules!(
+        "INTEGER",
+        &[
+            required("MAX", Value::Integer),
+            required("MIN", Value::Integer)
+        ],
+        ALTERNATIVE_ID,
+        &[required("THE-VALUE", Value::Integer)],
+        DEFINITION
+    ),
+    datatype_rules!(
+        "REAL",
+        &[
+            required("ACCURACY", Value::Integer),
+            required("MAX", Value::Double),
+            required("MIN", Value::Double)
+        ],
+        ALTERNATIVE_ID,
+        &[required("THE-VALUE", Value::Double)],
+        DEFINITION
+    ),
+    datatype_rules!(
+        "STRING",
+        &[required("MAX-LENGTH", Value::Integer)],
+        ALTERNATIVE_ID,
+        &[required("THE-VALUE", Value::String)],
+        DEFINITION
+    ),
+    datatype_rules!(
+        "XHTML",
+        NO_ATTRIBUTES,
+        ALTERNATIVE_ID,
+        &[optional("IS-SIMPLIFIED", Value::Boolean)],
+        &[
+            one("DEFINITION"),
+            maybe("THE-ORIGINAL-VALUE"),
+            one("THE-VALUE")
+        ]
+    ),
+];
+
+const ATTRIBUTE_DEFINITIONS: &[Child] = &[
+    many("ATTRIBUTE-DEFINITION-BOOLEAN"),
+    many("ATTRIBUTE-DEFINITION-DATE"),
+    many("ATTRIBUTE-DEFINITION-ENUMERATION"),
+    many("ATTRIBUTE-DEFINITION-INTEGER"),
+    many("ATTRIBUTE-DEFINITION-REAL"),
+    many("ATTRIBUTE-DEFINITION-STRING"),
+    many("ATTRIBUTE-DEFINITION-XHTML"),
+];
+
+const SPEC_TYPE_CHILDREN: &[Child] = &[maybe("ALTERNATIVE-ID"), maybe("SPEC-ATTRIBUTES")];
+
+const RULES: &[Element] = &[
+    element(
+        "REQ-IF",
+        sequence(&[
+            one("THE-HEADER"),
+            one("CORE-CONTENT"),
+            maybe("TOOL-EXTENSIONS"),
+        ]),
+    ),
+    element("THE-HEADER", elements(&[one("REQ-IF-HEADER")])),
+    Element {
+        key: "REQ-IF-HEADER",
+        identifiable: false,
+        attributes: &[required("IDENTIFIER", Value::Id)],
+        content: elements(&[
+            maybe("COMMENT"),
+            one("CREATION-TIME"),
+            maybe("REPOSITORY-ID"),
+            one("REQ-IF-TOOL-ID"),
+            one("REQ-IF-VERSION"),
+            one("SOURCE-TOOL-ID"),
+            one("TITLE"),
+        ]),
+    },
+    element("COMMENT", Content::Text(Value::String)),
+    element("CREATION-TIME", Content::Text(Value::DateTime)),
+    element("REPOSITORY-ID", Content::Text(Value::String)),
+    element("REQ-IF-TOOL-ID", Content::Text(Value::String)),
+    element("REQ-IF-VERSION", Content::Text(Value::String)),
+    element("SOURCE-TOOL-ID", Content::Text(Value::String)),
+    element("TITLE", Content::Text(Value::String)),
+    element(
+        "TOOL-EXTENSIONS",
+        elements(&[many("REQ-IF-TOOL-EXTENSION")]),
+    ),
+    element("REQ-IF-TOOL-EXTENSION", Content::Any),
+    element("CORE-CONTENT", elements(&[one("REQ-IF-CONTENT")])),
+    element(
+        "REQ-IF-CONTENT",
+        elements(&[
+            maybe("DATATYPES"),
+            maybe("SPEC-TYPES"),
+            maybe("SPEC-OBJECTS"),
+            maybe("SPEC-RELATIONS"),
+            maybe("SPECIFICATIONS"),
+            maybe("SPEC-RELATION-GROUPS"),
+        ]),
+    ),
+    element("ALTERNATIVE-ID", elements(&[one("ALTERNATIVE-ID")])),
+    Element {
+        key: "ALTERNATIVE-ID/ALTERNATIVE-ID",
+        identifiable: false,
+        attributes: &[required("IDENTIFIER", Value::String)],
+        content: elements(&[]),
+    },
+    element(
+        "DATATYPES",
+        elements(&[
+            many("DATATYPE-DEFINITION-BOOLEAN"),
+            many("DATATYPE-DEFINITION-DATE"),
+            many("DATATYPE-DEFINITION-ENUMERATION"),
+            many("DATATYPE-DEFINITION-INTEGER"),
+            many("DATATYPE-DEFINITION-REAL"),
+            many("DATATYPE-DEFINITION-STRING"),
+            many("DATATYPE-DEFINITION-XHTML"),
+        ]),
+    ),
+    element("SPECIFIED-VALUES", elements(&[many("ENUM-VALUE")])),
+    identifiable(
+        "ENUM-VALUE",
+        &[],
+        &[maybe("ALTERNATIVE-ID"), one("PROPERTIES")],
+    ),
+    element("PROPERTIES", elements(&[one("EMBEDDED-VALUE")])),
+    Element {
+        key: "EMBEDDED-VALUE",
+        identifiable: false,
+        attributes: &[
+            required("KEY", Value::Integer),
+            required("OTHER-CONTENT", Value::String),
+        ],
+        content: elements(&[]),
+    },
+    element(
+        "SPEC-TYPES",
+        elements(&[
+            many("SPEC-OBJECT-TYPE"),
+            many("SPECIFICATION-TYPE"),
+            many("SPEC-RELATION-TYPE"),
+            many("RELATION-GROUP-TYPE"),
+        ]),
+    ),
+    identifiable("SPEC-OBJECT-TYPE", &[], SPEC_TYPE_CHILDREN),
+    identifiable("SPECIFICATION-TYPE", &[], SPEC_TYPE_CHILDREN),
+    identifiable("SPEC-RELATION-TYPE", &[], SPEC_TYPE_CHILDREN),
+    identifiable("RELATION-GROUP-TYPE", &[], SPEC_TYPE_CHILDREN),
+    element("SPEC-ATTRIBUTES", elements(ATTRIBUTE_DEFINITIONS)),
+    Element {
+        key: "ATTRIBUTE-DEFINITION-ENUMERATION",
+        identifiable: true,
+        attributes: &[
+            optional("IS-EDITABLE", Value::Boolean),
+            required("MULTI-VALUED", Value::Boolean),
+        ],
+        content: elements(&[maybe("ALTERNATIVE-ID"), maybe("DEFAULT-VALUE"), one("TYPE")]),
+    },
+    element(
+        "ATTRIBUTE-VALUE-ENUMERATION/VALUES",
+        elements(&[many("ENUM-VALUE-REF")]),
+    ),
+    element("ATTRIBUTE-VALUE-XHTML/THE-VALUE", Content::XHtml),
+    element("ATTRIBUTE-VALUE-XHTML/THE-ORIGINAL-VALUE", Content::XHtml),
+    element(
+        "VALUES",
+        elements(&[
+            many("ATTRIBUTE-VALUE-BOOLEAN"),
+            many("ATTRIBUTE-VALUE-DATE"),
+            many("ATTRIBUTE-VALUE-ENUMERATION"),
+            many("ATTRIBUTE-VALUE-INTEGER"),
+            many("ATTRIBUTE-VALUE-REAL"),
+            many("ATTRIBUTE-VALUE-STRING"),
+            many("ATTRIBUTE-VALUE-XHTML"),
+        ]),
+    ),
+    element("SPEC-OBJECTS", elements(&[many("SPEC-OBJECT")])),
+    identifiable(
+        "SPEC-OBJECT",
+        &[],
+        &[maybe("ALTERNATIVE-ID"), one("TYPE"), maybe("VALUES")],
+    ),
+    element("SPEC-OBJECT/TYPE", elements(&[one("SPEC-OBJECT-TYPE-REF")])),
+    element("SPEC-RELATIONS", elements(&[many("SPEC-RELATION")])),
+    identifiable(
+        "SPEC-RELATION",
+        &[],
+        &[
+            maybe("ALTERNATIVE-ID"),
+            maybe("VALUES"),
+            one("SOURCE"),
+            one("TARGET"),
+            one("TYPE"),
+        ],
+    ),
+    element("SOURCE", elements(&[one("SPEC-OBJECT-REF")])),
+    element("TARGET", elements(&[one("SPEC-OBJECT-REF")])),
+    element(
+        "SPEC-RELATION/TYPE",
+        elements(&[one("SPEC-RELATION-TYPE-REF")]),
+    ),
+    element("SPECIFICATIONS", elements(&[many("SPECIFICATION")])),
+    identifiable(
+        "SPECIFICATION",
+        &[],
+        &[
+            maybe("ALTERNATIVE-ID"),
+            maybe("VALUES"),
+            maybe("CHILDREN"),
+            one("TYPE"),
+        ],
+    ),
+    element(
+        "SPECIFICATION/TYPE",
+        elements(&[one("SPECIFICATION-TYPE-REF")]),
+    ),
+    element("CHILDREN", elements(&[many("SPEC-HIERARCHY")])),
+    identifiable(
+        "SPEC-HIERARCHY",
+        &[
+            optional("IS-EDITABLE", Value::Boolean),
+            optional("IS-TABLE-INTERNAL", Value::Boolean),
+        ],
+        &[
+            maybe("ALTERNATIVE-ID"),
+            maybe("CHILDREN"),
+            maybe("EDITABLE-ATTS"),
+            one("OBJECT"),
+        ],
+    ),
+    element("OBJECT", elements(&[one("SPEC-OBJECT-REF")])),
+    element(
+        "EDITABLE-ATTS",
+        elements(&[
+            many("ATTRIBUTE-DEFINITION-BOOLEAN-REF"),
+            many("ATTRIBUTE-DEFINITION-DATE-REF"),
+            many("ATTRIBUTE-DEFINITION-ENUMERATION-REF"),
+            many("ATTRIBUTE-DEFINITION-INTEGER-REF"),
+            many("ATTRIBUTE-DEFINITION-REAL-REF"),
+            many("ATTRIBUTE-DEFINITION-STRING-REF"),
+            many("ATTRIBUTE-DEFINITION-XHTML-REF"),
+        ]),
+    ),
+    element("SPEC-RELATION-GROUPS", elements(&[many("RELATION-GROUP")])),
+    identifiable(
+        "RELATION-GROUP",
+        &[],
+        &[
+            maybe("ALTERNATIVE-ID"),
+            one("SOURCE-SPECIFICATION"),
+            maybe("SPEC-RELATIONS"),
+            one("TARGET-SPECIFICATION"),
+            one("TYPE"),
+        ],
+    ),
+    element(
+        "SOURCE-SPECIFICATION",
+        elements(&[one("SPECIFICATION-REF")]),
+    ),
+    element(
+        "TARGET-SPECIFICATION",
+        elements(&[one("SPECIFICATION-REF")]),
+    ),
+    element(
+        "RELATION-GROUP/SPEC-RELATIONS",
+        elements(&[many("SPEC-RELATION-REF")]),
+    ),
+    element(
+        "RELATION-GROUP/TYPE",
+        elements(&[one("RELATION-GROUP-TYPE-REF")]),
+    ),
+];
+
+/// Where an XHTML element may appear.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum XHtmlKind {
+    Block,
+    Inline,
+    /// Allowed both as a block and inline, like `ins`.
+    Both,
+    /// Only allowed in the elements listing it, like `li` or `td`.
+    Part,
+}
+
+/// Content model of an XHTML element.
+enum XHtmlContent {
+    Empty,
+    /// Text and inline elements.
+    Inline,
+    /// Text, block and inline elements.
+    Flow,
+    /// Block elements only.
+    Block,
+    /// `param` elements and flow content.
+    Object,
+    /// Only the listed elements.
+    Parts(&'static [&'static str]),
+}
+
+impl XHtmlContent {
+    fn allows_text(&self) -> bool {
+        matches!(
+            self,
+            XHtmlContent::Inline | XHtmlContent::Flow | XHtmlContent::Object
+        )
+    }
+
+    fn allows(&self, child: &XHtmlRule) -> bool {
+        match self {
+            XHtmlContent::Empty => false,
+            XHtmlContent::Inline => matches!(child.kind, XHtmlKind::Inline | XHtmlKind::Both),
+            XHtmlContent::Flow => child.kind != XHtmlKind::Part,
+            XHtmlContent::Block => matches!(child.kind, XHtmlKind::Block | XHtmlKind::Both),
+            XHtmlContent::Object => child.kind != XHtmlKind::Part || child.name == "param",
+            XHtmlContent::Parts(names) => names.contains(&child.name),
+        }
+    }
+}
+
+struct XHtmlRule {
+    name: &'static str,
+    kind: XHtmlKind,
+    content: XHtmlContent,
+}
+
+const fn xhtml(name: &'static str, kind: XHtmlKind, content: XHtmlContent) -> XHtmlRule {
+    XHtmlRule {
+        name,
+        kind,
+        content,
+    }
+}
+
+/// Elements of the text, hypertext, list, object, presentation, edit,
+/// bidirectional text and table modules of XHTML 1.1.
+const XHTML_RULES: &[XHtmlRule] = {
+    use XHtmlContent::{Block, Empty, Flow, Inline, Object, Parts};
+    use XHtmlKind::{Both, Part};
+    const BLOCK: XHtmlKind = XHtmlKind::Block;
+    const INLINE: XHtmlKind = XHtmlKind::Inline;
+    &[
+        xhtml("a", INLINE, Inline),
+        xhtml("abbr", INLINE, Inline),
+        xhtml("acronym", INLINE, Inline),
+        xhtml("address", BLOCK, Inline),
+        xhtml("b", INLINE, Inline),
+        xhtml("bdo", INLINE, Inline),
+        xhtml("big", INLINE, Inline),
+        xhtml("blockquote", BLOCK, Block),
+        xhtml("br", INLINE, Empty),
+        xhtml("caption", Part, Inline),
+        xhtml("cite", INLINE, Inline),
+        xhtml("code", INLINE, Inline),
+        xhtml("col", Part, Empty),
+        xhtml("colgroup", Part, Parts(&["col"])),
+        xhtml("dd", Part, Flow),
+        xhtml("del", Both, Flow),
+        xhtml("dfn", INLINE, Inline),
+        xhtml("div", BLOCK, Flow),
+        xhtml("dl", BLOCK, Parts(&["dt", "dd"])),
+        xhtml("dt", Part, Inline),
+        xhtml("em", INLINE, Inline),
+        xhtml("h1", BLOCK, Inline),
+        xhtml("h2", BLOCK, Inline),
+        xhtml("h3", BLOCK, Inline),
+        xhtml("h4", BLOCK, Inline),
+        xhtml("h5", BLOCK, Inline),
+        xhtml("h6", BLOCK, Inline),
+        xhtml("hr", BLOCK, Empty),
+        xhtml("i", INLINE, Inline),
+        xhtml("ins", Both, Flow),
+        xhtml("kbd", INLINE, Inline),
+        xhtml("li", Part, Flow),
+        xhtml("object", Both, Object),
+        xhtml("ol", BLOCK, Parts(&["li"])),
+        xhtml("p", BLOCK, Inline),
+        xhtml("param", Part, Empty),
+        xhtml("pre", BLOCK, Inline),
+        xhtml("q", INLINE, Inline),
+        xhtml("samp", INLINE, Inline),
+        xhtml("small", INLINE, Inline),
+        xhtml("span", INLINE, Inline),
+        xhtml("strong", INLINE, Inline),
+        xhtml("sub", INLINE, Inline),
+        xhtml("sup", INLINE, Inline),
+        xhtml(
+            "table",
+            BLOCK,
+            Parts(&[
+                "caption", "col", "colgroup", "thead", "tfoot", "tbody", "tr",
+            ]),
+        ),
+        xhtml("tbody", Part, Parts(&["tr"])),
+        xhtml("td", Part, Flow),
+        xhtml("tfoot", Part, Parts(&["tr"])),
+        xhtml("th", Part, Flow),
+        xhtml("thead", Part, Parts(&["tr"])),
+        xhtml("tr", Part, Parts(&["th", "td"])),
+        xhtml("tt", INLINE, Inline),
+        xhtml("ul", BLOCK, Parts(&["li"])),
+        xhtml("var", INLINE, Inline),
+    ]
+};
+
+/// References are the text of the elements whose name ends with "-REF".
+const REFERENCE: Element = element("*-REF", Content::Text(Value::IdRef));
+
+fn find_rule(parent: &str, name: &str) -> Option<&'static Element> {
+    let scoped = format!("{}/{}", parent, name);
+    let all = || RULES.iter().chain(DATATYPE_RULES.iter().flatten());
+    all()
+        .find(|e| e.key == scoped)
+        .or_else(|| all().find(|e| e.key == name))
+        .or_else(|| name.ends_with("-REF").then_some(&REFERENCE))
+}
+
+/// State of an open element.
+struct Frame {
+    name: String,
+    rule: Option<&'static Element>,
+    position: TextPosition,
+    /// Number of occurrences of each child element.
+    counts: HashMap<String, usize>,
+    /// Index in an ordered content model of the last child element.
+    last_index: usize,
+    text: String,
+    text_position: Option<TextPosition>,
+}
+
+struct Validator {
+    errors: Vec<SchemaError>,
+    stack: Vec<Frame>,
+    /// Open XHTML elements, `None` for the unknown ones.
+    xhtml: Vec<Option<&'static XHtmlRule>>,
+    /// Depth of content being skipped, if any.
+    skip_depth: usize,
+    identifiers: HashSet<String>,
+    references: Vec<(String, TextPosition)>,
+}
+
+impl Validator {
+    fn error(&mut self, position: TextPosition, message: String) {
+        self.errors.push(SchemaError {
+            line: position.row + 1,
+            column: position.column + 1,
+            message,
+        });
+    }
+
+    fn start(
+        &mut self,
+        name: &xml::name::OwnedName,
+        attributes: &[xml::attribute::OwnedAttribute],
+        position: TextPosition,
+    ) {
+        if self.skip_depth > 0 {
+            self.skip_depth += 1;
+            return;
+        }
+        if let Some(parent) = self.xhtml.last().copied() {
+            let rule = self.xhtml_rule(name, position);
+            if let (Some(parent), Some(rule)) = (parent, rule) {
+                if !parent.content.allows(rule) {
+                    self.error(
+                        position,
+                        format!(
+                            "Element xhtml:{} is not allowed in xhtml:{}",
+                            rule.name, parent.name
+                        ),
+                    );
+                }
+            }
+            self.xhtml.push(rule);
+            return;
+        }
+
+        let parent = self.stack.last().map(|f| f.name.clone());
+        let parent_rule = self.stack.last().and_then(|f| f.rule);
+        let local_name = name.local_name.as_str();
+
+        if let Some(Content::XHtml) = parent_rule.map(|r| &r.content) {
+            let frame = self.stack.last_mut().unwrap();
+            *frame.counts.entry(local_name.to_string()).or_default() += 1;
+            let parent = frame.name.clone();
+            if frame.counts.values().sum::<usize>() > 1 {
+                self.error(position, format!("{} must hold one XHTML element", parent));
+            }
+            let rule = self.xhtml_rule(name, position);
+            if rule.is_some_and(|rule| !matches!(rule.name, "div" | "p")) {
+                self.error(
+                    position,
+                    format!("{} must hold an xhtml:div or xhtml:p element", parent),
+                );
+            }
+            self.xhtml.push(rule);
+            return;
+        }
+        if let Some(Content::Any) = parent_rule.map(|r| &r.content) {
+            self.skip_depth = 1;
+            return;
+        }
+
+        if name.namespace.as_deref() != Some(REQIF_NAMESPACE) {
+            self.error(
+                position,
+                format!("Element {} is not in the ReqIF namespace", local_name),
+            );
+        }
+
+        let rule = match &parent {
+            None if local_name == "REQ-IF" => find_rule("", local_name),
+            None => {
+                self.error(
+                    position,
+                    format!("Root element must be REQ-IF, found {}", local_name),
+                );
+                None
+            }
+            Some(parent) => self.check_child(parent, parent_rule, local_name, position),
+        };
+        if let Some(rule) = rule {
+            self.check_attributes(rule, local_name, attributes, position);
+        } else if parent.is_some() {
+            self.skip_depth = 1;
+            return;
+        }
+
+        self.stack.push(Frame {
+            name: local_name.to_string(),
+            rule,
+            position,
+            counts: HashMap::new(),
+            last_index: 0,
+            text: String::new(),
+            text_position: None,
+        });
+    }
+
+    /// Returns the rule of the XHTML element `name`, reporting elements of
+    /// other namespaces and the ones ReqIF doesn't allow.
+    fn xhtml_rule(
+        &mut self,
+        name: &xml::name::OwnedName,
+        position: TextPosition,
+    ) -> Option<&'static XHtmlRule> {
+        if name.namespace.as_deref() != Some(XHTML_NAMESPACE) {
+            self.error(
+                position,
+                format!("Element {} is not in the XHTML namespace", name.local_name),
+            );
+            return None;
+        }
+        let rule = XHTML_RULES.iter().find(|r| r.name == name.local_name);
+        if rule.is_none() {
+            self.error(
+                position,
+                format!("Unknown XHTML element {}", name.local_name),
+            );
+        }
+        rule
+    }
+
+    /// Checks that `name` may appear in `parent`, and returns its rule.
+    fn check_child(
+        &mut self,
+        parent: &str,
+        parent_rule: Option<&'static Element>,
+        name: &str,
+        position: TextPosition,
+    ) -> Option<&'static Element> {
+        let (ordered, children) = match parent_rule.map(|r| &r.content) {
+            Some(Content::Elements { ordered, children }) => (*ordered, *children),
+            Some(Content::Text(_)) => {
+                self.error(
+                    position,
+                    format!("Element {} is not allowed in {}", name, parent),
+                );
+                return None;
+            }
+            _ => return None,
+        };
+        let index = match children.iter().position(|c| c.name == name) {
+            Some(index) => index,
+            None => {
+                self.error(
+                    position,
+                    format!("Element {} is not allowed in {}", name, parent),
+                );
+                return None;
+            }
+        };
+        let frame = self.stack.last_mut().unwrap();
+        let count = frame.counts.entry(name.to_string()).or_default();
+        *count += 1;
+        let count = *count;
+        let out_of_order = ordered && index < frame.last_index;
+        frame.last_index = index;
+        if children[index].max.is_some_and(|max| count > max) {
+            self.error(
+                position,
+                format!("Too many {} elements in {}", name, parent),
+            );
+        }
+        if out_of_order {
+            self.error(
+                position,
+                format!("Element {} is out of order in {}", name, parent),
+            );
+        }
+        find_rule(parent, name)
+    }
+
+    fn check_attributes(
+        &mut self,
+        rule: &'static Element,
+        element: &str,
+        attributes: &[xml::attribute::OwnedAttribute],
+        position: TextPosition,
+    ) {
+        let identifiable: &[Attribute] = if rule.identifiable {
+            &IDENTIFIABLE_ATTRIBUTES
+        } else {
+            &[]
+        };
+        let allowed = || identifiable.iter().chain(rule.attributes.iter());
+        for attribute in attributes {
+            // Attributes of other namespaces, like xsi:schemaLocation, are allowed.
+            if attribute.name.namespace.is_some() {
+                continue;
+            }
+            let key = attribute.name.local_name.as_str();
+            match allowed().find(|a| a.name == key) {
+                None => self.error(
+                    position,
+                    format!("Attribute {} is not allowed in {}", key, element),
+                ),
+                Some(a) if !a.value.accepts(&attribute.value) => self.error(
+                    position,
+                    format!(
+                        "Attribute {} of {} is not a valid {}: {:?}",
+                        key,
+                        element,
+                        a.value.name(),
+                        attribute.value
+                    ),
+                ),
+                Some(a) => {
+                    if a.value == Value::Id && !self.identifiers.insert(attribute.value.clone()) {
+                        self.error(
+                            position,
+                            format!("Duplicated identifier {}", attribute.value),
+                        );
+                    }
+                }
+            }
+        }
+        for a in allowed().filter(|a| a.required) {
+            if !attributes
+                .iter()
+                .any(|attribute| attribute.name.local_name == a.name)
+            {
+                self.error(
+                    position,
+                    format!("Missing attribute {} in {}", a.name, element),
+                );
+            }
+        }
+    }
+
+    fn characters(&mut self, text: &str, position: TextPosition) {
+        if self.skip_depth > 0 {
+            return;
+        }
+        if let Some(rule) = self.xhtml.last().copied() {
+            if let Some(rule) = rule.filter(|r| !r.content.allows_text()) {
+                if !text.trim().is_empty() {
+                    self.error(
+                        position,
+                        format!("Text is not allowed in xhtml:{}", rule.name),
+                    );
+                }
+            }
+            return;
+        }
+        if let Some(frame) = self.stack.last_mut() {
+            frame.text.push_str(text);
+            frame.text_position.get_or_insert(position);
+        }
+    }
+
+    fn end(&mut self, position: TextPosition) {
+        if self.skip_depth > 0 {
+            self.skip_depth -= 1;
+            return;
+        }
+        if self.xhtml.pop().is_some() {
+            return;
+        }
+        let frame = match self.stack.pop() {
+            Some(frame) => frame,
+            None => return,
+        };
+        let text_position = frame.text_position.unwrap_or(position);
+        match frame.rule.map(|r| &r.content) {
+            Some(Content::Text(value)) => {
+                if !value.accepts(&frame.text) {
+                    self.error(
+                        text_position,
+                        format!(
+                            "Content of {} is not a valid {}: {:?}",
+                            frame.name,
+                            value.name(),
+                            frame.text
+                        ),
+                    );
+                } else if *value == Value::IdRef {
+                    self.references
+                        .push((frame.text.trim().to_string(), text_position));
+                }
+            }
+            Some(Content::XHtml) => {
+                if !frame.text.trim().is_empty() {
+                    self.error(
+                        text_position,
+                        format!("Text is not allowed in {}", frame.name),
+                    );
+                }
+                if frame.counts.is_empty() {
+                    self.error(
+                        frame.position,
+                        format!("Missing XHTML element in {}", frame.name),
+                    );
+                }
+            }
+            Some(Content::Elements { children, .. }) => {
+                if !frame.text.trim().is_empty() {
+                    self.error(
+                        text_position,
+                        format!("Text is not allowed in {}", frame.name),
+                    );
+                }
+                for child in children.iter() {
+                    let count = frame.counts.get(child.name).copied().unwrap_or(0);
+                    if count < child.min {
+                        self.error(
+                            frame.position,
+                            format!("Missing element {} in {}", child.name, frame.name),
+                        );
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn finish(&mut self) {
+        let references = std::mem::take(&mut self.references);
+        for (reference, position) in references {
+            if !self.identifiers.contains(&reference) {
+                self.error(position, format!("Unknown reference {}", reference));
+            }
+        }
+    }
+}
+
+/// Checks `xml` against the ReqIF 1.0.1 schema, returning every violation
+/// found in document order. A document that is not well-formed XML reports
+/// the parse error only.
+pub fn validate(xml: &str) -> Vec<SchemaError> {
+    let mut validator = Validator {
+        errors: vec![],
+        stack: vec![],
+        xhtml: vec![],
+        skip_depth: 0,
+        identifiers: HashSet::new(),
+        references: vec![],
+    };
+    let mut reader = EventReader::new(xml.as_bytes());
+    loop {
+        let event = reader.next();
+        let position = reader.position();
+        match event {
+            Ok(XmlEvent::StartElement {
+                name, attributes, ..
+            }) => validator.start(&name, &attributes, position),
+            Ok(XmlEvent::Characters(text)) | Ok(XmlEvent::CData(text)) => {
+                validator.characters(&text, position)
+            }
+            Ok(XmlEvent::EndElement { .. }) => validator.end(position),
+            Ok(XmlEvent::EndDocument) => break,
+            Ok(_) => {}
+            Err(e) => {
+                let position = e.position();
+                return vec![SchemaError {
+                    line: position.row + 1,
+                    column: position.column + 1,
+                    message: e.msg().to_string(),
+                }];
+            }
+        }
+    }
+    validator.finish();
+    validator.errors
+}
+
+#[cfg(test)]
+mod test {
+    use super::{validate, SchemaError};
+    use crate::clock;
+    use crate::fixture::{self, document};
+    use crate::req_if::{
+        DataTypeDefinitionInteger, Object, SpecHierarchy, SpecRelation, SpecRelationType,
+    };
+
+    #[test]
+    fn test_written_document_is_valid() {
+        let mut reqif = document("Schema");
+        let now = clock::now();
+        reqif
+            .add_datatype(DataTypeDefinitionInteger::new(
+                "DT-INTEGER".to_string(),
+                now,
+                "Integer".to_string(),
+                0,
+                100,
+            ))
+            .expect("error");
+        reqif
+            .add_spec_relation_type(SpecRelationType::new(
+                "SRT-DERIVES".to_string(),
+                now,
+                "derives from".to_string(),
+            ))
+            .expect("error");
+        for id in ["REQ-1", "REQ-2"] {
+            fixture::add_requirement(&mut reqif, id, "The <b>system</b> shall work.");
+        }
+        reqif
+            .add_spec_relation(SpecRelation::new(
+                "REL-1".to_string(),
+                now,
+                String::new(),
+                "SRT-DERIVES".to_string(),
+                "REQ-2".to_string(),
+                "REQ-1".to_string(),
+            ))
+            .expect("error");
+        let mut specification = reqif
+            .build_module_specification("SPEC-1".to_string(), now, "Module".to_string())
+            .expect("error");
+        specification
+            .children
+            .add_spec_hierarchy(
+                SpecHierarchy::new("H-1".to_string(), now, Object::new("REQ-1".to_string())),
+                0,
+            )
+            .expect("error");
+        reqif.add_specification(specification).expect("error");
+
+        let xml = reqif.to_xml_string().expect("error");
+        assert_eq!(validate(&xml), vec![]);
+    }
+
+    #[test]
+    fn test_errors_have_positions() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<REQ-IF xmlns="http://www.omg.org/spec/ReqIF/20110401/reqif.xsd">
+  <THE-HEADER>
+    <REQ-IF-HEADER>
+      <CREATION-TIME>yesterday</CREATION-TIME>
+      <REQ-IF-TOOL-ID>tool</REQ-IF-TOOL-ID>
+      <REQ-IF-VERSION>1.0</REQ-IF-VERSION>
+      <SOURCE-TOOL-ID>tool</SOURCE-TOOL-ID>
+      <TITLE>Broken</TITLE>
+    </REQ-IF-HEADER>
+  </THE-HEADER>
+  <CORE-CONTENT>
+    <REQ-IF-CONTENT>
+      <SPEC-OBJECTS>
+        <SPEC-OBJECT IDENTIFIER="obj-1" LAST-CHANGE="2024-04-01T10:00:00+02:00">
+          <TYPE><SPEC-OBJECT-TYPE-REF>missing</SPEC-OBJECT-TYPE-REF></TYPE>
+          <TEXT>unexpected</TEXT>
+        </SPEC-OBJECT>
+      </SPEC-OBJECTS>
+    </REQ-IF-CONTENT>
+  </CORE-CONTENT>
+</REQ-IF>"#;
+
+        let errors: Vec<String> = validate(xml).iter().map(|e| e.to_string()).collect();
+        assert_eq!(
+            errors,
+            vec![
+                "4:5: Missing attribute IDENTIFIER in REQ-IF-HEADER",
+                "5:22: Content of CREATION-TIME is not a valid xsd:dateTime: \"yesterday\"",
+                "17:11: Element TEXT is not allowed in SPEC-OBJECT",
+                "16:39: Unknown reference missing",
+            ]
+        );
+
+        assert_eq!(
+            validate("<REQ-IF><THE-HEADER></REQ-IF>"),
+            vec![SchemaError {
+                line: 1,
+                column: 29,
+                message: "Unexpected closing tag: REQ-IF != THE-HEADER".to_string(),
+            }]
+        );
+    }
+
+    /// Messages for a document holding `content`, without the unresolved
+    /// references.
+    fn messages(content: &str) -> Vec<String> {
+        let xml = format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<REQ-IF xmlns="http://www.omg.org/spec/ReqIF/20110401/reqif.xsd" xmlns:xhtml="http://www.w3.org/1999/xhtml">
+  <THE-HEADER>
+    <REQ-IF-HEADER IDENTIFIER="header">
+      <CREATION-TIME>2024-04-01T10:00:00+02:00</CREATION-TIME>
+      <REQ-IF-TOOL-ID>tool</REQ-IF-TOOL-ID>
+      <REQ-IF-VERSION>1.0</REQ-IF-VERSION>
+      <SOURCE-TOOL-ID>tool</SOURCE-TOOL-ID>
+      <TITLE>Content</TITLE>
+    </REQ-IF-HEADER>
+  </THE-HEADER>
+  <CORE-CONTENT>
+    <REQ-IF-CONTENT>{}</REQ-IF-CONTENT>
+  </CORE-CONTENT>
+</REQ-IF>"#,
+            content
+        );
+        validate(&xml)
+            .into_iter()
+            .map(|e| e.message)
+            .filter(|m| !m.starts_with("Unknown reference"))
+            .collect()
+    }
+
+    /// A document shaped like a ReqIF Studio (ProR) export: EMF identifiers,
+    /// VALUES before TYPE and a tool extension of another namespace. It is
+    /// written after such exports, reduced to a few elements, not copied
+    /// from one.
+    const REQIF_STUDIO: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<REQ-IF xmlns="http://www.omg.org/spec/ReqIF/20110401/reqif.xsd" xmlns:configuration="http://eclipse.org/rmf/pror/toolextensions/1.0" xmlns:id="http://pror.org/presentation/id" xmlns:xhtml="http://www.w3.org/1999/xhtml">
+  <THE-HEADER>
+    <REQ-IF-HEADER IDENTIFIER="_o7scMadbEeafNduaIhMbQg">
+      <COMMENT>Created by: analyst</COMMENT>
+      <CREATION-TIME>2017-03-13T10:15:37.938+01:00</CREATION-TIME>
+      <REQ-IF-TOOL-ID>ProR (http://pror.org)</REQ-IF-TOOL-ID>
+      <REQ-IF-VERSION>1.0</REQ-IF-VERSION>
+      <SOURCE-TOOL-ID>ProR (http://pror.org)</SOURCE-TOOL-ID>
+      <TITLE>Requirements Document</TITLE>
+    </REQ-IF-HEADER>
+  </THE-HEADER>
+  <CORE-CONTENT>
+    <REQ-IF-CONTENT>
+      <DATATYPES>
+        <DATATYPE-DEFINITION-STRING IDENTIFIER="_o7scMqdbEeafNduaIhMbQg" LAST-CHANGE="2017-03-13T10:15:37.938+01:00" LONG-NAME="T_String32k" MAX-LENGTH="32000"/>
+        <DATATYPE-DEFINITION-XHTML IDENTIFIER="_o7scM6dbEeafNduaIhMbQg" LAST-CHANGE="2017-03-13T10:15:37.938+01:00" LONG-NAME="T_XHTML"/>
+        <DATATYPE-DEFINITION-ENUMERATION IDENTIFIER="_o7scNKdbEeafNduaIhMbQg" LAST-CHANGE="2017-03-13T10:16:02.125+01:00" LONG-NAME="T_Status">
+          <SPECIFIED-VALUES>
+            <ENUM-VALUE IDENTIFIER="_o7scNadbEeafNduaIhMbQg" LAST-CHANGE="2017-03-13T10:16:02.125+01:00" LONG-NAME="Draft">
+              <PROPERTIES>
+                <EMBEDDED-VALUE KEY="0" OTHER-CONTENT=""/>
+              </PROPERTIES>
+            </ENUM-VALUE>
+            <ENUM-VALUE IDENTIFIER="_o7scNqdbEeafNduaIhMbQg" LAST-CHANGE="2017-03-13T10:16:02.125+01:00" LONG-NAME="Approved">
+              <PROPERTIES>
+                <EMBEDDED-VALUE KEY="1" OTHER-CONTENT=""/>
+              </PROPERTIES>
+            </ENUM-VALUE>
+          </SPECIFIED-VALUES>
+        </DATATYPE-DEFINITION-ENUMERATION>
+      </DATATYPES>
+      <SPEC-TYPES>
+        <SPEC-OBJECT-TYPE IDENTIFIER="_o7scN6dbEeafNduaIhMbQg" LAST-CHANGE="2017-03-13T10:15:37.938+01:00" LONG-NAME="Requirement Type">
+          <SPEC-ATTRIBUTES>
+            <ATTRIBUTE-DEFINITION-STRING IDENTIFIER="_o7scOKdbEeafNduaIhMbQg" LAST-CHANGE="2017-03-13T10:15:37.938+01:00" LONG-NAME="ID">
+              <TYPE>
+                <DATATYPE-DEFINITION-STRING-REF>_o7scMqdbEeafNduaIhMbQg</DATATYPE-DEFINITION-STRING-REF>
+              </TYPE>
+            </ATTRIBUTE-DEFINITION-STRING>
+            <ATTRIBUTE-DEFINITION-XHTML IDENTIFIER="_o7scOadbEeafNduaIhMbQg" LAST-CHANGE="2017-03-13T10:15:37.938+01:00" LONG-NAME="Description">
+              <TYPE>
+                <DATATYPE-DEFINITION-XHTML-REF>_o7scM6dbEeafNduaIhMbQg</DATATYPE-DEFINITION-XHTML-REF>
+              </TYPE>
+            </ATTRIBUTE-DEFINITION-XHTML>
+            <ATTRIBUTE-DEFINITION-ENUMERATION IDENTIFIER="_o7scOqdbEeafNduaIhMbQg" LAST-CHANGE="2017-03-13T10:16:02.125+01:00" LONG-NAME="Status" MULTI-VALUED="false">
+              <TYPE>
+                <DATATYPE-DEFINITION-ENUMERATION-REF>_o7scNKdbEeafNduaIhMbQg</DATATYPE-DEFINITION-ENUMERATION-REF>
+              </TYPE>
+            </ATTRIBUTE-DEFINITION-ENUMERATION>
+          </SPEC-ATTRIBUTES>
+        </SPEC-OBJECT-TYPE>
+        <SPECIFICATION-TYPE IDENTIFIER="_o7scO6dbEeafNduaIhMbQg" LAST-CHANGE="2017-03-13T10:15:37.938+01:00" LONG-NAME="Specification Type">
+          <SPEC-ATTRIBUTES>
+            <ATTRIBUTE-DEFINITION-STRING IDENTIFIER="_o7scPKdbEeafNduaIhMbQg" LAST-CHANGE="2017-03-13T10:15:37.938+01:00" LONG-NAME="Description">
+              <TYPE>
+                <DATATYPE-DEFINITION-STRING-REF>_o7scMqdbEeafNduaIhMbQg</DATATYPE-DEFINITION-STRING-REF>
+              </TYPE>
+            </ATTRIBUTE-DEFINITION-STRING>
+          </SPEC-ATTRIBUTES>
+        </SPECIFICATION-TYPE>
+      </SPEC-TYPES>
+      <SPEC-OBJECTS>
+        <SPEC-OBJECT IDENTIFIER="_o7scPadbEeafNduaIhMbQg" LAST-CHANGE="2017-03-13T10:17:44.313+01:00">
+          <VALUES>
+            <ATTRIBUTE-VALUE-STRING THE-VALUE="REQ-1">
+              <DEFINITION>
+                <ATTRIBUTE-DEFINITION-STRING-REF>_o7scOKdbEeafNduaIhMbQg</ATTRIBUTE-DEFINITION-STRING-REF>
+              </DEFINITION>
+            </ATTRIBUTE-VALUE-STRING>
+            <ATTRIBUTE-VALUE-XHTML>
+              <DEFINITION>
+                <ATTRIBUTE-DEFINITION-XHTML-REF>_o7scOadbEeafNduaIhMbQg</ATTRIBUTE-DEFINITION-XHTML-REF>
+              </DEFINITION>
+              <THE-VALUE><xhtml:div>The system <xhtml:b>shall</xhtml:b> start in less than <xhtml:em>5 s</xhtml:em>.</xhtml:div></THE-VALUE>
+            </ATTRIBUTE-VALUE-XHTML>
+            <ATTRIBUTE-VALUE-ENUMERATION>
+              <DEFINITION>
+                <ATTRIBUTE-DEFINITION-ENUMERATION-REF>_o7scOqdbEeafNduaIhMbQg</ATTRIBUTE-DEFINITION-ENUMERATION-REF>
+              </DEFINITION>
+              <VALUES>
+                <ENUM-VALUE-REF>_o7scNqdbEeafNduaIhMbQg</ENUM-VALUE-REF>
+              </VALUES>
+            </ATTRIBUTE-VALUE-ENUMERATION>
+          </VALUES>
+          <TYPE>
+            <SPEC-OBJECT-TYPE-REF>_o7scN6dbEeafNduaIhMbQg</SPEC-OBJECT-TYPE-REF>
+          </TYPE>
+        </SPEC-OBJECT>
+        <SPEC-OBJECT IDENTIFIER="_o7scPqdbEeafNduaIhMbQg" LAST-CHANGE="2017-03-13T10:18:05.001+01:00">
+          <VALUES>
+            <ATTRIBUTE-VALUE-STRING THE-VALUE="REQ-2">
+              <DEFINITION>
+                <ATTRIBUTE-DEFINITION-STRING-REF>_o7scOKdbEeafNduaIhMbQg</ATTRIBUTE-DEFINITION-STRING-REF>
+              </DEFINITION>
+            </ATTRIBUTE-VALUE-STRING>
+            <ATTRIBUTE-VALUE-XHTML>
+              <DEFINITION>
+                <ATTRIBUTE-DEFINITION-XHTML-REF>_o7scOadbEeafNduaIhMbQg</ATTRIBUTE-DEFINITION-XHTML-REF>
+              </DEFINITION>
+              <THE-VALUE><xhtml:div><xhtml:p>The log shall hold:</xhtml:p><xhtml:ul><xhtml:li>the time</xhtml:li><xhtml:li>the user</xhtml:li></xhtml:ul></xhtml:div></THE-VALUE>
+            </ATTRIBUTE-VALUE-XHTML>
+          </VALUES>
+          <TYPE>
+            <SPEC-OBJECT-TYPE-REF>_o7scN6dbEeafNduaIhMbQg</SPEC-OBJECT-TYPE-REF>
+          </TYPE>
+        </SPEC-OBJECT>
+      </SPEC-OBJECTS>
+      <SPECIFICATIONS>
+        <SPECIFICATION IDENTIFIER="_o7scP6dbEeafNduaIhMbQg" LAST-CHANGE="2017-03-13T10:15:37.938+01:00" LONG-NAME="Specification Document">
+          <VALUES>
+            <ATTRIBUTE-VALUE-STRING THE-VALUE="Requirements Document">
+              <DEFINITION>
+                <ATTRIBUTE-DEFINITION-STRING-REF>_o7scPKdbEeafNduaIhMbQg</ATTRIBUTE-DEFINITION-STRING-REF>
+              </DEFINITION>
+            </ATTRIBUTE-VALUE-STRING>
+          </VALUES>
+          <TYPE>
+            <SPECIFICATION-TYPE-REF>_o7scO6dbEeafNduaIhMbQg</SPECIFICATION-TYPE-REF>
+          </TYPE>
+          <CHILDREN>
+            <SPEC-HIERARCHY IDENTIFIER="_o7scQKdbEeafNduaIhMbQg" LAST-CHANGE="2017-03-13T10:17:44.313+01:00">
+              <OBJECT>
+                <SPEC-OBJECT-REF>_o7scPadbEeafNduaIhMbQg</SPEC-OBJECT-REF>
+              </OBJECT>
+              <CHILDREN>
+                <SPEC-HIERARCHY IDENTIFIER="_o7scQadbEeafNduaIhMbQg" LAST-CHANGE="2017-03-13T10:18:05.001+01:00">
+                  <OBJECT>
+                    <SPEC-OBJECT-REF>_o7scPqdbEeafNduaIhMbQg</SPEC-OBJECT-REF>
+                  </OBJECT>
+                </SPEC-HIERARCHY>
+              </CHILDREN>
+            </SPEC-HIERARCHY>
+          </CHILDREN>
+        </SPECIFICATION>
+      </SPECIFICATIONS>
+    </REQ-IF-CONTENT>
+  </CORE-CONTENT>
+  <TOOL-EXTENSIONS>
+    <REQ-IF-TOOL-EXTENSION>
+      <configuration:ProrToolExtension>
+        <configuration:specViewConfigurations>
+          <configuration:ProrSpecViewConfiguration specification="_o7scP6dbEeafNduaIhMbQg">
+            <configuration:columns>
+              <configuration:Column label="ID" width="100"/>
+              <configuration:Column label="Description" width="400"/>
+            </configuration:columns>
+          </configuration:ProrSpecViewConfiguration>
+        </configuration:specViewConfigurations>
+      </configuration:ProrToolExtension>
+    </REQ-IF-TOOL-EXTENSION>
+  </TOOL-EXTENSIONS>
+</REQ-IF>"#;
+
+    /// A document shaped like a Capella Requirements viewpoint export:
+    /// `xml:lang` and `xsi:schemaLocation` on the root, UUID identifiers,
+    /// default values, a relation and an image given as nested objects. It
+    /// is written after such exports, reduced to a few elements, not copied
+    /// from one.
+    const CAPELLA: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<REQ-IF xmlns="http://www.omg.org/spec/ReqIF/20110401/reqif.xsd" xmlns:xhtml="http://www.w3.org/1999/xhtml" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xsi:schemaLocation="http://www.omg.org/spec/ReqIF/20110401/reqif.xsd http://www.omg.org/spec/ReqIF/20110401/reqif.xsd" xml:lang="en">
+  <THE-HEADER>
+    <REQ-IF-HEADER IDENTIFIER="_3f1c7a52-0b8e-4d6e-9a1d-2c5b8e4f7a10">
+      <CREATION-TIME>2023-11-07T16:42:09.512Z</CREATION-TIME>
+      <REPOSITORY-ID>capella</REPOSITORY-ID>
+      <REQ-IF-TOOL-ID>Capella Requirements</REQ-IF-TOOL-ID>
+      <REQ-IF-VERSION>1.0</REQ-IF-VERSION>
+      <SOURCE-TOOL-ID>Capella</SOURCE-TOOL-ID>
+      <TITLE>System Analysis</TITLE>
+    </REQ-IF-HEADER>
+  </THE-HEADER>
+  <CORE-CONTENT>
+    <REQ-IF-CONTENT>
+      <DATATYPES>
+        <DATATYPE-DEFINITION-BOOLEAN IDENTIFIER="_0d5e2b1a-6c3f-4e8a-b7d2-9f1a3c5e7b20" LAST-CHANGE="2023-11-07T16:42:09.512Z" LONG-NAME="Boolean"/>
+        <DATATYPE-DEFINITION-INTEGER IDENTIFIER="_1e6f3c2b-7d4a-4f9b-c8e3-0a2b4d6f8c30" LAST-CHANGE="2023-11-07T16:42:09.512Z" LONG-NAME="Integer" MAX="2147483647" MIN="-2147483648"/>
+        <DATATYPE-DEFINITION-DATE IDENTIFIER="_2f7a4d3c-8e5b-4a0c-d9f4-1b3c5e7a9d40" LAST-CHANGE="2023-11-07T16:42:09.512Z" LONG-NAME="Date"/>
+        <DATATYPE-DEFINITION-XHTML IDENTIFIER="_3a8b5e4d-9f6c-4b1d-e0a5-2c4d6f8b0e50" LAST-CHANGE="2023-11-07T16:42:09.512Z" LONG-NAME="Text"/>
+      </DATATYPES>
+      <SPEC-TYPES>
+        <SPEC-OBJECT-TYPE IDENTIFIER="_4b9c6f5e-0a7d-4c2e-f1b6-3d5e7a9c1f60" LAST-CHANGE="2023-11-07T16:42:09.512Z" LONG-NAME="System Requirement">
+          <SPEC-ATTRIBUTES>
+            <ATTRIBUTE-DEFINITION-XHTML IDENTIFIER="_5c0d7a6f-1b8e-4d3f-a2c7-4e6f8b0d2a70" LAST-CHANGE="2023-11-07T16:42:09.512Z" LONG-NAME="ReqIF.Text">
+              <TYPE>
+                <DATATYPE-DEFINITION-XHTML-REF>_3a8b5e4d-9f6c-4b1d-e0a5-2c4d6f8b0e50</DATATYPE-DEFINITION-XHTML-REF>
+              </TYPE>
+            </ATTRIBUTE-DEFINITION-XHTML>
+            <ATTRIBUTE-DEFINITION-BOOLEAN IDENTIFIER="_6d1e8b7a-2c9f-4e4a-b3d8-5f7a9c1e3b80" LAST-CHANGE="2023-11-07T16:42:09.512Z" LONG-NAME="Verified">
+              <DEFAULT-VALUE>
+                <ATTRIBUTE-VALUE-BOOLEAN THE-VALUE="false">
+                  <DEFINITION>
+                    <ATTRIBUTE-DEFINITION-BOOLEAN-REF>_6d1e8b7a-2c9f-4e4a-b3d8-5f7a9c1e3b80</ATTRIBUTE-DEFINITION-BOOLEAN-REF>
+                  </DEFINITION>
+                </ATTRIBUTE-VALUE-BOOLEAN>
+              </DEFAULT-VALUE>
+              <TYPE>
+                <DATATYPE-DEFINITION-BOOLEAN-REF>_0d5e2b1a-6c3f-4e8a-b7d2-9f1a3c5e7b20</DATATYPE-DEFINITION-BOOLEAN-REF>
+              </TYPE>
+            </ATTRIBUTE-DEFINITION-BOOLEAN>
+            <ATTRIBUTE-DEFINITION-INTEGER IDENTIFIER="_7e2f9c8b-3d0a-4f5b-c4e9-6a8b0d2f4c90" LAST-CHANGE="2023-11-07T16:42:09.512Z" LONG-NAME="Priority">
+              <TYPE>
+                <DATATYPE-DEFINITION-INTEGER-REF>_1e6f3c2b-7d4a-4f9b-c8e3-0a2b4d6f8c30</DATATYPE-DEFINITION-INTEGER-REF>
+              </TYPE>
+            </ATTRIBUTE-DEFINITION-INTEGER>
+            <ATTRIBUTE-DEFINITION-DATE IDENTIFIER="_8f3a0d9c-4e1b-4a6c-d5f0-7b9c1e3a5d00" LAST-CHANGE="2023-11-07T16:42:09.512Z" LONG-NAME="Review Date">
+              <TYPE>
+                <DATATYPE-DEFINITION-DATE-REF>_2f7a4d3c-8e5b-4a0c-d9f4-1b3c5e7a9d40</DATATYPE-DEFINITION-DATE-REF>
+              </TYPE>
+            </ATTRIBUTE-DEFINITION-DATE>
+          </SPEC-ATTRIBUTES>
+        </SPEC-OBJECT-TYPE>
+        <SPECIFICATION-TYPE IDENTIFIER="_9a4b1e0d-5f2c-4b7d-e6a1-8c0d2f4b6e10" LAST-CHANGE="2023-11-07T16:42:09.512Z" LONG-NAME="Requirements Module"/>
+        <SPEC-RELATION-TYPE IDENTIFIER="_0b5c2f1e-6a3d-4c8e-f7b2-9d1e3a5c7f20" LAST-CHANGE="2023-11-07T16:42:09.512Z" LONG-NAME="Refines"/>
+      </SPEC-TYPES>
+      <SPEC-OBJECTS>
+        <SPEC-OBJECT IDENTIFIER="_1c6d3a2f-7b4e-4d9f-a8c3-0e2f4b6d8a30" LAST-CHANGE="2023-11-07T16:40:51.007Z" LONG-NAME="Start-up">
+          <TYPE>
+            <SPEC-OBJECT-TYPE-REF>_4b9c6f5e-0a7d-4c2e-f1b6-3d5e7a9c1f60</SPEC-OBJECT-TYPE-REF>
+          </TYPE>
+          <VALUES>
+            <ATTRIBUTE-VALUE-XHTML>
+              <DEFINITION>
+                <ATTRIBUTE-DEFINITION-XHTML-REF>_5c0d7a6f-1b8e-4d3f-a2c7-4e6f8b0d2a70</ATTRIBUTE-DEFINITION-XHTML-REF>
+              </DEFINITION>
+              <THE-VALUE>
+                <xhtml:div>
+                  <xhtml:p>The system shall start as shown:</xhtml:p>
+                  <xhtml:p><xhtml:object data="files/startup.png" type="image/png"><xhtml:object data="files/startup.ole" type="application/octet-stream">startup</xhtml:object></xhtml:object></xhtml:p>
+                </xhtml:div>
+              </THE-VALUE>
+            </ATTRIBUTE-VALUE-XHTML>
+            <ATTRIBUTE-VALUE-INTEGER THE-VALUE="2">
+              <DEFINITION>
+                <ATTRIBUTE-DEFINITION-INTEGER-REF>_7e2f9c8b-3d0a-4f5b-c4e9-6a8b0d2f4c90</ATTRIBUTE-DEFINITION-INTEGER-REF>
+              </DEFINITION>
+            </ATTRIBUTE-VALUE-INTEGER>
+            <ATTRIBUTE-VALUE-DATE THE-VALUE="2023-11-30T00:00:00.000Z">
+              <DEFINITION>
+                <ATTRIBUTE-DEFINITION-DATE-REF>_8f3a0d9c-4e1b-4a6c-d5f0-7b9c1e3a5d00</ATTRIBUTE-DEFINITION-DATE-REF>
+              </DEFINITION>
+            </ATTRIBUTE-VALUE-DATE>
+          </VALUES>
+        </SPEC-OBJECT>
+        <SPEC-OBJECT IDENTIFIER="_2d7e4b3a-8c5f-4e0a-b9d4-1f3a5c7e9b40" LAST-CHANGE="2023-11-07T16:41:13.228Z" LONG-NAME="Start-up time">
+          <TYPE>
+            <SPEC-OBJECT-TYPE-REF>_4b9c6f5e-0a7d-4c2e-f1b6-3d5e7a9c1f60</SPEC-OBJECT-TYPE-REF>
+          </TYPE>
+          <VALUES>
+            <ATTRIBUTE-VALUE-XHTML IS-SIMPLIFIED="true">
+              <DEFINITION>
+                <ATTRIBUTE-DEFINITION-XHTML-REF>_5c0d7a6f-1b8e-4d3f-a2c7-4e6f8b0d2a70</ATTRIBUTE-DEFINITION-XHTML-REF>
+              </DEFINITION>
+              <THE-ORIGINAL-VALUE>
+                <xhtml:div><xhtml:table><xhtml:tr><xhtml:th>Mode</xhtml:th><xhtml:th>Time</xhtml:th></xhtml:tr><xhtml:tr><xhtml:td>Cold</xhtml:td><xhtml:td>5 s</xhtml:td></xhtml:tr></xhtml:table></xhtml:div>
+              </THE-ORIGINAL-VALUE>
+              <THE-VALUE>
+                <xhtml:div>Mode: Cold, Time: 5 s</xhtml:div>
+              </THE-VALUE>
+            </ATTRIBUTE-VALUE-XHTML>
+          </VALUES>
+        </SPEC-OBJECT>
+      </SPEC-OBJECTS>
+      <SPEC-RELATIONS>
+        <SPEC-RELATION IDENTIFIER="_3e8f5c4b-9d6a-4f1b-c0e5-2a4b6d8f0c50" LAST-CHANGE="2023-11-07T16:41:13.228Z">
+          <TYPE>
+            <SPEC-RELATION-TYPE-REF>_0b5c2f1e-6a3d-4c8e-f7b2-9d1e3a5c7f20</SPEC-RELATION-TYPE-REF>
+          </TYPE>
+          <SOURCE>
+            <SPEC-OBJECT-REF>_2d7e4b3a-8c5f-4e0a-b9d4-1f3a5c7e9b40</SPEC-OBJECT-REF>
+          </SOURCE>
+          <TARGET>
+            <SPEC-OBJECT-REF>_1c6d3a2f-7b4e-4d9f-a8c3-0e2f4b6d8a30</SPEC-OBJECT-REF>
+          </TARGET>
+        </SPEC-RELATION>
+      </SPEC-RELATIONS>
+      <SPECIFICATIONS>
+        <SPECIFICATION IDENTIFIER="_4f9a6d5c-0e7b-4a2c-d1f6-3b5c7e9a1d60" LAST-CHANGE="2023-11-07T16:42:09.512Z" LONG-NAME="System Analysis">
+          <TYPE>
+            <SPECIFICATION-TYPE-REF>_9a4b1e0d-5f2c-4b7d-e6a1-8c0d2f4b6e10</SPECIFICATION-TYPE-REF>
+          </TYPE>
+          <CHILDREN>
+            <SPEC-HIERARCHY IDENTIFIER="_5a0b7e6d-1f8c-4b3d-e2a7-4c6d8f0b2e70" LAST-CHANGE="2023-11-07T16:42:09.512Z">
+              <OBJECT>
+                <SPEC-OBJECT-REF>_1c6d3a2f-7b4e-4d9f-a8c3-0e2f4b6d8a30</SPEC-OBJECT-REF>
+              </OBJECT>
+            </SPEC-HIERARCHY>
+            <SPEC-HIERARCHY IDENTIFIER="_6b1c8f7e-2a9d-4c4e-f3b8-5d7e9a1c3f80" LAST-CHANGE="2023-11-07T16:42:09.512Z">
+              <OBJECT>
+                <SPEC-OBJECT-REF>_2d7e4b3a-8c5f-4e0a-b9d4-1f3a5c7e9b40</SPEC-OBJECT-REF>
+              </OBJECT>
+            </SPEC-HIERARCHY>
+          </CHILDREN>
+        </SPECIFICATION>
+      </SPECIFICATIONS>
+    </REQ-IF-CONTENT>
+  </CORE-CONTENT>
+</REQ-IF>"#;
+
+    #[test]
+    fn test_content_models() {
+        assert_eq!(validate(REQIF_STUDIO), vec![]);
+        assert_eq!(validate(CAPELLA), vec![]);
+        let errors: Vec<String> = validate(&CAPELLA.replace(r#" MAX="2147483647""#, ""))
+            .into_iter()
+            .map(|e| e.message)
+            .collect();
+        assert_eq!(
+            errors,
+            vec!["Missing attribute MAX in DATATYPE-DEFINITION-INTEGER"]
+        );
+
+        assert!(messages("<SPEC-OBJECTS/><SPEC-TYPES/><DATATYPES/>").is_empty());
+        assert_eq!(
+            messages("<DATATYPES/><SPEC-OBJECTS/><DATATYPES/>"),
+            vec!["Too many DATATYPES elements in REQ-IF-CONTENT"]
+        );
+        let header = r#"<THE-HEADER><REQ-IF-HEADER IDENTIFIER="header">
+  <CREATION-TIME>2024-04-01T10:00:00+02:00</CREATION-TIME>
+  <REQ-IF-TOOL-ID>tool</REQ-IF-TOOL-ID>
+  <REQ-IF-VERSION>1.0</REQ-IF-VERSION>
+  <SOURCE-TOOL-ID>tool</SOURCE-TOOL-ID>
+  <TITLE>Content</TITLE>
+</REQ-IF-HEADER></THE-HEADER>"#;
+        let unordered = format!(
+            r#"<REQ-IF xmlns="http://www.omg.org/spec/ReqIF/20110401/reqif.xsd">
+<CORE-CONTENT><REQ-IF-CONTENT/></CORE-CONTENT>{}</REQ-IF>"#,
+            header
+        );
+        let errors: Vec<String> = validate(&unordered)
+            .into_iter()
+            .map(|e| e.message)
+            .collect();
+        assert_eq!(errors, vec!["Element THE-HEADER is out of order in REQ-IF"]);
+
+        let value = |value: &str| {
+            messages(&format!(
+                r#"<SPEC-OBJECTS>
+  <SPEC-OBJECT IDENTIFIER="obj-1" LAST-CHANGE="2024-04-01T10:00:00+02:00">
+    <TYPE><SPEC-OBJECT-TYPE-REF>type</SPEC-OBJECT-TYPE-REF></TYPE>
+    <VALUES>
+      <ATTRIBUTE-VALUE-XHTML>
+        <DEFINITION><ATTRIBUTE-DEFINITION-XHTML-REF>text</ATTRIBUTE-DEFINITION-XHTML-REF></DEFINITION>
+        {}
+      </ATTRIBUTE-VALUE-XHTML>
+    </VALUES>
+  </SPEC-OBJECT>
+</SPEC-OBJECTS>"#,
+                value
+            ))
+        };
+        assert!(value(
+            "<THE-VALUE><xhtml:div><xhtml:p>A <xhtml:b>bold</xhtml:b> word</xhtml:p>\
+             <xhtml:ul><xhtml:li>item</xhtml:li></xhtml:ul></xhtml:div></THE-VALUE>"
+        )
+        .is_empty());
+        assert_eq!(
+            value(""),
+            vec!["Missing element THE-VALUE in ATTRIBUTE-VALUE-XHTML"]
+        );
+        assert_eq!(
+            value("<THE-VALUE><xhtml:blink><xhtml:script/></xhtml:blink></THE-VALUE>"),
+            vec![
+                "Unknown XHTML element blink",
+                "Unknown XHTML element script"
+            ]
+        );
+        assert_eq!(
+            value("<THE-VALUE><xhtml:span>text</xhtml:span></THE-VALUE>"),
+            vec!["THE-VALUE must hold an xhtml:div or xhtml:p element"]
+        );
+        assert_eq!(
+            value("<THE-VALUE><xhtml:p><xhtml:div>text</xhtml:div></xhtml:p></THE-VALUE>"),
+            vec!["Element xhtml:div is not allowed in xhtml:p"]
+        );
+        assert_eq!(
+            value("<THE-VALUE><xhtml:div><xhtml:ul>text</xhtml:ul></xhtml:div></THE-VALUE>"),
+            vec!["Text is not allowed in xhtml:ul"]
+        );
+        assert_eq!(
+            value("<THE-VALUE>text</THE-VALUE>"),
+            vec![
+                "Text is not allowed in THE-VALUE",
+                "Missing XHTML element in THE-VALUE"
+            ]
+        );
+    }
+}
@@ -0,0 +1,761 @@
+// reqif-rs: Help library to write reqif files implemented in Rust.
+// Copyright (C) <2024>  INVAP S.E.
+//
+// This file is part of reqif-rs.
+//
+// reqif-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::fmt;
+
+use crate::identifier::is_valid_ncname;
+use crate::req_if::{
+    AttributeValues, DataTypes, ReqIf, SpecAttributes, SpecHierarchy, SpecTypes, Specification,
+};
+
+/// Kind of element a reference is expected to point at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReferenceKind {
+    Datatype,
+    EnumValue,
+    SpecType,
+    AttributeDefinition,
+    SpecObject,
+    SpecRelation,
+    Specification,
+}
+
+impl fmt::Display for ReferenceKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ReferenceKind::Datatype => "DATATYPE",
+            ReferenceKind::EnumValue => "ENUM-VALUE",
+            ReferenceKind::SpecType => "SPEC-TYPE",
+            ReferenceKind::AttributeDefinition => "ATTRIBUTE-DEFINITION",
+            ReferenceKind::SpecObject => "SPEC-OBJECT",
+            ReferenceKind::SpecRelation => "SPEC-RELATION",
+            ReferenceKind::Specification => "SPECIFICATION",
+        };
+        f.write_str(name)
+    }
+}
+
+/// A problem found by [`ReqIf::validate`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Diagnostic {
+    /// More than one element uses `identifier`.
+    DuplicateIdentifier { identifier: String },
+    /// `identifier` is not a valid xsd:ID.
+    InvalidIdentifier { identifier: String },
+    /// `element` references `reference`, which is not a `kind` of the
+    /// document, or not one of the expected datatype.
+    DanglingReference {
+        element: String,
+        kind: ReferenceKind,
+        reference: String,
+    },
+    /// `element` holds a value of `definition`, which is not an attribute of
+    /// the element's type.
+    ForeignAttributeValue { element: String, definition: String },
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Diagnostic::DuplicateIdentifier { identifier } => {
+                write!(f, "Duplicated identifier {}", identifier)
+            }
+            Diagnostic::InvalidIdentifier { identifier } => {
+                write!(f, "Invalid identifier {:?}", identifier)
+            }
+            Diagnostic::DanglingReference {
+                element,
+                kind,
+                reference,
+            } => write!(f, "{} references unknown {} {}", element, kind, reference),
+            Diagnostic::ForeignAttributeValue {
+                element,
+                definition,
+            } => write!(
+                f,
+                "{} has a value for {}, which is not an attribute of its type",
+                element, definition
+            ),
+        }
+    }
+}
+
+/// Error returned when writing a document that doesn't validate.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidationError {
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid ReqIF document:")?;
+        for diagnostic in &self.diagnostics {
+            write!(f, "\n  {}", diagnostic)?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for ValidationError {}
+
+/// (identifier, datatype kind, datatype reference) of each attribute definition.
+fn attribute_definitions(attributes: &SpecAttributes) -> Vec<(&String, &'static str, &String)> {
+    let mut definitions = vec![];
+    for d in &attributes.boolean_attributes {
+        definitions.push((&d.identifier, "BOOLEAN", &d.type_ref.reference));
+    }
+    for d in &attributes.date_attributes {
+        definitions.push((&d.identifier, "DATE", &d.type_ref.reference));
+    }
+    for d in &attributes.enumeration_attributes {
+        definitions.push((&d.identifier, "ENUMERATION", &d.type_ref.reference));
+    }
+    for d in &attributes.integer_attributes {
+        definitions.push((&d.identifier, "INTEGER", &d.type_ref.reference));
+    }
+    for d in &attributes.real_attributes {
+        definitions.push((&d.identifier, "REAL", &d.type_ref.reference));
+    }
+    for d in &attributes.string_attributes {
+        definitions.push((&d.identifier, "STRING", &d.type_ref.reference));
+    }
+    for d in &attributes.xhtml_attributes {
+        definitions.push((&d.identifier, "XHTML", &d.type_ref.reference));
+    }
+    definitions
+}
+
+/// (attribute definition, datatype kind) of each value.
+fn attribute_values(values: &AttributeValues) -> Vec<(&String, &'static str)> {
+    let mut kinds = vec![];
+    for v in &values.boolean_values {
+        kinds.push((&v.definition.reference, "BOOLEAN"));
+    }
+    for v in &values.date_values {
+        kinds.push((&v.definition.reference, "DATE"));
+    }
+    for v in &values.enumeration_values {
+        kinds.push((&v.definition.reference, "ENUMERATION"));
+    }
+    for v in &values.integer_values {
+        kinds.push((&v.definition.reference, "INTEGER"));
+    }
+    for v in &values.real_values {
+        kinds.push((&v.definition.reference, "REAL"));
+    }
+    for v in &values.string_values {
+        kinds.push((&v.definition.reference, "STRING"));
+    }
+    for v in &values.xhtml_values {
+        kinds.push((&v.definition.reference, "XHTML"));
+    }
+    kinds
+}
+
+fn datatype_kinds(data_types: &DataTypes) -> HashMap<&str, &'static str> {
+    let mut kinds = HashMap::new();
+    for d in &data_types.boolean_definitions {
+        kinds.insert(d.identifier.as_str(), "BOOLEAN");
+    }
+    for d in &data_types.date_definitions {
+        kinds.insert(d.identifier.as_str(), "DATE");
+    }
+    for d in &data_types.enumeration_definitions {
+        kinds.insert(d.identifier.as_str(), "ENUMERATION");
+    }
+    for d in &data_types.integer_definitions {
+        kinds.insert(d.identifier.as_str(), "INTEGER");
+    }
+    for d in &data_types.real_definitions {
+        kinds.insert(d.identifier.as_str(), "REAL");
+    }
+    for d in &data_types.string_definitions {
+        kinds.insert(d.identifier.as_str(), "STRING");
+    }
+    for d in &data_types.xhtml_definitions {
+        kinds.insert(d.identifier.as_str(), "XHTML");
+    }
+    kinds
+}
+
+fn walk_hierarchy<'a>(nodes: &'a [SpecHierarchy], found: &mut Vec<&'a SpecHierarchy>) {
+    for node in nodes {
+        found.push(node);
+        if let Some(children) = &node.children {
+            walk_hierarchy(children.get_spec_hierarchy(), found);
+        }
+    }
+}
+
+/// The SPEC-ATTRIBUTES of every spec type.
+fn type_attributes(spec_types: &SpecTypes) -> Vec<&SpecAttributes> {
+    let mut attributes: Vec<&SpecAttributes> = spec_types
+        .get_specification_types()
+        .iter()
+        .map(|t| &t.attributes)
+        .collect();
+    attributes.extend(
+        spec_types
+            .get_spec_object_types()
+            .iter()
+            .map(|t| &t.attributes),
+    );
+    attributes.extend(
+        spec_types
+            .get_spec_relation_types()
+            .iter()
+            .map(|t| &t.attributes),
+    );
+    attributes.extend(
+        spec_types
+            .get_relation_group_types()
+            .iter()
+            .map(|t| &t.attributes),
+    );
+    attributes
+}
+
+/// Every hierarchy node of `specifications`, in document order.
+fn hierarchy_nodes(specifications: &[Specification]) -> Vec<&SpecHierarchy> {
+    let mut nodes = vec![];
+    for specification in specifications {
+        walk_hierarchy(specification.children.get_spec_hierarchy(), &mut nodes);
+    }
+    nodes
+}
+
+/// Identifiers of every element of `req_if`, in document order.
+pub(crate) fn identifiers(req_if: &ReqIf) -> Vec<&str> {
+    let content = &req_if.core_content.req_if_content;
+    let spec_types = &content.spec_types;
+    let data_types = &content.data_types;
+    let specifications = content.specifications.get_specifications();
+
+    let type_attributes = type_attributes(spec_types);
+    let nodes = hierarchy_nodes(specifications);
+
+    let mut identifiers: Vec<&str> = vec![];
+    identifiers.extend(
+        data_types
+            .boolean_definitions
+            .iter()
+            .map(|d| d.identifier.as_str()),
+    );
+    identifiers.extend(
+        data_types
+            .date_definitions
+            .iter()
+            .map(|d| d.identifier.as_str()),
+    );
+    for enumeration in &data_types.enumeration_definitions {
+        identifiers.push(&enumeration.identifier);
+        identifiers.extend(
+            enumeration
+                .specified_values
+                .values
+                .iter()
+                .map(|v| v.identifier.as_str()),
+        );
+    }
+    identifiers.extend(
+        data_types
+            .integer_definitions
+            .iter()
+            .map(|d| d.identifier.as_str()),
+    );
+    identifiers.extend(
+        data_types
+            .real_definitions
+            .iter()
+            .map(|d| d.identifier.as_str()),
+    );
+    identifiers.extend(
+        data_types
+            .string_definitions
+            .iter()
+            .map(|d| d.identifier.as_str()),
+    );
+    identifiers.extend(
+        data_types
+            .xhtml_definitions
+            .iter()
+            .map(|d| d.identifier.as_str()),
+    );
+    identifiers.extend(
+        spec_types
+            .get_specification_types()
+            .iter()
+            .map(|t| t.identifier.as_str()),
+    );
+    identifiers.extend(
+        spec_types
+            .get_spec_object_types()
+            .iter()
+            .map(|t| t.identifier.as_str()),
+    );
+    identifiers.extend(
+        spec_types
+            .get_spec_relation_types()
+            .iter()
+            .map(|t| t.identifier.as_str()),
+    );
+    identifiers.extend(
+        spec_types
+            .get_relation_group_types()
+            .iter()
+            .map(|t| t.identifier.as_str()),
+    );
+    for attributes in &type_attributes {
+        identifiers.extend(
+            attribute_definitions(attributes)
+                .iter()
+                .map(|(identifier, _, _)| identifier.as_str()),
+        );
+    }
+    identifiers.extend(
+        content
+            .spec_object
+            .get_spec_objects()
+            .iter()
+            .map(|o| o.identifier.as_str()),
+    );
+    identifiers.extend(
+        content
+            .spec_relations
+            .get_spec_relations()
+            .iter()
+            .map(|r| r.identifier.as_str()),
+    );
+    identifiers.extend(specifications.iter().map(|s| s.identifier.as_str()));
+    identifiers.extend(nodes.iter().map(|n| n.identifier.as_str()));
+    identifiers.extend(
+        content
+            .spec_relation_groups
+            .get_relation_groups()
+            .iter()
+            .map(|g| g.identifier.as_str()),
+    );
+    identifiers
+}
+
+struct Validator<'a> {
+    req_if: &'a ReqIf,
+    /// Attribute definitions of every spec type: identifier to datatype kind.
+    all_definitions: HashMap<&'a str, &'static str>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl<'a> Validator<'a> {
+    fn dangling(&mut self, element: &str, kind: ReferenceKind, reference: &str) {
+        self.diagnostics.push(Diagnostic::DanglingReference {
+            element: element.to_string(),
+            kind,
+            reference: reference.to_string(),
+        });
+    }
+
+    fn check_identifiers(&mut self, identifiers: Vec<&str>) {
+        let mut seen = HashSet::new();
+        let mut reported = HashSet::new();
+        for identifier in identifiers {
+            if !is_valid_ncname(identifier) {
+                self.diagnostics.push(Diagnostic::InvalidIdentifier {
+                    identifier: identifier.to_string(),
+                });
+            }
+            if !seen.insert(identifier) && reported.insert(identifier) {
+                self.diagnostics.push(Diagnostic::DuplicateIdentifier {
+                    identifier: identifier.to_string(),
+                });
+            }
+        }
+    }
+
+    fn check_definitions(&mut self, attributes: &SpecAttributes) {
+        let req_if = self.req_if;
+        let kinds = datatype_kinds(&req_if.core_content.req_if_content.data_types);
+        for (identifier, kind, data_type) in attribute_definitions(attributes) {
+            if kinds.get(data_type.as_str()) != Some(&kind) {
+                self.dangling(identifier, ReferenceKind::Datatype, data_type);
+            }
+        }
+    }
+
+    /// Checks the values of `element` against the attributes of its type,
+    /// when the type is known.
+    fn check_values(
+        &mut self,
+        element: &str,
+        values: &AttributeValues,
+        attributes: Option<&SpecAttributes>,
+    ) {
+        let own: Vec<(&String, &'static str)> = attributes
+            .map(|a| {
+                attribute_definitions(a)
+                    .into_iter()
+                    .map(|(identifier, kind, _)| (identifier, kind))
+                    .collect()
+            })
+            .unwrap_or_default();
+        for (definition, kind) in attribute_values(values) {
+            if self.all_definitions.get(definition.as_str()) != Some(&kind) {
+                self.dangling(element, ReferenceKind::AttributeDefinition, definition);
+            } else if attributes.is_some() && !own.contains(&(definition, kind)) {
+                self.diagnostics.push(Diagnostic::ForeignAttributeValue {
+                    element: element.to_string(),
+                    definition: definition.to_string(),
+                });
+            }
+        }
+
+        let req_if = self.req_if;
+        let content = &req_if.core_content.req_if_content;
+        for value in &values.enumeration_values {
+            let enumeration = attributes
+                .and_then(|a| {
+                    a.enumeration_attributes
+                        .iter()
+                        .find(|d| d.identifier == value.definition.reference)
+                })
+                .and_then(|d| {
+                    content
+                        .data_types
+                        .get_enumeration_definition(&d.type_ref.reference)
+                });
+            if let Some(enumeration) = enumeration {
+                for reference in &value.values.references {
+                    if !enumeration
+                        .specified_values
+                        .values
+                        .iter()
+                        .any(|v| &v.identifier == reference)
+                    {
+                        self.dangling(element, ReferenceKind::EnumValue, reference);
+                    }
+                }
+            }
+        }
+    }
+
+    fn run(&mut self) {
+        let req_if = self.req_if;
+        let content = &req_if.core_content.req_if_content;
+        let spec_types = &content.spec_types;
+        let spec_objects = content.spec_object.get_spec_objects();
+        let spec_relations = content.spec_relations.get_spec_relations();
+        let specifications = content.specifications.get_specifications();
+        let relation_groups = content.spec_relation_groups.get_relation_groups();
+
+        let type_attributes = type_attributes(spec_types);
+        let nodes = hierarchy_nodes(specifications);
+
+        self.check_identifiers(identifiers(req_if));
+
+        for attributes in &type_attributes {
+            for (identifier, kind, _) in attribute_definitions(attributes) {
+                self.all_definitions.insert(identifier, kind);
+            }
+        }
+        for attributes in type_attributes {
+            self.check_definitions(attributes);
+        }
+
+        for object in spec_objects {
+            let reference = &object.spec_object_type.reference;
+            let object_type = spec_types.get_spec_object_type(reference);
+            if object_type.is_none() {
+                self.dangling(&object.identifier, ReferenceKind::SpecType, reference);
+            }
+            self.check_values(
+                &object.identifier,
+                &object.values,
+                object_type.map(|t| &t.attributes),
+            );
+        }
+
+        let object_ids: HashSet<&str> =
+            spec_objects.iter().map(|o| o.identifier.as_str()).collect();
+        for relation in spec_relations {
+            let reference = &relation.spec_relation_type.reference;
+            let relation_type = spec_types.get_spec_relation_type(reference);
+            if relation_type.is_none() {
+                self.dangling(&relation.identifier, ReferenceKind::SpecType, reference);
+            }
+            for end in [&relation.source.object_ref, &relation.target.object_ref] {
+                if !object_ids.contains(end.as_str()) {
+                    self.dangling(&relation.identifier, ReferenceKind::SpecObject, end);
+                }
+            }
+            self.check_values(
+                &relation.identifier,
+                &relation.values,
+                relation_type.map(|t| &t.attributes),
+            );
+        }
+
+        for specification in specifications {
+            let reference = &specification.type_ref.spec_ref;
+            let specification_type = spec_types.get_specification_type(reference);
+            if specification_type.is_none() {
+                self.dangling(
+                    &specification.identifier,
+                    ReferenceKind::SpecType,
+                    reference,
+                );
+            }
+            self.check_values(
+                &specification.identifier,
+                &specification.values,
+                specification_type.map(|t| &t.attributes),
+            );
+        }
+        for node in nodes {
+            if !object_ids.contains(node.object.object_ref.as_str()) {
+                self.dangling(
+                    &node.identifier,
+                    ReferenceKind::SpecObject,
+                    &node.object.object_ref,
+                );
+            }
+        }
+
+        let relation_ids: HashSet<&str> = spec_relations
+            .iter()
+            .map(|r| r.identifier.as_str())
+            .collect();
+        let specification_ids: HashSet<&str> = specifications
+            .iter()
+            .map(|s| s.identifier.as_str())
+            .collect();
+        for group in relation_groups {
+            let reference = &group.relation_group_type.reference;
+            if spec_types.get_relation_group_type(reference).is_none() {
+                self.dangling(&group.identifier, ReferenceKind::SpecType, reference);
+            }
+            for end in [
+                &group.source_specification.specification_ref,
+                &group.target_specification.specification_ref,
+            ] {
+                if !specification_ids.contains(end.as_str()) {
+                    self.dangling(&group.identifier, ReferenceKind::Specification, end);
+                }
+            }
+            for relation in &group.spec_relations.references {
+                if !relation_ids.contains(relation.as_str()) {
+                    self.dangling(&group.identifier, ReferenceKind::SpecRelation, relation);
+                }
+            }
+        }
+    }
+}
+
+/// Checks the referential integrity of `req_if`, see [`ReqIf::validate`].
+pub(crate) fn validate(req_if: &ReqIf) -> Vec<Diagnostic> {
+    let mut validator = Validator {
+        req_if,
+        all_definitions: HashMap::new(),
+        diagnostics: vec![],
+    };
+    validator.run();
+    validator.diagnostics
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Diagnostic, ReferenceKind, ValidationError};
+    use crate::clock;
+    use crate::fixture::document;
+    use crate::req_if::{
+        AttributeDefinitionString, AttributeDefinitionXHtml, AttributeValueString,
+        DataTypeDefinitionString, Object, ReqIf, SpecHierarchy, SpecObject, SpecObjectType,
+        SpecRelation, SpecRelationType,
+    };
+
+    fn build() -> ReqIf {
+        let mut reqif = document("Validation");
+        let now = clock::now();
+        reqif
+            .add_datatype(DataTypeDefinitionString::new(
+                "DT-STRING".to_string(),
+                now,
+                "String".to_string(),
+                32,
+            ))
+            .expect("error");
+        let mut test_case = SpecObjectType::new("SOT-TEST".to_string(), now, "Test".to_string());
+        test_case
+            .add_attribute(AttributeDefinitionString::new(
+                "AD-STEPS".to_string(),
+                now,
+                "Steps".to_string(),
+                "DT-STRING".to_string(),
+            ))
+            .expect("error");
+        reqif.add_spec_object_type(test_case).expect("error");
+        reqif
+            .add_spec_relation_type(SpecRelationType::new(
+                "SRT-VERIFIES".to_string(),
+                now,
+                "verifies".to_string(),
+            ))
+            .expect("error");
+
+        reqif
+            .add_requirement(
+                SpecObject::requirement(
+                    "REQ-1".to_string(),
+                    now,
+                    "First".to_string(),
+                    "Text.".to_string(),
+                    &reqif.core_content.req_if_content.spec_types,
+                )
+                .expect("error"),
+            )
+            .expect("error");
+        let mut test = SpecObject::new(
+            "TEST-1".to_string(),
+            now,
+            "Test".to_string(),
+            "SOT-TEST".to_string(),
+        );
+        test.set_value(AttributeValueString::new(
+            "AD-STEPS".to_string(),
+            "Run it".to_string(),
+        ));
+        reqif.add_spec_object(test).expect("error");
+        reqif
+            .add_spec_relation(SpecRelation::new(
+                "REL-1".to_string(),
+                now,
+                String::new(),
+                "SRT-VERIFIES".to_string(),
+                "TEST-1".to_string(),
+                "REQ-1".to_string(),
+            ))
+            .expect("error");
+
+        let mut specification = reqif
+            .build_module_specification("SPEC-1".to_string(), now, "Module".to_string())
+            .expect("error");
+        specification
+            .children
+            .add_spec_hierarchy(
+                SpecHierarchy::new("H-1".to_string(), now, Object::new("REQ-1".to_string())),
+                0,
+            )
+            .expect("error");
+        reqif.add_specification(specification).expect("error");
+        reqif
+    }
+
+    #[test]
+    fn test_valid_document() {
+        assert_eq!(build().validate(), vec![]);
+    }
+
+    #[test]
+    fn test_invalid_document() {
+        let now = clock::now();
+        let mut reqif = build();
+        reqif
+            .add_requirement_attribute(AttributeDefinitionXHtml::new(
+                "AD-NOTES".to_string(),
+                now,
+                "Notes".to_string(),
+                "DT-STRING".to_string(),
+            ))
+            .expect("error");
+        let mut requirement = SpecObject::requirement(
+            "REQ-2".to_string(),
+            now,
+            "Second".to_string(),
+            "Text.".to_string(),
+            &reqif.core_content.req_if_content.spec_types,
+        )
+        .expect("error");
+        requirement.set_value(AttributeValueString::new(
+            "AD-STEPS".to_string(),
+            "Not a requirement attribute".to_string(),
+        ));
+        reqif.add_requirement(requirement).expect("error");
+        reqif
+            .add_requirement(
+                SpecObject::requirement(
+                    "REQ-3".to_string(),
+                    now,
+                    "Clash".to_string(),
+                    "Text.".to_string(),
+                    &reqif.core_content.req_if_content.spec_types,
+                )
+                .expect("error"),
+            )
+            .expect("error");
+        reqif.get_spec_object_mut("REQ-3").unwrap().identifier = "TEST-1".to_string();
+        let mut specification = reqif
+            .build_module_specification("SPEC-2".to_string(), now, "Other".to_string())
+            .expect("error");
+        specification
+            .children
+            .add_spec_hierarchy(
+                SpecHierarchy::new("H-2".to_string(), now, Object::new("REQ-9".to_string())),
+                0,
+            )
+            .expect("error");
+        reqif.add_specification(specification).expect("error");
+        reqif
+            .update_spec_hierarchy("H-2", |node| node.identifier = "2 H".to_string())
+            .expect("error");
+
+        assert_eq!(
+            reqif.validate(),
+            vec![
+                Diagnostic::DuplicateIdentifier {
+                    identifier: "TEST-1".to_string()
+                },
+                Diagnostic::InvalidIdentifier {
+                    identifier: "2 H".to_string()
+                },
+                Diagnostic::DanglingReference {
+                    element: "AD-NOTES".to_string(),
+                    kind: ReferenceKind::Datatype,
+                    reference: "DT-STRING".to_string()
+                },
+                Diagnostic::ForeignAttributeValue {
+                    element: "REQ-2".to_string(),
+                    definition: "AD-STEPS".to_string()
+                },
+                Diagnostic::DanglingReference {
+                    element: "2 H".to_string(),
+                    kind: ReferenceKind::SpecObject,
+                    reference: "REQ-9".to_string()
+                },
+            ]
+        );
+
+        assert!(reqif.to_xml_string().is_ok());
+        reqif.set_validate_on_write(true);
+        let error = reqif.to_xml_string().expect_err("error");
+        let error = error.downcast::<ValidationError>().expect("error");
+        assert_eq!(error.diagnostics.len(), 5);
+        assert!(error
+            .to_string()
+            .contains("2 H references unknown SPEC-OBJECT REQ-9"));
+    }
+}
@@ -0,0 +1,327 @@
+// reqif-rs: Help library to write reqif files implemented in Rust.
+// Copyright (C) <2024>  INVAP S.E.
+//
+// This file is part of reqif-rs.
+//
+// reqif-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use anyhow::{anyhow, bail, Result};
+use std::cell::RefCell;
+use std::io::{self, Write};
+use std::rc::Rc;
+use xml::writer::XmlEvent as WriterEvent;
+use yaserde::ser::{Config, Serializer};
+use yaserde::YaSerialize;
+
+use crate::req_if::{
+    DataTypes, RelationGroup, SpecObject, SpecRelation, SpecTypes, Specification, TheHeader,
+};
+use crate::xhtml::XHTML_NAMESPACE;
+
+pub const REQIF_NAMESPACE: &str = "http://www.omg.org/spec/ReqIF/20110401/reqif.xsd";
+
+/// Wraps a writer and keeps its first error, which yaserde only reports as
+/// a message.
+pub(crate) struct IoErrorKeeper<W: Write> {
+    inner: W,
+    error: Rc<RefCell<Option<io::Error>>>,
+}
+
+impl<W: Write> IoErrorKeeper<W> {
+    pub(crate) fn new(inner: W) -> Self {
+        IoErrorKeeper {
+            inner,
+            error: Rc::new(RefCell::new(None)),
+        }
+    }
+
+    /// Shared handle on the kept error, usable once the keeper is moved.
+    pub(crate) fn error(&self) -> Rc<RefCell<Option<io::Error>>> {
+        self.error.clone()
+    }
+
+    fn keep(&mut self, error: io::Error) -> io::Error {
+        let copy = io::Error::new(error.kind(), error.to_string());
+        self.error.borrow_mut().get_or_insert(error);
+        copy
+    }
+}
+
+impl<W: Write> Write for IoErrorKeeper<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.inner.write(buf).map_err(|e| self.keep(e))
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush().map_err(|e| self.keep(e))
+    }
+}
+
+/// Returns the kept I/O error if there is one, `message` otherwise.
+pub(crate) fn take_error(error: &RefCell<Option<io::Error>>, message: String) -> anyhow::Error {
+    match error.borrow_mut().take() {
+        Some(error) => error.into(),
+        None => anyhow!(message),
+    }
+}
+
+/// Sections of REQ-IF-CONTENT written one element at a time, in document order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Section {
+    SpecObjects,
+    SpecRelations,
+    Specifications,
+    RelationGroups,
+}
+
+impl Section {
+    fn element(self) -> &'static str {
+        match self {
+            Section::SpecObjects => "SPEC-OBJECTS",
+            Section::SpecRelations => "SPEC-RELATIONS",
+            Section::Specifications => "SPECIFICATIONS",
+            Section::RelationGroups => "SPEC-RELATION-GROUPS",
+        }
+    }
+}
+
+/// Writes a ReqIF document incrementally, without holding it in memory.
+///
+/// The header and type sections are written by [`ReqIfWriter::new`]. Spec
+/// objects, relations, specifications and relation groups then follow one at
+/// a time, in that order: each kind must be complete before the next starts.
+/// [`ReqIfWriter::finish`] closes the document.
+pub struct ReqIfWriter<W: Write> {
+    serializer: Serializer<IoErrorKeeper<W>>,
+    error: Rc<RefCell<Option<io::Error>>>,
+    current: Option<Section>,
+}
+
+impl<W: Write> ReqIfWriter<W> {
+    pub fn new(
+        writer: W,
+        header: &TheHeader,
+        data_types: &DataTypes,
+        spec_types: &SpecTypes,
+    ) -> Result<Self> {
+        let writer = IoErrorKeeper::new(writer);
+        let error = writer.error();
+        let config = Config {
+            perform_indent: true,
+            ..Default::default()
+        };
+        let mut req_if_writer = ReqIfWriter {
+            serializer: Serializer::new_from_writer(writer, &config),
+            error,
+            current: None,
+        };
+        req_if_writer.write_event(
+            WriterEvent::start_element("REQ-IF")
+                .default_ns(REQIF_NAMESPACE)
+                .ns("xhtml", XHTML_NAMESPACE),
+        )?;
+        req_if_writer.serialize("THE-HEADER", header)?;
+        req_if_writer.write_event(WriterEvent::start_element("CORE-CONTENT"))?;
+        req_if_writer.write_event(WriterEvent::start_element("REQ-IF-CONTENT"))?;
+        req_if_writer.serialize("DATATYPES", data_types)?;
+        req_if_writer.serialize("SPEC-TYPES", spec_types)?;
+        Ok(req_if_writer)
+    }
+
+    pub fn write_spec_object(&mut self, spec_object: &SpecObject) -> Result<()> {
+        self.enter(Some(Section::SpecObjects))?;
+        self.serialize("SPEC-OBJECT", spec_object)
+    }
+
+    pub fn write_spec_relation(&mut self, spec_relation: &SpecRelation) -> Result<()> {
+        self.enter(Some(Section::SpecRelations))?;
+        self.serialize("SPEC-RELATION", spec_relation)
+    }
+
+    pub fn write_specification(&mut self, specification: &Specification) -> Result<()> {
+        self.enter(Some(Section::Specifications))?;
+        self.serialize("SPECIFICATION", specification)
+    }
+
+    pub fn write_relation_group(&mut self, relation_group: &RelationGroup) -> Result<()> {
+        self.enter(Some(Section::RelationGroups))?;
+        self.serialize("RELATION-GROUP", relation_group)
+    }
+
+    /// Closes the document, flushes and returns the underlying writer.
+    pub fn finish(mut self) -> Result<W> {
+        self.enter(None)?;
+        for _ in 0..3 {
+            self.write_event(WriterEvent::end_element())?;
+        }
+        let mut writer = self.serializer.into_inner();
+        writer
+            .flush()
+            .map_err(|e| take_error(&self.error, e.to_string()))?;
+        Ok(writer.inner)
+    }
+
+    /// Closes the open section and opens `section`, or only closes it when
+    /// `section` is `None`.
+    fn enter(&mut self, section: Option<Section>) -> Result<()> {
+        if let Some(current) = self.current {
+            match section {
+                Some(section) if section == current => return Ok(()),
+                Some(section) if section < current => bail!(
+                    "{} can't be written after {}",
+                    section.element(),
+                    current.element()
+                ),
+                _ => self.write_event(WriterEvent::end_element())?,
+            }
+        }
+        if let Some(section) = section {
+            self.write_event(WriterEvent::start_element(section.element()))?;
+            self.current = Some(section);
+        }
+        Ok(())
+    }
+
+    fn write_event<'a, E: Into<WriterEvent<'a>>>(&mut self, event: E) -> Result<()> {
+        self.serializer
+            .write(event)
+            .map_err(|e| take_error(&self.error, e.to_string()))
+    }
+
+    fn serialize<T: YaSerialize>(&mut self, name: &str, value: &T) -> Result<()> {
+        self.serializer.set_start_event_name(Some(name.to_string()));
+        value
+            .serialize(&mut self.serializer)
+            .map_err(|e| take_error(&self.error, e))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::ReqIfWriter;
+    use crate::clock;
+    use crate::fixture::{self, document};
+    use crate::req_if::{
+        Object, RelationGroup, RelationGroupType, ReqIf, SpecHierarchy, SpecRelation,
+        SpecRelationType,
+    };
+
+    fn build() -> ReqIf {
+        let mut reqif = document("Streaming");
+        let now = clock::now();
+        reqif
+            .add_spec_relation_type(SpecRelationType::new(
+                "SRT-REFINES".to_string(),
+                now,
+                "refines".to_string(),
+            ))
+            .expect("error");
+        reqif
+            .add_relation_group_type(RelationGroupType::new(
+                "RGT-LINKS".to_string(),
+                now,
+                "Links".to_string(),
+            ))
+            .expect("error");
+        let mut specification = reqif
+            .build_module_specification("SPEC-1".to_string(), now, "Module".to_string())
+            .expect("error");
+        for i in 0..3 {
+            let id = format!("REQ-{}", i);
+            fixture::add_requirement(&mut reqif, &id, &format!("Requirement {}.", i));
+            specification
+                .children
+                .add_spec_hierarchy(
+                    SpecHierarchy::new(format!("H-{}", i), now, Object::new(id)),
+                    0,
+                )
+                .expect("error");
+        }
+        reqif.add_specification(specification).expect("error");
+        reqif
+            .add_spec_relation(SpecRelation::new(
+                "REL-1".to_string(),
+                now,
+                String::new(),
+                "SRT-REFINES".to_string(),
+                "REQ-1".to_string(),
+                "REQ-0".to_string(),
+            ))
+            .expect("error");
+        let mut group = RelationGroup::new(
+            "RG-1".to_string(),
+            now,
+            String::new(),
+            "RGT-LINKS".to_string(),
+            "SPEC-1".to_string(),
+            "SPEC-1".to_string(),
+        );
+        group.add_spec_relation("REL-1".to_string());
+        reqif.add_relation_group(group).expect("error");
+        reqif
+    }
+
+    #[test]
+    fn test_streaming_matches_document() {
+        let reqif = build();
+        let content = &reqif.core_content.req_if_content;
+        let mut writer = ReqIfWriter::new(
+            vec![],
+            &reqif.the_header,
+            &content.data_types,
+            &content.spec_types,
+        )
+        .expect("error");
+        for spec_object in content.spec_object.get_spec_objects() {
+            writer.write_spec_object(spec_object).expect("error");
+        }
+        for spec_relation in content.spec_relations.get_spec_relations() {
+            writer.write_spec_relation(spec_relation).expect("error");
+        }
+        for specification in content.specifications.get_specifications() {
+            writer.write_specification(specification).expect("error");
+        }
+        for relation_group in content.spec_relation_groups.get_relation_groups() {
+            writer.write_relation_group(relation_group).expect("error");
+        }
+        let streamed = String::from_utf8(writer.finish().expect("error")).expect("error");
+
+        assert_eq!(streamed, reqif.to_xml_string().expect("error"));
+    }
+
+    #[test]
+    fn test_streaming_sections() {
+        let reqif = build();
+        let content = &reqif.core_content.req_if_content;
+        let spec_object = &content.spec_object.get_spec_objects()[0];
+
+        let mut writer = ReqIfWriter::new(
+            vec![],
+            &reqif.the_header,
+            &content.data_types,
+            &content.spec_types,
+        )
+        .expect("error");
+        writer
+            .write_specification(&content.specifications.get_specifications()[0])
+            .expect("error");
+        assert!(writer.write_spec_object(spec_object).is_err());
+        let streamed = writer.finish().expect("error");
+
+        let read = ReqIf::from_reader(streamed.as_slice()).expect("error");
+        let read_content = &read.core_content.req_if_content;
+        assert!(read_content.spec_object.get_spec_objects().is_empty());
+        assert_eq!(read_content.specifications.get_specifications().len(), 1);
+    }
+}
@@ -0,0 +1,551 @@
+// reqif-rs: Help library to write reqif files implemented in Rust.
+// Copyright (C) <2024>  INVAP S.E.
+//
+// This file is part of reqif-rs.
+//
+// reqif-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use anyhow::{bail, Result};
+use std::io::{Read, Write};
+use xml::reader::{EventReader, XmlEvent as ReaderEvent};
+use xml::writer::XmlEvent as WriterEvent;
+use yaserde::de::Deserializer;
+use yaserde::ser::Serializer;
+use yaserde::{YaDeserialize, YaSerialize};
+
+pub const XHTML_NAMESPACE: &str = "http://www.w3.org/1999/xhtml";
+
+/// Elements rendered on their own line by [`XHtmlValue::to_plain_text`].
+const BLOCK_ELEMENTS: [&str; 16] = [
+    "address",
+    "blockquote",
+    "div",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "li",
+    "ol",
+    "p",
+    "pre",
+    "table",
+    "tr",
+    "ul",
+];
+
+/// Elements other than [`BLOCK_ELEMENTS`] around which indentation is
+/// dropped on import.
+const STRUCTURE_ELEMENTS: [&str; 9] = [
+    "caption", "dd", "dl", "dt", "tbody", "td", "tfoot", "th", "thead",
+];
+
+/// A node of XHTML content: an element or a run of text.
+#[derive(Debug, Clone, PartialEq)]
+pub enum XHtmlNode {
+    Element(XHtmlElement),
+    Text(String),
+}
+
+impl From<XHtmlElement> for XHtmlNode {
+    fn from(element: XHtmlElement) -> Self {
+        XHtmlNode::Element(element)
+    }
+}
+
+impl From<&str> for XHtmlNode {
+    fn from(text: &str) -> Self {
+        XHtmlNode::Text(text.to_string())
+    }
+}
+
+impl From<String> for XHtmlNode {
+    fn from(text: String) -> Self {
+        XHtmlNode::Text(text)
+    }
+}
+
+/// An XHTML element such as `p`, `b` or `table`, named without prefix.
+#[derive(Debug, Clone, PartialEq)]
+pub struct XHtmlElement {
+    pub name: String,
+    pub attributes: Vec<(String, String)>,
+    pub children: Vec<XHtmlNode>,
+}
+
+impl XHtmlElement {
+    pub fn new(name: &str) -> Self {
+        XHtmlElement {
+            name: name.to_string(),
+            attributes: vec![],
+            children: vec![],
+        }
+    }
+
+    pub fn with_attribute(mut self, name: &str, value: &str) -> Self {
+        self.attributes.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    pub fn with_child(mut self, child: impl Into<XHtmlNode>) -> Self {
+        self.children.push(child.into());
+        self
+    }
+}
+
+/// Content of a `THE-VALUE` or `THE-ORIGINAL-VALUE` element.
+///
+/// The nodes are written below a single `xhtml:div`. On import a lone
+/// enclosing `div` is unwrapped again, and whitespace-only text containing a
+/// line break between block elements is treated as indentation and dropped,
+/// except inside `pre`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct XHtmlValue {
+    nodes: Vec<XHtmlNode>,
+}
+
+impl XHtmlValue {
+    /// Creates a value holding plain text, escaped when written.
+    pub fn new(text: String) -> Self {
+        if text.is_empty() {
+            return XHtmlValue { nodes: vec![] };
+        }
+        XHtmlValue {
+            nodes: vec![XHtmlNode::Text(text)],
+        }
+    }
+
+    pub fn from_nodes(nodes: Vec<XHtmlNode>) -> Self {
+        XHtmlValue { nodes }
+    }
+
+    /// Parses a well-formed XHTML fragment such as `<p>A <b>bold</b> word</p>`.
+    ///
+    /// Elements may be unprefixed or use the `xhtml` prefix; elements from
+    /// any other namespace are rejected.
+    pub fn parse(fragment: &str) -> Result<Self> {
+        let document = format!(
+            r#"<div xmlns="{0}" xmlns:xhtml="{0}">{1}</div>"#,
+            XHTML_NAMESPACE, fragment
+        );
+        let mut builder = NodeBuilder::new();
+        for event in EventReader::new(document.as_bytes()) {
+            match event? {
+                ReaderEvent::StartElement {
+                    name, attributes, ..
+                } => {
+                    if name.namespace.as_deref() != Some(XHTML_NAMESPACE) {
+                        bail!("Element {} is not in the XHTML namespace", name);
+                    }
+                    builder.start(
+                        name.local_name,
+                        attributes
+                            .into_iter()
+                            .map(|a| (a.name.local_name, a.value))
+                            .collect(),
+                    );
+                }
+                ReaderEvent::EndElement { .. } => builder.end(),
+                ReaderEvent::Characters(text)
+                | ReaderEvent::CData(text)
+                | ReaderEvent::Whitespace(text) => builder.text(text),
+                _ => {}
+            }
+        }
+        // First the div added above, then the one of the fragment, if any.
+        Ok(XHtmlValue {
+            nodes: unwrap_div(unwrap_div(builder.finish())),
+        })
+    }
+
+    pub fn nodes(&self) -> &Vec<XHtmlNode> {
+        &self.nodes
+    }
+
+    /// Returns the text of the value, with block elements on separate lines.
+    pub fn to_plain_text(&self) -> String {
+        let mut text = String::new();
+        collect_text(&self.nodes, &mut text);
+        text.trim().to_string()
+    }
+}
+
+fn collect_text(nodes: &[XHtmlNode], text: &mut String) {
+    for node in nodes {
+        match node {
+            XHtmlNode::Text(value) => text.push_str(value),
+            XHtmlNode::Element(element) if element.name == "br" => text.push('\n'),
+            XHtmlNode::Element(element) => {
+                let block = BLOCK_ELEMENTS.contains(&element.name.as_str());
+                if block && !text.is_empty() && !text.ends_with('\n') {
+                    text.push('\n');
+                }
+                collect_text(&element.children, text);
+                if block && !text.ends_with('\n') {
+                    text.push('\n');
+                }
+            }
+        }
+    }
+}
+
+/// Builds a node tree from a flat sequence of XML events.
+struct NodeBuilder {
+    stack: Vec<XHtmlElement>,
+}
+
+impl NodeBuilder {
+    fn new() -> Self {
+        NodeBuilder {
+            stack: vec![XHtmlElement::new("")],
+        }
+    }
+
+    fn start(&mut self, name: String, attributes: Vec<(String, String)>) {
+        if is_structure(&name) {
+            self.drop_indentation();
+        }
+        self.stack.push(XHtmlElement {
+            name,
+            attributes,
+            children: vec![],
+        });
+    }
+
+    fn end(&mut self) {
+        if self.stack.len() > 1 {
+            if self.in_structure() {
+                self.drop_indentation();
+            }
+            let element = self.stack.pop().expect("stack is not empty");
+            self.push(XHtmlNode::Element(element));
+        }
+    }
+
+    fn text(&mut self, text: String) {
+        if let Some(XHtmlNode::Text(previous)) = self.current().children.last_mut() {
+            previous.push_str(&text);
+            return;
+        }
+        self.push(XHtmlNode::Text(text));
+    }
+
+    /// Whether the current element is the top or a block element.
+    fn in_structure(&mut self) -> bool {
+        let name = &self.current().name;
+        name.is_empty() || is_structure(name)
+    }
+
+    /// Drops indentation ending the current element's children, before a
+    /// block element or the end of the current one, when it follows a block
+    /// element or the start of the current one.
+    fn drop_indentation(&mut self) {
+        if self.stack.iter().any(|element| element.name == "pre") {
+            return;
+        }
+        let in_structure = self.in_structure();
+        let children = &mut self.current().children;
+        let indentation = match children.last() {
+            Some(XHtmlNode::Text(text)) => text.trim().is_empty() && text.contains('\n'),
+            _ => false,
+        };
+        let after_block = match children.len().checked_sub(2).map(|index| &children[index]) {
+            Some(XHtmlNode::Element(element)) => is_structure(&element.name),
+            Some(XHtmlNode::Text(_)) => false,
+            None => in_structure,
+        };
+        if indentation && after_block {
+            children.pop();
+        }
+    }
+
+    fn push(&mut self, node: XHtmlNode) {
+        self.current().children.push(node);
+    }
+
+    fn current(&mut self) -> &mut XHtmlElement {
+        self.stack.last_mut().expect("stack is not empty")
+    }
+
+    /// Returns the top level nodes.
+    fn finish(mut self) -> Vec<XHtmlNode> {
+        while self.stack.len() > 1 {
+            self.end();
+        }
+        self.drop_indentation();
+        self.stack.pop().expect("stack is not empty").children
+    }
+}
+
+fn is_structure(name: &str) -> bool {
+    BLOCK_ELEMENTS.contains(&name) || STRUCTURE_ELEMENTS.contains(&name)
+}
+
+/// Replaces a single enclosing `div` without attributes by its children.
+fn unwrap_div(mut nodes: Vec<XHtmlNode>) -> Vec<XHtmlNode> {
+    if let [XHtmlNode::Element(element)] = nodes.as_mut_slice() {
+        if element.name == "div" && element.attributes.is_empty() {
+            return std::mem::take(&mut element.children);
+        }
+    }
+    nodes
+}
+
+/// Writes the nodes as they are. The writer indents markup unless text was
+/// written just before, so an empty text precedes every tag of the value.
+fn write_nodes<W: Write>(nodes: &[XHtmlNode], writer: &mut Serializer<W>) -> Result<(), String> {
+    let unindented = |writer: &mut Serializer<W>| {
+        writer
+            .write(WriterEvent::characters(""))
+            .map_err(|e| e.to_string())
+    };
+    for node in nodes {
+        match node {
+            XHtmlNode::Text(text) => writer
+                .write(WriterEvent::characters(text))
+                .map_err(|e| e.to_string())?,
+            XHtmlNode::Element(element) => {
+                unindented(writer)?;
+                let name = format!("xhtml:{}", element.name);
+                let mut event = WriterEvent::start_element(name.as_str());
+                for (attribute, value) in &element.attributes {
+                    event = event.attr(attribute.as_str(), value);
+                }
+                writer.write(event).map_err(|e| e.to_string())?;
+                write_nodes(&element.children, writer)?;
+                writer
+                    .write(WriterEvent::end_element())
+                    .map_err(|e| e.to_string())?;
+            }
+        }
+    }
+    if !nodes.is_empty() {
+        unindented(writer)?;
+    }
+    Ok(())
+}
+
+impl YaSerialize for XHtmlValue {
+    fn serialize<W: Write>(&self, writer: &mut Serializer<W>) -> Result<(), String> {
+        let name = writer
+            .get_start_event_name()
+            .unwrap_or_else(|| "THE-VALUE".to_string());
+        writer
+            .write(WriterEvent::start_element(name.as_str()))
+            .map_err(|e| e.to_string())?;
+        writer
+            .write(WriterEvent::start_element("xhtml:div"))
+            .map_err(|e| e.to_string())?;
+        write_nodes(&self.nodes, writer)?;
+        writer
+            .write(WriterEvent::end_element())
+            .map_err(|e| e.to_string())?;
+        writer
+            .write(WriterEvent::end_element())
+            .map_err(|e| e.to_string())
+    }
+
+    fn serialize_attributes(
+        &self,
+        attributes: Vec<xml::attribute::OwnedAttribute>,
+        namespace: xml::namespace::Namespace,
+    ) -> Result<
+        (
+            Vec<xml::attribute::OwnedAttribute>,
+            xml::namespace::Namespace,
+        ),
+        String,
+    > {
+        Ok((attributes, namespace))
+    }
+}
+
+impl YaDeserialize for XHtmlValue {
+    fn deserialize<R: Read>(reader: &mut Deserializer<R>) -> Result<Self, String> {
+        let start_name = match reader.next_event()? {
+            ReaderEvent::StartElement { name, .. } => name,
+            event => return Err(format!("Expected THE-VALUE start, found {:?}", event)),
+        };
+        let depth = reader.depth();
+        let mut builder = NodeBuilder::new();
+
+        // The closing tag is left for the caller to consume.
+        loop {
+            let at_start_depth = reader.depth() == depth;
+            match reader.peek()? {
+                ReaderEvent::EndElement { .. } if at_start_depth => break,
+                ReaderEvent::EndDocument => {
+                    return Err(format!("Unclosed element {}", start_name.local_name))
+                }
+                _ => {}
+            }
+            match reader.next_event()? {
+                ReaderEvent::StartElement {
+                    name, attributes, ..
+                } => builder.start(
+                    name.local_name,
+                    attributes
+                        .into_iter()
+                        .map(|a| (a.name.local_name, a.value))
+                        .collect(),
+                ),
+                ReaderEvent::EndElement { .. } => builder.end(),
+                ReaderEvent::Characters(text)
+                | ReaderEvent::CData(text)
+                | ReaderEvent::Whitespace(text) => builder.text(text),
+                _ => {}
+            }
+        }
+        Ok(XHtmlValue {
+            nodes: unwrap_div(builder.finish()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{XHtmlElement, XHtmlNode, XHtmlValue};
+    use crate::clock;
+    use crate::fixture::document;
+    use crate::req_if::{AttributeValueXHtml, ReqIf, SpecObject};
+
+    #[test]
+    fn test_parse_fragment() {
+        let value = XHtmlValue::parse(
+            "<p>The <b>system</b> shall:</p>\n<ul>\n  <li>start</li>\n  <li>stop</li>\n</ul>",
+        )
+        .expect("error");
+        let expected = XHtmlValue::from_nodes(vec![
+            XHtmlElement::new("p")
+                .with_child("The ")
+                .with_child(XHtmlElement::new("b").with_child("system"))
+                .with_child(" shall:")
+                .into(),
+            XHtmlElement::new("ul")
+                .with_child(XHtmlElement::new("li").with_child("start"))
+                .with_child(XHtmlElement::new("li").with_child("stop"))
+                .into(),
+        ]);
+        assert_eq!(value, expected);
+        assert_eq!(value.to_plain_text(), "The system shall:\nstart\nstop");
+
+        let prefixed =
+            XHtmlValue::parse(r#"<xhtml:div><xhtml:p class="x">A</xhtml:p></xhtml:div>"#)
+                .expect("error");
+        assert_eq!(
+            prefixed.nodes(),
+            &vec![XHtmlNode::Element(
+                XHtmlElement::new("p")
+                    .with_attribute("class", "x")
+                    .with_child("A")
+            )]
+        );
+
+        assert!(XHtmlValue::parse("<p>unclosed").is_err());
+        assert!(XHtmlValue::parse(r#"<svg xmlns="http://www.w3.org/2000/svg"/>"#).is_err());
+    }
+
+    #[test]
+    fn test_significant_whitespace() {
+        let inline = XHtmlValue::parse("<p><b>a</b>\n<i>b</i></p>").expect("error");
+        assert_eq!(
+            inline.nodes(),
+            &vec![XHtmlNode::Element(
+                XHtmlElement::new("p")
+                    .with_child(XHtmlElement::new("b").with_child("a"))
+                    .with_child("\n")
+                    .with_child(XHtmlElement::new("i").with_child("b"))
+            )]
+        );
+        assert_eq!(inline.to_plain_text(), "a\nb");
+
+        let pre = XHtmlValue::parse("<div>\n  <pre>\n  <b>a</b>\n  <i>b</i>\n</pre>\n</div>")
+            .expect("error");
+        assert_eq!(
+            pre.nodes(),
+            &vec![XHtmlNode::Element(
+                XHtmlElement::new("pre")
+                    .with_child("\n  ")
+                    .with_child(XHtmlElement::new("b").with_child("a"))
+                    .with_child("\n  ")
+                    .with_child(XHtmlElement::new("i").with_child("b"))
+                    .with_child("\n")
+            )]
+        );
+
+        let mut reqif = document("XHTML");
+        let trailing = XHtmlValue::parse("A <b>bold</b>").expect("error");
+        for (identifier, value) in [("REQ-1", &inline), ("REQ-2", &pre), ("REQ-3", &trailing)] {
+            let mut requirement = SpecObject::requirement(
+                identifier.to_string(),
+                clock::now(),
+                identifier.to_string(),
+                String::new(),
+                &reqif.core_content.req_if_content.spec_types,
+            )
+            .expect("error");
+            let text = &mut requirement.values.xhtml_values[1];
+            *text =
+                AttributeValueXHtml::from_xhtml(text.definition.reference.clone(), value.clone());
+            reqif.add_requirement(requirement).expect("error");
+        }
+        let xml = reqif.to_xml_string().expect("error");
+        let read = ReqIf::from_reader(xml.as_bytes()).expect("error");
+        assert_eq!(read, reqif);
+    }
+
+    #[test]
+    fn test_write_markup() {
+        let mut reqif = document("XHTML");
+        let now = clock::now();
+        let mut requirement = SpecObject::requirement(
+            "REQ-1".to_string(),
+            now,
+            "First".to_string(),
+            String::new(),
+            &reqif.core_content.req_if_content.spec_types,
+        )
+        .expect("error");
+        let text = &mut requirement.values.xhtml_values[1];
+        *text = AttributeValueXHtml::from_xhtml(
+            text.definition.reference.clone(),
+            XHtmlValue::parse("<p>The <b>system</b> shall <i>work</i> &amp; log.</p>")
+                .expect("error"),
+        );
+        let mut other = SpecObject::requirement(
+            "REQ-2".to_string(),
+            now,
+            "Second".to_string(),
+            String::new(),
+            &reqif.core_content.req_if_content.spec_types,
+        )
+        .expect("error");
+        let text = &mut other.values.xhtml_values[1];
+        *text = AttributeValueXHtml::with_plain_text_fallback(
+            text.definition.reference.clone(),
+            XHtmlValue::parse("<table><tr><td>a</td></tr></table>").expect("error"),
+        );
+        reqif.add_requirement(requirement).expect("error");
+        reqif.add_requirement(other).expect("error");
+
+        let xml = reqif.to_xml_string().expect("error");
+        assert!(xml.contains("<xhtml:p>The <xhtml:b>system</xhtml:b> shall <xhtml:i>work</xhtml:i> &amp; log.</xhtml:p>"));
+        assert!(xml.contains(r#"IS-SIMPLIFIED="true""#));
+        assert!(!xml.contains("&lt;"));
+
+        let read = ReqIf::from_reader(xml.as_bytes()).expect("error");
+        assert_eq!(read, reqif);
+    }
+}